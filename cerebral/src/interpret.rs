@@ -2,14 +2,20 @@
 //! An interpreter for clog
 
 use std::{
+    cmp::Ordering,
+    convert::TryFrom,
     fmt,
+    iter,
     rc::Rc,
 };
 
 use clog::{
+    ast::Span,
+    bigint::BigInt,
+    diagnostic::Diagnostic,
     dtree::DTree,
-    imper_ast::{ConstraintValue, Expr, Module, ValPath},
-    types::{BinOpcode, Literal, UnOpcode},
+    imper_ast::{ConstraintValue, Expr, ExprKind, Module, ValPath},
+    types::{BinOpcode, Literal, UnOpcode, LIST},
 };
 
 use crate::stdlib::std_call;
@@ -22,13 +28,16 @@ mod test {
 
     macro_rules! expr {
         (Int($e:expr)) => {
-            Expr::Literal(Literal::Int($e))
+            Expr::new(ExprKind::Literal(Literal::Int($e)), Span::default())
         };
         (+ ($($e1:tt)+), ($($e2:tt)+)) => {
-            Expr::BinOp(box(expr!($($e1)+)), BinOpcode::Add, box(expr!($($e2)+)))
+            Expr::new(
+                ExprKind::BinOp(Box::new(expr!($($e1)+)), BinOpcode::Add, Box::new(expr!($($e2)+))),
+                Span::default(),
+            )
         };
         (- ($($e:tt)+)) => {
-            Expr::UnOp(UnOpcode::Minus, box(expr!($($e)+)))
+            Expr::new(ExprKind::UnOp(UnOpcode::Minus, Box::new(expr!($($e)+))), Span::default())
         };
     }
     #[test]
@@ -44,21 +53,27 @@ mod test {
         let prgrm = "let add = {m => {n => m + n}}";
         let parsed = parse(prgrm).unwrap();
         let compiled = ast2imper_ast(parsed).unwrap();
-        let ctx = Context::new(&compiled);
+        let mut ctx = Context::new(&compiled);
+        ctx.eval_toplevel().unwrap();
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Debug)]
 pub enum Value {
     Unit,
     Int(isize),
+    Float(f64),
+    BigInt(BigInt),
     Bool(bool),
+    Char(char),
     String(String),
     // value of sum tag, not directly accessible
     Tag(u16),
     /// nth type's mth constructor applied to value
     SumVar(u16, u16, Rc<Value>),
     Tuple(Vec<Rc<Value>>),
+    /// nth type's fields in their declared order
+    Record(u16, Vec<Rc<Value>>),
     /// nth function from context, caputuring list of values and
     /// currying partially applied with second list of values
     Closure(u16, Vec<Rc<Value>>, Vec<Rc<Value>>),
@@ -66,11 +81,46 @@ pub enum Value {
     Imported(&'static str),
 }
 
+/// A runtime error and the span of the expression that raised it
 #[derive(Debug)]
 pub enum IntrpErr {
-    TypeMismatch,
-    InvalidPath,
-    NonExhaustivePattern,
+    TypeMismatch(Span),
+    InvalidPath(Span),
+    NonExhaustivePattern(Span),
+    /// the result of int arithmetic doesn't fit in an int
+    Overflow(Span),
+    DivisionByZero(Span),
+    /// an index or a slice of a string past its end or negative
+    IndexOutOfBounds(Span),
+    /// an int that isn't the code point of a character
+    InvalidChar(Span),
+}
+
+impl IntrpErr {
+    pub fn span(&self) -> Span {
+        match *self {
+            IntrpErr::TypeMismatch(span)
+            | IntrpErr::InvalidPath(span)
+            | IntrpErr::NonExhaustivePattern(span)
+            | IntrpErr::Overflow(span)
+            | IntrpErr::DivisionByZero(span)
+            | IntrpErr::IndexOutOfBounds(span)
+            | IntrpErr::InvalidChar(span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let (code, message) = match self {
+            IntrpErr::TypeMismatch(_) => ("E0200", "runtime type mismatch"),
+            IntrpErr::InvalidPath(_) => ("E0201", "invalid value path"),
+            IntrpErr::NonExhaustivePattern(_) => ("E0202", "no pattern matched the arguments"),
+            IntrpErr::Overflow(_) => ("E0203", "integer overflow"),
+            IntrpErr::DivisionByZero(_) => ("E0204", "division by zero"),
+            IntrpErr::IndexOutOfBounds(_) => ("E0205", "string index out of bounds"),
+            IntrpErr::InvalidChar(_) => ("E0206", "invalid character code point"),
+        };
+        Diagnostic::error(code, message.to_owned(), self.span())
+    }
 }
 
 pub struct Context<'a, 'input> {
@@ -90,48 +140,89 @@ impl<'a, 'input> Context<'a, 'input> {
         }
     }
 
-    pub fn eval_toplevel(&mut self) {
+    pub fn eval_toplevel(&mut self) -> Result<(), IntrpErr> {
+        // local slot 0 of a top-level binding is for its dictionaries, the overloaded
+        // ones are closures taking them
+        self.locals = vec![Rc::new(Value::Unit)];
         for (e, _, _) in &self.module.globals {
-            let value = self.eval_exp(e).unwrap();
+            let value = self.eval_exp(e)?;
             // println!("{}", value.display(self.module));
             self.statics.push(value);
             // check satisfaction of constraints and print?
         }
+        Ok(())
     }
 
-    fn resolve(&self, path: &ValPath) -> Result<Rc<Value>, IntrpErr> {
-        match path {
+    /// value of a top-level name, after eval_toplevel
+    pub fn global(&self, name: &str) -> Option<Rc<Value>> {
+        match self.module.globals_names.get(name)? {
+            ValPath::StaticVal(v) => pathvec_from_valvec(v, &self.statics),
+            _ => None,
+        }
+    }
+
+    fn resolve(&self, path: &ValPath, span: Span) -> Result<Rc<Value>, IntrpErr> {
+        let value = match path {
             ValPath::Local(ref v) => pathvec_from_valvec(v, &self.locals),
             ValPath::StaticVal(ref v) => pathvec_from_valvec(v, &self.statics),
             ValPath::CaptureLocal(i, _) => pathvec_from_valvec(&[*i], &self.captures),
             ValPath::CaptureCaptured(i, _) => pathvec_from_valvec(&[*i], &self.captures),
-            ValPath::Constructor(i, j) => Ok(Rc::new(Value::Constructor(*i, *j))),
-            ValPath::Imported(s) => Ok(Rc::new(Value::Imported(s))),
-        }
+            ValPath::Constructor(i, j) => Some(Rc::new(Value::Constructor(*i, *j))),
+            ValPath::Imported(s) => Some(Rc::new(Value::Imported(s))),
+            // replaced by the methods of instances in a checked module
+            ValPath::TraitMethod(..) => None,
+        };
+        value.ok_or(IntrpErr::InvalidPath(span))
     }
 
     pub fn eval_exp(&self, expr: &Expr) -> Result<Rc<Value>, IntrpErr> {
         //println!["{:?}", &expr];
-        match expr {
-            &Expr::Literal(Literal::Unit) => Ok(Rc::new(Value::Unit)),
-            &Expr::Literal(Literal::Int(n)) => Ok(Rc::new(Value::Int(n))),
-            &Expr::Literal(Literal::Bool(p)) => Ok(Rc::new(Value::Bool(p))),
-            &Expr::Literal(Literal::String(s)) => Ok(Rc::new(Value::String(s.to_owned()))),
-            &Expr::Bound(ref path) => self.resolve(path),
-            &Expr::Slice(ref e1, ref e2, ref e3) => {
+        let span = expr.span;
+        match expr.kind {
+            ExprKind::Literal(Literal::Unit) => Ok(Rc::new(Value::Unit)),
+            ExprKind::Literal(Literal::Int(n)) => Ok(Rc::new(Value::Int(n))),
+            ExprKind::Literal(Literal::Float(x)) => Ok(Rc::new(Value::Float(x))),
+            ExprKind::Literal(Literal::BigInt(ref n)) => Ok(Rc::new(Value::BigInt(n.clone()))),
+            ExprKind::Literal(Literal::Bool(p)) => Ok(Rc::new(Value::Bool(p))),
+            ExprKind::Literal(Literal::Char(c)) => Ok(Rc::new(Value::Char(c))),
+            ExprKind::Literal(Literal::String(ref s)) => Ok(Rc::new(Value::String(s.to_string()))),
+            ExprKind::Bound(ref path) => self.resolve(path, span),
+            ExprKind::Slice(ref e1, ref e2, ref e3) => {
                 match (&*self.eval_exp(e1)?, &*self.eval_exp(e2)?, &*self.eval_exp(e3)?) {
-                    (Value::String(s), Value::Int(a), Value::Int(b)) => {
-                        Ok(Rc::new(Value::String(s.chars().skip(*a as usize).take((b-a) as usize).collect())))
+                    (Value::String(s), &Value::Int(a), &Value::Int(b)) => {
+                        match (byte_offset(s, a), byte_offset(s, b)) {
+                            (Some(i), Some(j)) if i <= j => Ok(Rc::new(Value::String(s[i..j].to_owned()))),
+                            _ => Err(IntrpErr::IndexOutOfBounds(span)),
+                        }
                     },
-                    _ => panic!("Runtime Type Error")
+                    _ => Err(IntrpErr::TypeMismatch(span)),
                 }
             }
-            &Expr::UnOp(op, ref e) => self.eval_unop(op, e),
-            &Expr::BinOp(ref e1, op, ref e2) => self.eval_binop(e1, op, e2),
-            &Expr::Closure(n) => Ok(Rc::new(Value::Closure(n, self.gen_captures(n)?, vec![]))),
-            &Expr::Tuple(ref v) => self.eval_tuple(v),
-            &Expr::Application(ref e1, ref e2) => self.eval_appl(e1, e2),
-            &Expr::SumVal {
+            ExprKind::UnOp(op, ref e) => self.eval_unop(op, e, span),
+            ExprKind::BinOp(ref e1, op, ref e2) => self.eval_binop(e1, op, e2, span),
+            ExprKind::Closure(n) => Ok(Rc::new(Value::Closure(n, self.gen_captures(n, span)?, vec![]))),
+            ExprKind::Tuple(ref v) => self.eval_tuple(v),
+            ExprKind::Record(target, ref v) => Ok(Rc::new(Value::Record(
+                target,
+                v.iter().map(|e| self.eval_exp(e)).collect::<Result<Vec<_>, _>>()?,
+            ))),
+            ExprKind::Field(ref e, n) => match *self.eval_exp(e)? {
+                Value::Record(_, ref v) | Value::Tuple(ref v) => Ok(v[n as usize].clone()),
+                _ => Err(IntrpErr::TypeMismatch(span)),
+            },
+            ExprKind::Update(ref e, ref updates) => match *self.eval_exp(e)? {
+                Value::Record(target, ref v) => {
+                    let mut v = v.clone();
+                    for (n, e) in updates {
+                        v[*n as usize] = self.eval_exp(e)?;
+                    }
+                    Ok(Rc::new(Value::Record(target, v)))
+                }
+                _ => Err(IntrpErr::TypeMismatch(span)),
+            },
+            ExprKind::List(ref v, ref tl) => self.eval_list(v, tl.as_deref()),
+            ExprKind::Application(ref e1, ref e2) => self.eval_appl(e1, e2, span),
+            ExprKind::SumVal {
                 target,
                 position,
                 ref value,
@@ -140,87 +231,115 @@ impl<'a, 'input> Context<'a, 'input> {
                 position,
                 self.eval_exp(value)?,
             ))),
-            &Expr::Conditional(ref cond, ref e1, ref e2) => match *self.eval_exp(cond)? {
+            ExprKind::Conditional(ref cond, ref e1, ref e2) => match *self.eval_exp(cond)? {
                 Value::Bool(true) => self.eval_exp(e1),
                 Value::Bool(false) => self.eval_exp(e2),
-                _ => Err(IntrpErr::TypeMismatch),
+                _ => Err(IntrpErr::TypeMismatch(cond.span)),
             },
-            &Expr::Error => panic!("Error"),
+            ExprKind::Let(ref e1, ref dtree, ref e2) => {
+                let mut locals = self.locals.clone();
+                locals.push(self.eval_exp(e1)?);
+                let ctx = Context {
+                    module: self.module,
+                    statics: self.statics.clone(),
+                    captures: self.captures.clone(),
+                    locals,
+                };
+                ctx.match_tree(dtree, &[], e1.span)?;
+                ctx.eval_exp(e2)
+            }
+            ExprKind::Overloaded(_) => Err(IntrpErr::InvalidPath(span)),
+            ExprKind::Error => panic!("Error"),
         }
     }
 
-    fn eval_unop(&self, op: UnOpcode, e: &Expr) -> Result<Rc<Value>, IntrpErr> {
+    fn eval_unop(&self, op: UnOpcode, e: &Expr, span: Span) -> Result<Rc<Value>, IntrpErr> {
         match (op, &*self.eval_exp(e)?) {
             (UnOpcode::Not, &Value::Bool(p)) => Ok(Rc::new(Value::Bool(!p))),
-            (UnOpcode::Minus, &Value::Int(n)) => Ok(Rc::new(Value::Int(-n))),
-            _ => Err(IntrpErr::TypeMismatch),
+            (UnOpcode::Minus, &Value::Int(n)) => Ok(Rc::new(Value::Int(n.checked_neg().ok_or(IntrpErr::Overflow(span))?))),
+            (UnOpcode::Minus, &Value::Float(x)) => Ok(Rc::new(Value::Float(-x))),
+            (UnOpcode::Minus, Value::BigInt(n)) => Ok(Rc::new(Value::BigInt(-n))),
+            (UnOpcode::Show, Value::Int(n)) => Ok(Rc::new(Value::String(n.to_string()))),
+            (UnOpcode::Show, Value::Float(x)) => Ok(Rc::new(Value::String(format!("{:?}", x)))),
+            (UnOpcode::Show, Value::BigInt(n)) => Ok(Rc::new(Value::String(n.to_string()))),
+            (UnOpcode::Show, Value::Bool(p)) => Ok(Rc::new(Value::String(p.to_string()))),
+            (UnOpcode::Show, Value::Unit) => Ok(Rc::new(Value::String("()".to_owned()))),
+            (UnOpcode::Show, Value::Char(c)) => Ok(Rc::new(Value::String(format!("{:?}", c)))),
+            (UnOpcode::Show, Value::String(s)) => Ok(Rc::new(Value::String(format!("{:?}", s)))),
+            _ => Err(IntrpErr::TypeMismatch(span)),
         }
     }
 
 
-    // XXX equality needs complete rewrite
-    // XXX collapse 2-level match to 1
-    fn eval_binop(&self, e1: &Expr, op: BinOpcode, e2: &Expr) -> Result<Rc<Value>, IntrpErr> {
+    fn eval_binop(&self, e1: &Expr, op: BinOpcode, e2: &Expr, span: Span) -> Result<Rc<Value>, IntrpErr> {
         use BinOpcode::*;
-        match (&*self.eval_exp(e1)?, &*self.eval_exp(e2)?) {
-            (Value::String(s), Value::Int(n)) => match op {
-                Index => Ok(Rc::new(Value::Int(s.chars().nth(*n as usize)
-                            .expect(&format!("Index {} out of range for string \"{}\"", n, s)) as isize))),
-                _ => Err(IntrpErr::TypeMismatch)
+        let (v1, v2) = (self.eval_exp(e1)?, self.eval_exp(e2)?);
+        if let Equal | NotEq | Greater | Less | GreaterEq | LessEq = op {
+            // unordered values, i.e. containing a NaN, are only unequal
+            let b = match (op, compare(&v1, &v2)) {
+                (NotEq, None) => true,
+                (_, None) => false,
+                (Equal, Some(ord)) => ord.is_eq(),
+                (NotEq, Some(ord)) => ord.is_ne(),
+                (Greater, Some(ord)) => ord.is_gt(),
+                (Less, Some(ord)) => ord.is_lt(),
+                (GreaterEq, Some(ord)) => ord.is_ge(),
+                (_, Some(ord)) => ord.is_le(),
+            };
+            return Ok(Rc::new(Value::Bool(b)));
+        }
+        match (&*v1, &*v2) {
+            (Value::String(s), &Value::Int(n)) => match op {
+                Index => {
+                    let c = usize::try_from(n).ok().and_then(|n| s.chars().nth(n));
+                    Ok(Rc::new(Value::Char(c.ok_or(IntrpErr::IndexOutOfBounds(span))?)))
+                }
+                _ => Err(IntrpErr::TypeMismatch(span))
+            }
+            (&Value::Int(n), &Value::Int(m)) => {
+                let result = match op {
+                    Add => n.checked_add(m),
+                    Sub => n.checked_sub(m),
+                    Mul => n.checked_mul(m),
+                    Div | Mod if m == 0 => return Err(IntrpErr::DivisionByZero(span)),
+                    Div => n.checked_div(m),
+                    Mod => n.checked_rem(m),
+                    _ => return Err(IntrpErr::TypeMismatch(span)),
+                };
+                Ok(Rc::new(Value::Int(result.ok_or(IntrpErr::Overflow(span))?)))
             }
-            (Value::Int(n), Value::Int(m)) => match op {
-                Add => Ok(Rc::new(Value::Int(n + m))),
-                Sub => Ok(Rc::new(Value::Int(n - m))),
-                Mul => Ok(Rc::new(Value::Int(n * m))),
-                Div => Ok(Rc::new(Value::Int(n / m))),
-                Mod => Ok(Rc::new(Value::Int(n % m))),
-                Equal => Ok(Rc::new(Value::Bool(n == m))),
-                NotEq => Ok(Rc::new(Value::Bool(n != m))),
-                Greater => Ok(Rc::new(Value::Bool(n > m))),
-                GreaterEq => Ok(Rc::new(Value::Bool(n >= m))),
-                Less => Ok(Rc::new(Value::Bool(n < m))),
-                LessEq => Ok(Rc::new(Value::Bool(n <= m))),
-                _ => Err(IntrpErr::TypeMismatch),
+            (Value::BigInt(n), Value::BigInt(m)) => match op {
+                Add => Ok(Rc::new(Value::BigInt(n + m))),
+                Sub => Ok(Rc::new(Value::BigInt(n - m))),
+                Mul => Ok(Rc::new(Value::BigInt(n * m))),
+                Div | Mod => {
+                    let (q, r) = n.div_rem(m).ok_or(IntrpErr::DivisionByZero(span))?;
+                    Ok(Rc::new(Value::BigInt(if op == Div { q } else { r })))
+                }
+                _ => Err(IntrpErr::TypeMismatch(span)),
+            },
+            (Value::Float(x), Value::Float(y)) => match op {
+                Add => Ok(Rc::new(Value::Float(x + y))),
+                Sub => Ok(Rc::new(Value::Float(x - y))),
+                Mul => Ok(Rc::new(Value::Float(x * y))),
+                Div => Ok(Rc::new(Value::Float(x / y))),
+                Mod => Ok(Rc::new(Value::Float(x % y))),
+                _ => Err(IntrpErr::TypeMismatch(span)),
             },
             (&Value::Bool(p), &Value::Bool(q)) => match op {
                 And => Ok(Rc::new(Value::Bool(p && q))),
                 Or => Ok(Rc::new(Value::Bool(p || q))),
-                Equal => Ok(Rc::new(Value::Bool(p == q))),
-                NotEq => Ok(Rc::new(Value::Bool(p != q))),
-                _ => Err(IntrpErr::TypeMismatch),
-            },
-            (Value::Unit, Value::Unit) => match op {
-                Equal => Ok(Rc::new(Value::Bool(true))),
-                NotEq => Ok(Rc::new(Value::Bool(false))),
-                _ => Err(IntrpErr::TypeMismatch),
+                _ => Err(IntrpErr::TypeMismatch(span)),
             },
             (Value::String(s1), Value::String(s2)) => match op {
-                Equal => Ok(Rc::new(Value::Bool(s1 == s2))),
-                NotEq => Ok(Rc::new(Value::Bool(s1 != s2))),
                 Concat => Ok(Rc::new(Value::String(s1.clone()+s2))),
-                _ => Err(IntrpErr::TypeMismatch),
-            },
-
-            // NOTE: All equality operations below need reconsideration
-            (Value::SumVar(..), Value::SumVar(..)) => match op {
-                Equal | BinOpcode::NotEq => panic!("sum type equality"),
-                _ => Err(IntrpErr::TypeMismatch),
-            },
-            (Value::Tuple(v1), Value::Tuple(v2)) => match op {
-                Equal => Ok(Rc::new(Value::Bool(v1 == v2))),
-                NotEq => Ok(Rc::new(Value::Bool(v1 != v2))),
-                _ => Err(IntrpErr::TypeMismatch),
-            },
-            (c1 @ Value::Closure(..), c2 @ Value::Closure(..)) => match op {
-                Equal => Ok(Rc::new(Value::Bool(c1 == c2))),
-                NotEq => Ok(Rc::new(Value::Bool(c1 != c2))),
-                _ => Err(IntrpErr::TypeMismatch),
+                _ => Err(IntrpErr::TypeMismatch(span)),
             },
-            _ => Err(IntrpErr::TypeMismatch),
+            _ => Err(IntrpErr::TypeMismatch(span)),
         }
     }
 
-    fn eval_tuple(&self, v: &Vec<Expr>) -> Result<Rc<Value>, IntrpErr> {
+    fn eval_tuple(&self, v: &[Expr]) -> Result<Rc<Value>, IntrpErr> {
         Ok(Rc::new(Value::Tuple(
             v.iter()
                 .map(|e| self.eval_exp(e))
@@ -228,7 +347,20 @@ impl<'a, 'input> Context<'a, 'input> {
         )))
     }
 
-    fn eval_appl(&self, e1: &Expr, e2: &Expr) -> Result<Rc<Value>, IntrpErr> {
+    /// The list of the values of v followed by tl, built from the end
+    fn eval_list(&self, v: &[Expr], tl: Option<&Expr>) -> Result<Rc<Value>, IntrpErr> {
+        let values = v.iter().map(|e| self.eval_exp(e)).collect::<Result<Vec<_>, _>>()?;
+        let mut list = match tl {
+            Some(tl) => self.eval_exp(tl)?,
+            None => Rc::new(Value::SumVar(LIST, 1, Rc::new(Value::Unit))),
+        };
+        for value in values.into_iter().rev() {
+            list = Rc::new(Value::SumVar(LIST, 2, Rc::new(Value::Tuple(vec![value, list]))));
+        }
+        Ok(list)
+    }
+
+    fn eval_appl(&self, e1: &Expr, e2: &Expr, span: Span) -> Result<Rc<Value>, IntrpErr> {
         match *self.eval_exp(e1)? {
             Value::Closure(n, ref cap, ref cur) => {
                 let mut cur = cur.clone();
@@ -245,9 +377,9 @@ impl<'a, 'input> Context<'a, 'input> {
             }
             Value::Imported(name) => {
                 let e2 = self.eval_exp(e2)?;
-                std_call(name, e2)
+                std_call(name, e2, span)
             }
-            _ => Err(IntrpErr::TypeMismatch),
+            _ => Err(IntrpErr::TypeMismatch(span)),
         }
     }
 
@@ -258,58 +390,198 @@ impl<'a, 'input> Context<'a, 'input> {
         locals: Vec<Rc<Value>>,
     ) -> Result<Rc<Value>, IntrpErr> {
         let func = &self.module.closures[n as usize];
-        let matched_arm = match_tree(&func.dtree, &locals)?;
-        let ctx = Context {
+        let mut ctx = Context {
             module: self.module,
             statics: self.statics.clone(),
             captures,
             locals,
         };
+        let (matched_arm, bound) = ctx.match_tree(&func.dtree, &func.guards, func.span)?;
+        // the names the alternatives of or-patterns bind at different paths
+        ctx.locals.extend(bound);
         ctx.eval_exp(&func.branches[matched_arm as usize])
     }
 
+    /// Find the arm matched by the locals, with the guards of the arms of the
+    /// closure, and the values the matched alternative binds to the next local slots.
+    /// span is the span of the matching closure
+    fn match_tree(
+        &self,
+        tree: &DTree,
+        guards: &[Option<Expr>],
+        span: Span,
+    ) -> Result<(u16, Vec<Rc<Value>>), IntrpErr> {
+        let valvec = &self.locals;
+        match tree {
+            DTree::Empty => Err(IntrpErr::NonExhaustivePattern(span)),
+            &DTree::Exit(m) => Ok((m, vec![])),
+            DTree::Guard { arm, ref otherwise } => {
+                if self.test_guard(guards, *arm, span)? {
+                    Ok((*arm, vec![]))
+                } else {
+                    self.match_tree(otherwise, guards, span)
+                }
+            }
+            DTree::Alternative { ref bound, ref then, .. } => {
+                let bound = bound
+                    .iter()
+                    .map(|path| match path {
+                        ValPath::Local(v) => pathvec_from_valvec(v, valvec).ok_or(IntrpErr::InvalidPath(span)),
+                        _ => Err(IntrpErr::InvalidPath(span)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                match **then {
+                    DTree::Exit(m) => Ok((m, bound)),
+                    // the guard may use the bound values
+                    DTree::Guard { arm, ref otherwise } => {
+                        let mut locals = self.locals.clone();
+                        locals.extend(bound.iter().cloned());
+                        let ctx = Context {
+                            module: self.module,
+                            statics: self.statics.clone(),
+                            captures: self.captures.clone(),
+                            locals,
+                        };
+                        if ctx.test_guard(guards, arm, span)? {
+                            Ok((arm, bound))
+                        } else {
+                            self.match_tree(otherwise, guards, span)
+                        }
+                    }
+                    _ => Err(IntrpErr::InvalidPath(span)),
+                }
+            }
+            DTree::Finite {
+                value: ValPath::Local(ref v),
+                ref branches,
+            } => {
+                let val = pathvec_from_valvec(v, valvec).ok_or(IntrpErr::InvalidPath(span))?;
+                match *val {
+                    Value::Bool(false) => self.match_tree(&branches[0], guards, span),
+                    Value::Bool(true) => self.match_tree(&branches[1], guards, span),
+                    // XXX: double check off by 1.
+                    Value::Tag(n) => self.match_tree(&branches[n as usize], guards, span),
+                    _ => Err(IntrpErr::TypeMismatch(span)),
+                }
+            }
+            DTree::Infinite {
+                value: ValPath::Local(ref v),
+                ref branches,
+                ref default,
+            } => {
+                let val = pathvec_from_valvec(v, valvec).ok_or(IntrpErr::InvalidPath(span))?;
+                // the first test the value passes picks the branch
+                let passes = |test: &ConstraintValue| match *val {
+                    Value::Int(n) => Ok(test.matches_int(n)),
+                    Value::Char(c) => Ok(test.matches_char(c)),
+                    Value::String(ref s) => Ok(test.matches_str(s)),
+                    _ => Err(IntrpErr::TypeMismatch(span)),
+                };
+                let mut branch = &**default;
+                for (test, tree) in branches {
+                    if passes(test)? {
+                        branch = tree;
+                        break;
+                    }
+                }
+                self.match_tree(branch, guards, span)
+            }
+            _ => Err(IntrpErr::InvalidPath(span)),
+        }
+    }
+
+    /// Evaluate the guard of the arm
+    fn test_guard(&self, guards: &[Option<Expr>], arm: u16, span: Span) -> Result<bool, IntrpErr> {
+        let guard = guards[arm as usize].as_ref().ok_or(IntrpErr::InvalidPath(span))?;
+        match *self.eval_exp(guard)? {
+            Value::Bool(b) => Ok(b),
+            _ => Err(IntrpErr::TypeMismatch(guard.span)),
+        }
+    }
+
     /// Generate vector of captured value for nth closure
-    fn gen_captures(&self, n: u16) -> Result<Vec<Rc<Value>>, IntrpErr> {
+    fn gen_captures(&self, n: u16, span: Span) -> Result<Vec<Rc<Value>>, IntrpErr> {
         let closure = &self.module.closures[n as usize];
         let mut captures = vec![None; closure.captures.len()];
 
         for (path, _) in &closure.captures {
-            match path {
-                ValPath::CaptureLocal(i, ref v) => {
-                    captures[*i as usize].replace(pathvec_from_valvec(v, &self.locals)?);
-                }
-                ValPath::CaptureCaptured(i, j) => {
-                    captures[*i as usize].replace(pathvec_from_valvec(&[*j], &self.captures)?);
-                }
-                _ => return Err(IntrpErr::InvalidPath),
-            }
+            let (i, value) = match path {
+                ValPath::CaptureLocal(i, ref v) => (*i, pathvec_from_valvec(v, &self.locals)),
+                ValPath::CaptureCaptured(i, j) => (*i, pathvec_from_valvec(&[*j], &self.captures)),
+                _ => return Err(IntrpErr::InvalidPath(span)),
+            };
+            captures[i as usize] = Some(value.ok_or(IntrpErr::InvalidPath(span))?);
         }
 
         Ok(captures.into_iter().flatten().collect())
     }
 }
 
-pub fn pathvec_from_valvec(path: &[u16], valvec: &Vec<Rc<Value>>) -> Result<Rc<Value>, IntrpErr> {
-    fn pathvec_from_val(path: &[u16], val: &Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
+/// Structural comparison of first-order values. Strings, tuples and records are
+/// ordered lexicographically and variants by the position of their constructor,
+/// then by their arguments. None for unordered values, i.e. floats that are NaN,
+/// and for closures, which the checker never compares
+fn compare(v1: &Value, v2: &Value) -> Option<Ordering> {
+    match (v1, v2) {
+        (Value::Unit, Value::Unit) => Some(Ordering::Equal),
+        (Value::Int(n), Value::Int(m)) => Some(n.cmp(m)),
+        (Value::Float(x), Value::Float(y)) => x.partial_cmp(y),
+        (Value::BigInt(n), Value::BigInt(m)) => Some(n.cmp(m)),
+        (Value::Bool(p), Value::Bool(q)) => Some(p.cmp(q)),
+        (Value::Char(c), Value::Char(d)) => Some(c.cmp(d)),
+        (Value::String(s1), Value::String(s2)) => Some(s1.cmp(s2)),
+        (Value::SumVar(_, i, v), Value::SumVar(_, j, w)) => match i.cmp(j) {
+            Ordering::Equal => compare(v, w),
+            ord => Some(ord),
+        },
+        (Value::Tuple(v1), Value::Tuple(v2)) | (Value::Record(_, v1), Value::Record(_, v2)) => {
+            for (v, w) in v1.iter().zip(v2) {
+                match compare(v, w)? {
+                    Ordering::Equal => (),
+                    ord => return Some(ord),
+                }
+            }
+            Some(v1.len().cmp(&v2.len()))
+        }
+        _ => None,
+    }
+}
+
+/// The offset in s of its nth code point, or of its end if it has n code points.
+/// None if n is negative or past the end
+fn byte_offset(s: &str, n: isize) -> Option<usize> {
+    let n = usize::try_from(n).ok()?;
+    s.char_indices().map(|(i, _)| i).chain(iter::once(s.len())).nth(n)
+}
+
+/// Find the value at path, where the path's head is an index in valvec.
+/// Returns None if the path doesn't exist in the value
+pub fn pathvec_from_valvec(path: &[u16], valvec: &[Rc<Value>]) -> Option<Rc<Value>> {
+    fn pathvec_from_val(path: &[u16], val: &Rc<Value>) -> Option<Rc<Value>> {
         match path {
-            [] => Ok(val.clone()),
+            [] => Some(val.clone()),
             [n, tail @ ..] => match **val {
-                Value::Tuple(ref v) => pathvec_from_val(tail, &v[*n as usize]),
+                Value::Tuple(ref v) | Value::Record(_, ref v) => pathvec_from_val(tail, &v[*n as usize]),
+                // the rest of a string after a prefix of n bytes
+                Value::String(ref s) => {
+                    let rest = Rc::new(Value::String(s.get(*n as usize..)?.to_owned()));
+                    pathvec_from_val(tail, &rest)
+                }
                 Value::SumVar(_ty_idx, con_idx, ref inner_val) => {
                     if *n == con_idx {
                         pathvec_from_val(tail, inner_val)
                     } else if *n == 0 {
-                        Ok(Rc::new(Value::Tag(con_idx - 1)))
+                        Some(Rc::new(Value::Tag(con_idx - 1)))
                     } else {
-                        Err(IntrpErr::InvalidPath)
+                        None
                     }
                 }
-                _ => Err(IntrpErr::InvalidPath),
+                _ => None,
             },
         }
     }
     match path {
-        [] => Err(IntrpErr::InvalidPath),
+        [] => None,
         [n, tail @ ..] => pathvec_from_val(tail, &valvec[*n as usize]),
     }
 }
@@ -321,14 +593,22 @@ impl fmt::Display for Value {
             Value::Bool(true) => write!(f, "true"),
             Value::Bool(false) => write!(f, "false"),
             Value::Int(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{:?}", x),
+            Value::BigInt(n) => write!(f, "{}", n),
+            Value::Char(c) => write!(f, "{}", c),
             Value::String(s) => write!(f, "{}", s),
-            Value::Tuple(v) => v
-                .iter()
-                .map(|x| {
+            Value::Tuple(v) => v.iter().try_for_each(|x| {
+                x.fmt(f)?;
+                write!(f, ", ")
+            }),
+            Value::Record(n, v) => {
+                write!(f, "<record {}>{{", n)?;
+                v.iter().try_for_each(|x| {
                     x.fmt(f)?;
                     write!(f, ", ")
-                })
-                .collect(),
+                })?;
+                write!(f, "}}")
+            }
             Value::Closure(n, ..) => write!(f, "<closure {}>", n),
             Value::SumVar(n, m, val) => write!(f, "<type {}>::<variant {}>{}", n, m, val),
             Value::Tag(n) => write!(f, "<tag {}>", n),
@@ -389,38 +669,3 @@ impl fmt::Display for Value {
 //     }
 // }
 
-pub fn match_tree(tree: &DTree, valvec: &Vec<Rc<Value>>) -> Result<u16, IntrpErr> {
-    match tree {
-        &DTree::Empty => Err(IntrpErr::NonExhaustivePattern),
-        &DTree::Exit(m) => Ok(m),
-        &DTree::Finite {
-            value: ValPath::Local(ref v),
-            ref branches,
-        } => {
-            let val = pathvec_from_valvec(v, valvec)?;
-            match *val {
-                Value::Bool(false) => match_tree(&branches[0], valvec),
-                Value::Bool(true) => match_tree(&branches[1], valvec),
-                // XXX: double check off by 1.
-                Value::Tag(n) => match_tree(&branches[n as usize], valvec),
-                _ => Err(IntrpErr::TypeMismatch),
-            }
-        }
-        &DTree::Infinite {
-            value: ValPath::Local(ref v),
-            ref branches,
-            ref default,
-        } => match *pathvec_from_valvec(v, valvec)? {
-            Value::Int(n) => match_tree(
-                branches.get(&ConstraintValue::Int(n)).unwrap_or(default),
-                valvec,
-            ),
-            Value::String(ref s) => match_tree(
-                branches.get(&ConstraintValue::Str(s)).unwrap_or(default),
-                valvec,
-            ),
-            _ => Err(IntrpErr::TypeMismatch),
-        },
-        _ => Err(IntrpErr::InvalidPath),
-    }
-}
@@ -0,0 +1,20 @@
+//! cerebral, the interpreter and REPL of ceen
+
+use clog::{
+    diagnostic::Diagnostic,
+    imper_ast::Module,
+    parse,
+    type_check::TypingContext,
+};
+
+pub mod interpret;
+pub mod stdlib;
+
+/// Parse and type check a source file with the standard library in scope
+pub fn compile(src: &str) -> Result<Module<'_>, Vec<Diagnostic>> {
+    let bindings = parse::parse(src)?;
+    let mut ctx = TypingContext::new();
+    ctx.add_imports(stdlib::std_imports());
+    ctx.add_bindings(bindings);
+    ctx.export()
+}
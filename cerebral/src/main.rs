@@ -1,40 +1,40 @@
 //#![feature(box_syntax)]
 
 use std::env;
+use std::process;
 use std::fs::File;
 use std::io::{
     prelude::*
 };
 
-use clog::{
-    parse,
-    type_check,
-};
-
-mod stdlib;
-mod interpret;
+use cerebral::{compile, interpret};
 
 fn main() {
     let mut args = env::args();
     args.next();
     let input_file = args.next().expect("No input file given");
-    let mut f = File::open(input_file).expect("File not found");
+    let mut f = File::open(&input_file).expect("File not found");
     let mut contents = String::new();
     f.read_to_string(&mut contents)
         .expect("Cannot read file");
-    let contents = parse::uncomment(&mut contents);
-    let bindings = parse::parse(&contents).unwrap();
-    
-    let mut ctx = type_check::TypingContext::new();
-    
-    ctx.add_imports(stdlib::std_imports());
 
-    for b in bindings {
-        ctx.add_binding(b).expect("Failed to type check");
+    let module = match compile(&contents) {
+        Ok(module) => module,
+        Err(diagnostics) => {
+            for d in &diagnostics {
+                eprintln!("{}", d.render(&contents, &input_file));
+            }
+            process::exit(1);
+        }
+    };
+    for d in &module.warnings {
+        eprintln!("{}", d.render(&contents, &input_file));
     }
-    let module = ctx.export().unwrap();
     let mut ctx = interpret::Context::new(&module);
-    ctx.eval_toplevel();
+    if let Err(e) = ctx.eval_toplevel() {
+        eprintln!("{}", e.to_diagnostic().render(&contents, &input_file));
+        process::exit(1);
+    }
 }
 
 //fn repl() {
@@ -44,8 +44,7 @@ fn main() {
 //        s += line.unwrap().trim_end();
 //        if s.chars().last().unwrap() == ';' {
 //            s.pop();
-//            let contents = parse::uncomment(&mut s);
-//            let result = parse::parse(&contents).unwrap();
+//            let result = parse::parse(&s).unwrap();
 //            // let module = type_check::ast2imper_ast(result).unwrap();
 //            // let mut ctx = interpret::Context::new(&module);
 //            // ctx.eval_toplevel();
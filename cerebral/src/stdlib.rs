@@ -1,57 +1,81 @@
 use std::{
+    convert::TryFrom,
     rc::Rc,
     collections::HashMap,
     io::{stdin, BufRead},
+    sync::OnceLock,
 };
 use clog::{
-    types::Type,
+    ast::Span,
+    bigint::BigInt,
+    types::{Type, TypeScheme},
     imper_ast::ValPath,
 };
 use crate::{
     interpret::{Value, IntrpErr}
 };
 
-static mut STL: Option<HashMap<&'static str, (Type, fn(Rc<Value>) -> Result<Rc<Value>, IntrpErr>)>> = None;
+/// a function of the standard library, given its argument and the span of the call
+type StdFn = fn(Rc<Value>, Span) -> Result<Rc<Value>, IntrpErr>;
 
-pub fn std_imports() -> HashMap<&'static str, (ValPath, Type)> {
-    let mut stlmap = HashMap::new();
-    stlmap.insert("print", 
-        (   Type::Function(Box::new(Type::String), Box::new(Type::Unit)), 
-            cn_print as fn(Rc<Value>) -> Result<Rc<Value>, IntrpErr>));
-    stlmap.insert("i2str",
-        (Type::Function(Box::new(Type::Int), Box::new(Type::String)), cn_i2s));
-    stlmap.insert("readline", 
-        (Type::Function(Box::new(Type::Unit), Box::new(Type::String)), cn_readline));   
-    stlmap.insert("len", 
-        (Type::Function(Box::new(Type::String), Box::new(Type::Int)), cn_len));   
+static STL: OnceLock<HashMap<&'static str, (Type, StdFn)>> = OnceLock::new();
 
-    let mut map = HashMap::new();
-    for (name, (t, _)) in &stlmap {
-        map.insert(*name, (ValPath::Imported(name), t.clone()));
-    }
+fn stl() -> &'static HashMap<&'static str, (Type, StdFn)> {
+    STL.get_or_init(|| {
+        let mut stlmap = HashMap::new();
+        stlmap.insert("print", 
+            (   Type::Function(Box::new(Type::String), Box::new(Type::Unit)), 
+                cn_print as StdFn));
+        stlmap.insert("i2str",
+            (Type::Function(Box::new(Type::Int), Box::new(Type::String)), cn_i2s));
+        stlmap.insert("readline", 
+            (Type::Function(Box::new(Type::Unit), Box::new(Type::String)), cn_readline));   
+        stlmap.insert("len", 
+            (Type::Function(Box::new(Type::String), Box::new(Type::Int)), cn_len));   
+        stlmap.insert("i2f",
+            (Type::Function(Box::new(Type::Int), Box::new(Type::Float)), cn_i2f));
+        stlmap.insert("f2i",
+            (Type::Function(Box::new(Type::Float), Box::new(Type::Int)), cn_f2i));
+        stlmap.insert("f2str",
+            (Type::Function(Box::new(Type::Float), Box::new(Type::String)), cn_f2s));
+        stlmap.insert("i2big",
+            (Type::Function(Box::new(Type::Int), Box::new(Type::BigInt)), cn_i2big));
+        stlmap.insert("big2i",
+            (Type::Function(Box::new(Type::BigInt), Box::new(Type::Int)), cn_big2i));
+        stlmap.insert("big2str",
+            (Type::Function(Box::new(Type::BigInt), Box::new(Type::String)), cn_big2s));
+        stlmap.insert("ord",
+            (Type::Function(Box::new(Type::Char), Box::new(Type::Int)), cn_ord));
+        stlmap.insert("chr",
+            (Type::Function(Box::new(Type::Int), Box::new(Type::Char)), cn_chr));
+        stlmap.insert("c2str",
+            (Type::Function(Box::new(Type::Char), Box::new(Type::String)), cn_c2s));
+        stlmap
+    })
+}
 
-    unsafe {  
-        STL.get_or_insert(stlmap);
+pub fn std_imports() -> HashMap<&'static str, (ValPath, TypeScheme)> {
+    let mut map = HashMap::new();
+    for (name, (t, _)) in stl() {
+        map.insert(*name, (ValPath::Imported(name), TypeScheme::mono(t.clone())));
     }
     map
 }
 
-pub fn std_call(function: &str, value: Rc<Value>) -> Result<Rc<Value>,IntrpErr> {
-    unsafe {
-        STL.as_ref().unwrap()[function].1(value)
-    }
+pub fn std_call(function: &str, value: Rc<Value>, span: Span) -> Result<Rc<Value>,IntrpErr> {
+    stl()[function].1(value, span)
 }
 
-fn cn_print(s: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
+fn cn_print(s: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
     if let Value::String(ref s) = *s {
-        print!("{}", s.replace("\\n", "\n"));
+        print!("{}", s);
         Ok(Rc::new(Value::Unit))
     } else {
         panic!("Runtime type error")
     }
 }
 
-fn cn_i2s(n: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
+fn cn_i2s(n: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
     if let Value::Int(i) = *n {
         Ok(Rc::new(Value::String(format!("{}", i))))
     } else {
@@ -59,7 +83,7 @@ fn cn_i2s(n: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
     }
 }
 
-fn cn_readline(_: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
+fn cn_readline(_: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
     let mut s = String::new();
     match stdin().lock().read_line(&mut s) {
         Ok(_) => Ok(Rc::new(Value::String(s))),
@@ -67,10 +91,94 @@ fn cn_readline(_: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
     }
 }
 
-fn cn_len(s: Rc<Value>) -> Result<Rc<Value>, IntrpErr> {
+fn cn_len(s: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
     if let Value::String(ref s) = *s {
-        Ok(Rc::new(Value::Int(s.len() as isize)))
+        Ok(Rc::new(Value::Int(s.chars().count() as isize)))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+fn cn_i2f(n: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Int(i) = *n {
+        Ok(Rc::new(Value::Float(i as f64)))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+/// truncates towards zero, raises an overflow error if x is NaN or the result
+/// doesn't fit in an int
+fn cn_f2i(x: Rc<Value>, span: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Float(x) = *x {
+        // isize::MIN is a power of two, so both bounds are exact as floats
+        let x = x.trunc();
+        if x >= isize::MIN as f64 && x < -(isize::MIN as f64) {
+            Ok(Rc::new(Value::Int(x as isize)))
+        } else {
+            Err(IntrpErr::Overflow(span))
+        }
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+fn cn_f2s(x: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Float(x) = *x {
+        Ok(Rc::new(Value::String(format!("{:?}", x))))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+fn cn_i2big(n: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Int(i) = *n {
+        Ok(Rc::new(Value::BigInt(BigInt::from(i))))
     } else {
         panic!("Runtime type error")
     }
-}
\ No newline at end of file
+}
+
+/// raises an overflow error if n doesn't fit in an int
+fn cn_big2i(n: Rc<Value>, span: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::BigInt(ref n) = *n {
+        Ok(Rc::new(Value::Int(n.to_isize().ok_or(IntrpErr::Overflow(span))?)))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+fn cn_big2s(n: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::BigInt(ref n) = *n {
+        Ok(Rc::new(Value::String(n.to_string())))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+/// the code point of a character
+fn cn_ord(c: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Char(c) = *c {
+        Ok(Rc::new(Value::Int(c as isize)))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+/// the character of a code point, an error for surrogates and ints out of range
+fn cn_chr(n: Rc<Value>, span: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Int(n) = *n {
+        let c = u32::try_from(n).ok().and_then(char::from_u32);
+        Ok(Rc::new(Value::Char(c.ok_or(IntrpErr::InvalidChar(span))?)))
+    } else {
+        panic!("Runtime type error")
+    }
+}
+
+fn cn_c2s(c: Rc<Value>, _: Span) -> Result<Rc<Value>, IntrpErr> {
+    if let Value::Char(c) = *c {
+        Ok(Rc::new(Value::String(c.to_string())))
+    } else {
+        panic!("Runtime type error")
+    }
+}
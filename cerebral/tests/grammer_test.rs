@@ -1,9 +1,7 @@
-use clog::parse::uncomment;
 #[cfg(test)]
 mod test {
     use {
         clog::{
-            grammar, 
             type_check,
             parse,
         },
@@ -14,7 +12,6 @@ mod test {
     };
     #[test]
     fn test_parser () {
-        use super::*;
         let mut f = File::open("tests/parsed.mal").expect("file not found");
         let mut contents = String::new();
         f.read_to_string(&mut contents)
@@ -26,25 +23,21 @@ mod test {
 
     #[test]
     fn test_reduce () {
-        use super::*;
         let mut f = File::open("tests/type_checked.mal").expect("file not found");
         let mut contents = String::new();
         f.read_to_string(&mut contents)
             .expect("Cannot read file");
-        let contents = uncomment(&mut contents);
         let result = parse::parse(&contents).unwrap();
         type_check::ast2imper_ast(result).unwrap();
     }
 
     #[test]
     fn test_err() {
-        use super::*;
         let mut f = File::open("tests/parse_err.mal").expect("file not found");
         let mut contents = String::new();
         f.read_to_string(&mut contents)
             .expect("Cannot read file");
-        let contents = uncomment(&mut contents);
-        let result = parse::parse(&contents);
-        println!("{:?}", result)
+        let errors = parse::parse(&contents).unwrap_err();
+        assert!(errors.len() > 1, "parsing should continue after the first error");
     }
 }
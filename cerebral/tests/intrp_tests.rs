@@ -2,28 +2,30 @@
 mod test {
     use {
         clog::{
-            grammar, 
+            bigint::BigInt,
             type_check,
             parse,
         },
-        cerebral::interpret,
+        cerebral::{compile, interpret},
         std::{
             fs::File,
             io::prelude::*,
+            rc::Rc,
         },
     };
+    #[test]
     fn test_simple() {
         let mut f = File::open("tests/simple.mal").expect("file not found");
         let mut contents = String::new();
         f.read_to_string(&mut contents)
             .expect("Cannot read file");
-        let contents = parse::uncomment(&mut contents);
         let result = parse::parse(&contents).unwrap();
         let module = type_check::ast2imper_ast(result).unwrap();
         let mut ctx = interpret::Context::new(&module);
-        ctx.eval_toplevel();
+        ctx.eval_toplevel().unwrap();
     }
 
+    #[test]
     fn test_fact() {
         fn fact(n: u32) -> u32 {
             if n == 0 { 1 } else { n * fact(n-1) }
@@ -32,11 +34,11 @@ mod test {
         let mut contents = String::new();
         f.read_to_string(&mut contents)
             .expect("Cannot read file");
-        let contents = parse::uncomment(&mut contents);
         let result = parse::parse(&contents).unwrap();
         let module = type_check::ast2imper_ast(result).unwrap();
         let mut ctx = interpret::Context::new(&module);
-        ctx.eval_toplevel();
+        ctx.eval_toplevel().unwrap();
+        assert_eq!(*ctx.global("f9").unwrap(), interpret::Value::Int(fact(9) as isize));
     }
 
     #[test]
@@ -45,10 +47,808 @@ mod test {
         let mut contents = String::new();
         f.read_to_string(&mut contents)
             .expect("Cannot read file");
-        let contents = parse::uncomment(&mut contents);
-        let result = parse::parse(&contents).unwrap();
-        let module = type_check::ast2imper_ast(result).unwrap();
+        let module = compile(&contents).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+    }
+
+    #[test]
+    fn test_let() {
+        let mut f = File::open("tests/let.mal").expect("file not found");
+        let mut contents = String::new();
+        f.read_to_string(&mut contents)
+            .expect("Cannot read file");
+        let module = compile(&contents).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        assert_eq!(*ctx.global("r1").unwrap(), interpret::Value::Int(15));
+        assert_eq!(*ctx.global("r2").unwrap(), interpret::Value::Int(32));
+        assert_eq!(*ctx.global("r3").unwrap(), interpret::Value::String("s3".to_owned()));
+    }
+
+    #[test]
+    fn test_report_all_errors() {
+        let src = "let x = 1 + \"a\"\nlet y = z\nlet w = 2 + true";
+        let errors = compile(src).unwrap_err();
+        let codes: Vec<_> = errors.iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["E0100", "E0102", "E0100"]);
+        assert_eq!(&src[errors[1].span.start..errors[1].span.end], "z");
+    }
+
+    #[test]
+    fn test_report_all_type_errors() {
+        let src = "let f = { x => x x }\nlet g = { y => (y + 1, if y then 1 else \"a\" end) }";
+        let errors = compile(src).unwrap_err();
+        let codes: Vec<_> = errors.iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["E0109", "E0100", "E0100"]);
+        assert!(errors[0].message.starts_with("cannot construct the infinite type"));
+        assert_eq!(&src[errors[2].span.start..errors[2].span.end], "\"a\"");
+    }
+
+    #[test]
+    fn test_match_checks() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   let f = { (nil ()) => 0, (cons (_, nil ())) => 1 }\n\
+                   let (1, y) = (1, 2)";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0110", "non-exhaustive patterns: `(cons (_, cons _))` not covered"),
+                ("E0110", "non-exhaustive patterns: `(0, _)` not covered"),
+            ]
+        );
+
+        let src = "let f = { true => 1, false => 0, true => 2 }\nlet r = (f true, f false)";
+        let module = compile(src).unwrap();
+        let warnings: Vec<_> = module.warnings.iter().map(|d| &src[d.span.start..d.span.end]).collect();
+        assert_eq!(warnings, ["true"]);
+        assert_eq!(module.warnings[0].span.start, src.rfind("true =>").unwrap());
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        assert_eq!(
+            *ctx.global("r").unwrap(),
+            interpret::Value::Tuple(vec![Rc::new(interpret::Value::Int(1)), Rc::new(interpret::Value::Int(0))])
+        );
+    }
+
+    #[test]
+    fn test_abstract_types() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   type Stack t = abstract List t with\n\
+                   let empty = Stack (nil ())\n\
+                   let push = { x (Stack l) => Stack (cons (x, l)) }\n\
+                   rec size = { (Stack (nil ())) => 0, (Stack (cons (_, l))) => 1 + size (Stack l) }\n\
+                   end\n\
+                   let s = size (push 1 (push 2 empty))";
+        let module = compile(src).unwrap();
+        assert!(module.globals_names.contains_key("push"));
+        assert!(!module.globals_names.contains_key("Stack"));
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        assert_eq!(*ctx.global("s").unwrap(), interpret::Value::Int(2));
+
+        let bad = format!("{}\nlet t = {{ (Stack _) => 0 }}\nlet u = Stack (nil ())", src);
+        let errors = compile(&bad).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0104", "cannot find constructor `Stack`"),
+                ("E0102", "cannot find value `Stack` in this scope"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let src = "type Pair (a, b) = { fst: a, snd: b }\n\
+                   type Point = { x: int, y: int }\n\
+                   let p = { y = 2, x = 1 }\n\
+                   let q = { p with x = 10 }\n\
+                   let swap = { { fst, snd = s } => { fst = s, snd = fst } }\n\
+                   let norm = { { x = 0, y } => y, { x, y = 0 } => x, { x, y } => x + y }\n\
+                   let r = (q.x, q.y, norm p, norm { x = 0, y = 5 }, (swap { fst = 1, snd = \"a\" }).fst)";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let int = |n| Rc::new(interpret::Value::Int(n));
+        assert_eq!(
+            *ctx.global("r").unwrap(),
+            interpret::Value::Tuple(vec![int(10), int(2), int(3), int(5), Rc::new(interpret::Value::String("a".to_owned()))])
+        );
+        assert_eq!(*ctx.global("p").unwrap(), interpret::Value::Record(2, vec![int(1), int(2)]));
+
+        let src = "type Point = { x: int, y: int }\n\
+                   type Flag = { on: bool }\n\
+                   let a = { x = 1 }\n\
+                   let b = { x = 1, y = 2, on = true, x = 3 }\n\
+                   let c = { x = true, y = 2 }.z\n\
+                   let d = { { on = true } => 1 }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0117", "missing field `y` of record `Point`"),
+                ("E0116", "record `Point` has no field `on`"),
+                ("E0118", "field `x` is specified more than once"),
+                ("E0100", "mismatched types `int` and `bool`"),
+                ("E0119", "no field or method `z` on type `Point`"),
+                ("E0110", "non-exhaustive patterns: `{ on = false }` not covered"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_methods() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   def List.len = { (nil ()) => 0, (cons (_, l)) => 1 + l.len }\n\
+                   def List.map = { (nil ()) f => nil (), (cons (x, l)) f => cons (f x, l.map f) }\n\
+                   type Point = { x: int, y: int }\n\
+                   def Point.dot = { p q => p.x * q.x + p.y * q.y }\n\
+                   let l = cons (1, cons (2, nil ()))\n\
+                   let r = (l.len, (l.map { x => x * 10 }).len, { x = 1, y = 2 }.dot { x = 3, y = 4 })";
+        let module = compile(src).unwrap();
+        let list = &module.type_decls[1];
+        assert_eq!(list.methods.len(), 2);
         let mut ctx = interpret::Context::new(&module);
-        ctx.eval_toplevel();
+        ctx.eval_toplevel().unwrap();
+        let int = |n| Rc::new(interpret::Value::Int(n));
+        assert_eq!(*ctx.global("r").unwrap(), interpret::Value::Tuple(vec![int(2), int(2), int(11)]));
+
+        let src = "type A = | A int\n\
+                   type B = | B int\n\
+                   def A.get = { (A n) => n }\n\
+                   def B.get = { (B n) => n }\n\
+                   def A.get = { _ => 0 }\n\
+                   def B.wrong = { (A n) => n }\n\
+                   let a = (A 1).get\n\
+                   let b = (B 1).size\n\
+                   let f = { x => x.get }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0121", "method `get` is defined more than once"),
+                ("E0100", "mismatched types `A` and `B`"),
+                ("E0119", "no field or method `size` on type `B`"),
+                ("E0120", "cannot infer the type of the receiver of `.get`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traits() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   instance Show int = { show = i2str }\n\
+                   instance Show bool = { show = { true => \"yes\", false => \"no\" } }\n\
+                   instance Show t => Show (List t) = {\n\
+                       show = { (nil ()) => \"nil\", (cons (x, l)) => show x ++ \" :: \" ++ show l }\n\
+                   }\n\
+                   instance Eq t => Eq (List t) = {\n\
+                       eq = { (nil ()) (nil ()) => true, (cons (x, l)) (cons (y, m)) => x = y and l = m, _ _ => false }\n\
+                   }\n\
+                   rec member = { _ (nil ()) => false, x (cons (y, l)) => x = y or member x l }\n\
+                   let l = cons (1, cons (2, nil ()))\n\
+                   let r = (show l, show (cons (true, nil ())), l = l, l != cons (1, nil ()), member 2 l, member (cons (1, nil ())) (cons (l, nil ())))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        assert_eq!(
+            *ctx.global("r").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("1 :: 2 :: nil"),
+                string("yes :: nil"),
+                boolean(true),
+                boolean(true),
+                boolean(true),
+                boolean(false),
+            ])
+        );
+
+        let src = "type Box t = | B t\n\
+                   let a = show print\n\
+                   let b = B print = B print\n\
+                   instance Show int = { show = i2str, size = 1 }\n\
+                   instance Show int = { show = i2str }\n\
+                   instance Hash int = { hash = 1 }\n\
+                   instance Show (Box t) = { show = { (B n) => i2str n } }\n\
+                   trait Pretty a = { pretty: a -> string, width: a -> int }\n\
+                   instance Pretty (Box t) = { pretty = { (B x) => show x } }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0122", "no instance of `Show` for type `string -> ()`"),
+                ("E0132", "values of type `Box(string -> ())` contain functions and can't be compared"),
+                ("E0128", "trait `Show` has no method `size`"),
+                ("E0126", "conflicting instances of `Show` for the same type"),
+                ("E0124", "cannot find trait `Hash`"),
+                ("E0129", "the methods of the instance of `Show` are less general than its type"),
+                ("E0127", "missing method `width` of trait `Pretty`"),
+                ("E0122", "no instance of `Show` for type `a`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trait_types() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   type Circle = { r: int }\n\
+                   type Square = | Sq int\n\
+                   def Circle.area = { c => 3 * c.r * c.r }\n\
+                   def Circle.name = { _ => \"circle\" }\n\
+                   def Square.area = { (Sq s) => s * s }\n\
+                   def Square.name = { _ => \"square\" }\n\
+                   type Named = trait{ name: string }\n\
+                   type Shape = trait{ area: int, name: string }\n\
+                   let describe = { (s : Named) => s.name }\n\
+                   rec sum = { (nil ()) => 0, (cons ((x : Shape), l)) => x.area + sum l }\n\
+                   let shapes = (cons ({ r = 1 }, cons ({ r = 2 }, nil ())) : List Shape)\n\
+                   let apply = { (f : Shape -> int) => f (Sq 3 : Shape) }\n\
+                   let r = (describe { r = 1 }, describe (Sq 2 : Shape), sum shapes, apply { (n : Named) => 7 })";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let int = |i| Rc::new(interpret::Value::Int(i));
+        assert_eq!(
+            *ctx.global("r").unwrap(),
+            interpret::Value::Tuple(vec![string("circle"), string("square"), int(15), int(7)])
+        );
+
+        let src = "type Square = | Sq int\n\
+                   def Square.area = { (Sq s) => s * s }\n\
+                   type Shape = trait{ area: int, name: string }\n\
+                   let x = (Sq 1 : Shape)\n\
+                   let y = ((Sq 1 : trait{ area: int }) : Shape)\n\
+                   let z = { (s : trait{ area: int }) => s.size }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0130", "type `Square` is not a subtype of `trait{area: int, name: string}`, it has no method `name`"),
+                ("E0130", "type `trait{area: int}` is not a subtype of `trait{area: int, name: string}`, it has no method `name`"),
+                ("E0119", "no field or method `size` on type `trait{area: int}`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deriving() {
+        let src = "type List t = | nil () | cons (t, List t) deriving (show, eq, ord)\n\
+                   type Tagged = | Int int | Pair (int, (bool, string)) deriving (show, eq, ord)\n\
+                   type Point = { x: int, y: int } deriving (show, eq, ord)\n\
+                   let l = cons (Int 1, cons (Pair (2, (true, \"a\")), nil ()))\n\
+                   let shown = (show l, show { x = 1, y = -2 })\n\
+                   let equal = (l = l, l != cons (Int 1, nil ()), { x = 1, y = 2 } = { x = 1, y = 3 })\n\
+                   let ordered = (compare l (cons (Int 1, nil ())), compare (Int 5) (Pair (0, (false, \"\"))),\n\
+                                  compare (Pair (2, (true, \"b\"))) (Pair (2, (true, \"a\"))), compare { x = 1, y = 2 } { x = 1, y = 2 })";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        let int = |i| Rc::new(interpret::Value::Int(i));
+        assert_eq!(
+            *ctx.global("shown").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("cons(Int(1), cons(Pair(2, (true, \"a\")), nil))"),
+                string("{ x = 1, y = -2 }"),
+            ])
+        );
+        assert_eq!(
+            *ctx.global("equal").unwrap(),
+            interpret::Value::Tuple(vec![boolean(true), boolean(true), boolean(false)])
+        );
+        assert_eq!(
+            *ctx.global("ordered").unwrap(),
+            interpret::Value::Tuple(vec![int(1), int(-1), int(1), int(0)])
+        );
+
+        let src = "type Box = | B int deriving (show, hash)\n\
+                   type F = | F (int -> int) deriving eq\n\
+                   type A = int deriving show\n\
+                   type Opt t = | None () | Some t deriving eq\n\
+                   let x = Some { x => x } = None ()";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                // aliases are defined before the other declarations
+                ("E0108", "derived instances of aliases and abstract types are not supported yet"),
+                ("E0131", "cannot derive `hash`, only `show`, `eq` and `ord` can be derived"),
+                ("E0132", "values of type `int -> int` contain functions and can't be compared"),
+                ("E0132", "values of type `a -> a` contain functions and can't be compared"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tuple_instances() {
+        // the elements are shown and compared by their own instances
+        let src = "type P = | A int | B int deriving (show, ord)\n\
+                   type M = | M int\n\
+                   instance Eq M = { eq = { _ _ => true } }\n\
+                   instance Show bool = { show = { true => \"yes\", false => \"no\" } }\n\
+                   let twice = { x => show (x, x) }\n\
+                   let shown = (show (1, \"a\"), show ((A 1, 'c'), [(2, true)]), twice ())\n\
+                   let equal = ((M 1, 2) = (M 2, 2), (M 1, 2) = (M 1, 3))\n\
+                   let ordered = (compare (A 1, 2) (B 0, 1), (1, 2, 3) < (1, 2, 4), (B 1, 0) > (A 5, 0))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        let int = |i| Rc::new(interpret::Value::Int(i));
+        assert_eq!(
+            *ctx.global("shown").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("(1, \"a\")"),
+                string("((A(1), 'c'), [(2, yes)])"),
+                string("((), ())"),
+            ])
+        );
+        assert_eq!(
+            *ctx.global("equal").unwrap(),
+            interpret::Value::Tuple(vec![boolean(true), boolean(false)])
+        );
+        assert_eq!(
+            *ctx.global("ordered").unwrap(),
+            interpret::Value::Tuple(vec![int(-1), boolean(true), boolean(true)])
+        );
+
+        // an overloaded value has to be bound to a name to be generalised
+        let src = "let (a, b) = ({ x => show x }, 2)\n\
+                   let c = show (1, 2, 3, 4, 5, 6, 7, 8)";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0108", "overloaded values bound to patterns are not supported yet"),
+                ("E0122", "no instance of `Show` for type `(int, int, int, int, int, int, int, int)`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monomorphic_local_bindings() {
+        // a local binding that uses traits can be used at a single type
+        let src = "let f = { x => let g = { y => show y } in (g x, g 2) }\n\
+                   let v = f 1";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        assert_eq!(*ctx.global("v").unwrap(), interpret::Value::Tuple(vec![string("1"), string("2")]));
+
+        // but it isn't generalised, also when used through another name
+        let src = "let f = { x => let g = { y => show y } in (g 1, g true) }\n\
+                   let h = { x => let g = { y => y = y } in let k = g in (k 'c', g \"a\") }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0135", "`g` is used at types `int -> string` and `bool -> string`, a local binding that uses traits isn't generic"),
+                ("E0135", "`g` is used at types `char -> bool` and `string -> bool`, a local binding that uses traits isn't generic"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_structural_comparison() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   type Opt t = | None () | Some t\n\
+                   type P = { a: int, b: string }\n\
+                   let less = { x y => x < y }\n\
+                   let equal = (Some 1 = Some 1, None () != Some 2, cons (1, nil ()) = cons (1, nil ()), { a = 1, b = \"x\" } = { a = 1, b = \"y\" })\n\
+                   let ordered = (\"abc\" < \"abd\", (1, \"b\") < (1, \"a\"), None () < Some 0, Some 3 >= Some 2,\n\
+                                  cons (1, nil ()) < cons (1, cons (0, nil ())), less (Some (1, 2)) (Some (1, 3)))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        assert_eq!(
+            *ctx.global("equal").unwrap(),
+            interpret::Value::Tuple(vec![boolean(true), boolean(true), boolean(true), boolean(false)])
+        );
+        assert_eq!(
+            *ctx.global("ordered").unwrap(),
+            interpret::Value::Tuple(vec![boolean(true), boolean(false), boolean(true), boolean(true), boolean(true), boolean(true)])
+        );
+
+        let src = "type Opt t = | None () | Some t\n\
+                   let same = { x y => x = y }\n\
+                   let a = same print print\n\
+                   let b = Some print < Some print\n\
+                   let c = (1, { x => x }) = (1, { x => x })";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0132", "values of type `string -> ()` contain functions and can't be compared"),
+                ("E0132", "values of type `Opt(string -> ())` contain functions and can't be compared"),
+                ("E0132", "values of type `(int, a -> a)` contain functions and can't be compared"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        let src = "let half = { x => x / 2.0 }\n\
+                   let nan = 0.0 / 0.0\n\
+                   let values = (half 3.0, 1.5 + 1e-3 * 2.0, -2.5E2, i2f 7, f2i (-3.99), f2str 0.25)\n\
+                   let tests = (nan = nan, nan != nan, 1.5 < 2.0, (1.0, \"a\") < (1.0, \"b\"))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let float = |x| Rc::new(interpret::Value::Float(x));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                float(1.5),
+                float(1.502),
+                float(-250.0),
+                float(7.0),
+                Rc::new(interpret::Value::Int(-3)),
+                Rc::new(interpret::Value::String("0.25".to_owned())),
+            ])
+        );
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        assert_eq!(
+            *ctx.global("tests").unwrap(),
+            interpret::Value::Tuple(vec![boolean(false), boolean(true), boolean(true), boolean(true)])
+        );
+
+        let src = "let neg = { x => -x }\n\
+                   let a = 1 + 1.5\n\
+                   let b = neg 1.5\n\
+                   let c = \"a\" * \"b\"";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0100", "mismatched types `int` and `float`"),
+                ("E0100", "mismatched types `int` and `float`"),
+                ("E0100", "mismatched types `int` and `string`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bigint() {
+        let src = "rec fact = { n => if n = 0n then 1n else n * fact (n - 1n) end }\n\
+                   let values = (big2str (fact 30n), fact 25n / fact 23n, -7n % 3n, big2i (i2big 42), fact 20n < fact 21n)";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let big = |s| Rc::new(interpret::Value::BigInt(BigInt::from_decimal(s).unwrap()));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                Rc::new(interpret::Value::String("265252859812191058636308480000000".to_owned())),
+                big("600"),
+                big("-1"),
+                Rc::new(interpret::Value::Int(42)),
+                Rc::new(interpret::Value::Bool(true)),
+            ])
+        );
+
+        let errors = compile("let a = 1n + 1\nlet b = 99999999999999999999").unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [("E0002", "integer literal is too large for `int`, add the suffix `n` for a `bigint`")]
+        );
+        let errors = compile("let a = 1n + 1").unwrap_err();
+        assert_eq!(errors[0].message, "mismatched types `bigint` and `int`");
+    }
+
+    #[test]
+    fn test_overflow() {
+        for (src, code) in [
+            ("let a = 9223372036854775807 + 1", "E0203"),
+            ("let a = -9223372036854775807 - 2", "E0203"),
+            ("let a = 4611686018427387904 * 2", "E0203"),
+            ("let a = 1 / 0", "E0204"),
+            ("let a = 1n % 0n", "E0204"),
+            ("let a = big2i (i2big 9223372036854775807 + 1n)", "E0203"),
+            ("let a = f2i (0.0 / 0.0)", "E0203"),
+            ("let a = f2i 9223372036854775808.0", "E0203"),
+            ("let a = f2i (-1e30)", "E0203"),
+        ] {
+            let module = compile(src).unwrap();
+            let mut ctx = interpret::Context::new(&module);
+            let err = ctx.eval_toplevel().unwrap_err().to_diagnostic();
+            assert_eq!(err.code, code, "{}", src);
+            assert_eq!(err.span.start, 8, "{}", src);
+        }
+
+        // the least int isn't a literal, but its float converts exactly
+        let module = compile("let a = (f2i (-9223372036854775808.0), -9223372036854775807 - 1)").unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let least = Rc::new(interpret::Value::Int(isize::MIN));
+        assert_eq!(*ctx.global("a").unwrap(), interpret::Value::Tuple(vec![least.clone(), least]));
+    }
+
+    #[test]
+    fn test_chars() {
+        let src = "let s = \"مرحبا world\"\n\
+                   let kind = { ' ' => \"space\", 'م' => \"meem\", c => c2str c }\n\
+                   let values = (len s, s[1], s[6:11], kind s[0], kind s[5], ord 'أ', chr 97, show '\\n', 'a' < 'b')";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let character = |c| Rc::new(interpret::Value::Char(c));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                Rc::new(interpret::Value::Int(11)),
+                character('ر'),
+                string("world"),
+                string("meem"),
+                string("space"),
+                Rc::new(interpret::Value::Int(1571)),
+                character('a'),
+                string("'\\n'"),
+                Rc::new(interpret::Value::Bool(true)),
+            ])
+        );
+
+        for (src, code) in [
+            ("let a = \"abc\"[3]", "E0205"),
+            ("let a = \"abc\"[-1]", "E0205"),
+            ("let a = \"abc\"[2:1]", "E0205"),
+            ("let a = \"abc\"[0:4]", "E0205"),
+            ("let a = chr 55296", "E0206"),
+        ] {
+            let module = compile(src).unwrap();
+            let mut ctx = interpret::Context::new(&module);
+            assert_eq!(ctx.eval_toplevel().unwrap_err().to_diagnostic().code, code, "{}", src);
+        }
+
+        let errors = compile("let a = \"abc\"[0] = 97\nlet f = { 'a' => 1 }").unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0100", "mismatched types `char` and `int`"),
+                ("E0110", "non-exhaustive patterns: `'b'` not covered"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        let src = "rec rev_onto = { acc [] => acc, acc (x :: rest) => rev_onto (x :: acc) rest }\n\
+                   rec sum = { [] => 0, (x :: rest) => x + sum rest }\n\
+                   let pairs = { [a, b, ..rest] => show (a + b) ++ show rest, _ => \"short\" }\n\
+                   let values = (show (rev_onto [] [1, 2, 3]), show [[1], [], [2, 3]], sum [1, 2, 3], \
+                   show ['a', 'b'], [1, 2] = 1 :: 2 :: [], [1, 2] < [1, 3], [] < [0], \
+                   show (1 :: [2]), show ([] : [int]), pairs [1, 2, 3], pairs [1])";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        let boolean = |b| Rc::new(interpret::Value::Bool(b));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("[3, 2, 1]"),
+                string("[[1], [], [2, 3]]"),
+                Rc::new(interpret::Value::Int(6)),
+                string("['a', 'b']"),
+                boolean(true),
+                boolean(true),
+                boolean(true),
+                string("[1, 2]"),
+                string("[]"),
+                string("3[3]"),
+                string("short"),
+            ])
+        );
+
+        let errors = compile("let a = [1, \"a\"]\nlet f = { [] => 0, [x, y, ..rest] => x }").unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0100", "mismatched types `int` and `string`"),
+                ("E0110", "non-exhaustive patterns: `(_ :: [])` not covered"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comprehensions() {
+        let src = "let range = 3\n\
+                   let values = (show [1..5], show [5..1], show [x * 10 | x <- [1..range], x != 2], \
+                   show [show a ++ c2str b | a <- [1, 2], b <- ['x', 'y']], show [[y | y <- [1..x]] | x <- [1..3]], \
+                   show [a + b | (a, b) <- [(1, 2), (3, 4)]], show [0 | _ <- [1..2]])";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("[1, 2, 3, 4, 5]"),
+                string("[]"),
+                string("[10, 30]"),
+                string("[\"1x\", \"1y\", \"2x\", \"2y\"]"),
+                string("[[1], [1, 2], [1, 2, 3]]"),
+                string("[3, 7]"),
+                string("[0, 0]"),
+            ])
+        );
+
+        // the errors are reported at the parts of the comprehension
+        let src = "let a = [x | x <- [1..3], x]\nlet b = [1..'z']\nlet c = [x | (x :: _) <- [[1]]]";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), &src[d.span.start..d.span.end]))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0100", "mismatched types `bool` and `int`", "x"),
+                ("E0100", "mismatched types `int` and `char`", "'z'"),
+                ("E0110", "non-exhaustive patterns: `[]` not covered", "(x :: _) <- [[1]]"),
+            ]
+        );
+        let errors = compile("let a = [x | x + 1 <- [1]]").unwrap_err();
+        assert_eq!((errors[0].code, errors[0].message.as_str()), ("E0001", "unexpected `x + 1`"));
+    }
+
+    #[test]
+    fn test_guards() {
+        let src = "let sign = { n when n < 0 => \"neg\", 0 => \"zero\", _ => \"pos\" }\n\
+                   let classify = { 0 when false => \"never\", n when n % 2 = 0 => \"even\", _ => \"odd\" }\n\
+                   rec take_while = { p (x :: rest) when p x => x :: take_while p rest, _ _ => [] }\n\
+                   let values = (sign (-2), sign 0, sign 3, classify 0, classify 3, show (take_while { x => x < 3 } [1, 2, 3, 1]))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let string = |s: &str| Rc::new(interpret::Value::String(s.to_owned()));
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                string("neg"),
+                string("zero"),
+                string("pos"),
+                string("even"),
+                string("odd"),
+                string("[1, 2]"),
+            ])
+        );
+
+        // guarded arms don't cover their patterns
+        let src = "let f = { n when n > 0 => 1 }\nlet g = { true when 1 => 1, _ => 2 }\nlet h = { x when x > 0 => 1, x => 2, _ => 3 }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), &src[d.span.start..d.span.end]))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0110", "non-exhaustive patterns: `_` not covered", "{ n when n > 0 => 1 }"),
+                ("E0100", "mismatched types `bool` and `int`", "1"),
+                ("E0111", "unreachable pattern", "_"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_or_patterns() {
+        let src = "type Option a = | Some a | None ()\n\
+                   let get = { (x, 0) | (0, x) => x, _ => -1 }\n\
+                   let size = { (Some (1 | 2) | None ()) => \"small\", _ => \"big\" }\n\
+                   let firsts = { l @ (x :: _) => x :: l, [] => [] }\n\
+                   let last = { ([x] | [_, x] | [_, _, x]) y when x > y => { z => x + z } 100, _ _ => 0 }\n\
+                   let values = (get (3, 0), get (0, 4), get (1, 1), size (None ()), size (Some 2), size (Some 3),\n\
+                                 show (firsts [1, 2]), last [1, 5] 2, last [1, 1] 2)";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(vec![
+                Rc::new(interpret::Value::Int(3)),
+                Rc::new(interpret::Value::Int(4)),
+                Rc::new(interpret::Value::Int(-1)),
+                Rc::new(interpret::Value::String("small".to_owned())),
+                Rc::new(interpret::Value::String("small".to_owned())),
+                Rc::new(interpret::Value::String("big".to_owned())),
+                Rc::new(interpret::Value::String("[1, 1, 2]".to_owned())),
+                Rc::new(interpret::Value::Int(105)),
+                Rc::new(interpret::Value::Int(0)),
+            ])
+        );
+
+        let src = "let f = { (x, 0) | (0, y) => 1, _ => 2 }\n\
+                   let g = { (x, 0) | (x, \"a\") => 1, _ => 2 }\n\
+                   let h = { 1 | 2 | 1 => 1, _ => 2 }\n\
+                   let i = { (1 | 2, 3 | 4) => 1, (1, 4) => 2, (2 | 5, 3) => 3, _ => 4 }\n\
+                   let ((x, 0) | (0, x)) = (1, 0)";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), &src[d.span.start..d.span.end]))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0133", "`y` isn't bound in every alternative of the pattern", "(x, 0)"),
+                ("E0133", "`x` isn't bound in every alternative of the pattern", "(0, y)"),
+                ("E0100", "mismatched types `int` and `string`", "\"a\""),
+                ("E0111", "unreachable pattern", "1"),
+                ("E0111", "unreachable pattern", "(1, 4)"),
+                ("E0111", "unreachable pattern", "2"),
+                (
+                    "E0108",
+                    "or-patterns binding names at different positions outside of closures are not supported yet",
+                    "(x, 0) | (0, x)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_patterns() {
+        let src = "let class = { '0'..'9' => \"digit\", ('a'..'z' | 'A'..'Z') => \"letter\", ' ' => \"space\", _ => \"other\" }\n\
+                   let kw = { (\"let \" ++ rest) => rest, \"in\" => \"in\", (\"0x\" ++ hex) => hex, s => \"id \" ++ s }\n\
+                   let size = { 0 => \"zero\", 1..9 => \"small\", 10..99 => \"medium\", _ => \"big\" }\n\
+                   let values = (class '7', class 'Q', class ' ', class '+', kw \"let x\", kw \"in\", kw \"inner\",\n\
+                                 kw \"0xff\", size 0, size 5, size 50, size (0 - 5))";
+        let module = compile(src).unwrap();
+        let mut ctx = interpret::Context::new(&module);
+        ctx.eval_toplevel().unwrap();
+        let strings = ["digit", "letter", "space", "other", "x", "in", "id inner", "ff", "zero", "small", "medium", "big"];
+        assert_eq!(
+            *ctx.global("values").unwrap(),
+            interpret::Value::Tuple(
+                strings.iter().map(|s| Rc::new(interpret::Value::String(s.to_string()))).collect()
+            )
+        );
+
+        let src = "let a = { 0..9 => 1, 10..20 => 2 }\n\
+                   let b = { 'a'..'z' => 1 }\n\
+                   let c = { (\"ab\" ++ _) => 1, \"\" => 2 }\n\
+                   let d = { '\\u{0}'..'m' => 1, 'n'..'\\u{10FFFF}' => 2 }\n\
+                   let e = { 5..1 => 1 }\n\
+                   let f = { (\"a\" ++ (\"b\" | \"c\")) => 1, \"ab\" => 2, _ => 3 }\n\
+                   let g = { 1..9 => 1, 5 => 2, _ => 3 }";
+        let errors = compile(src).unwrap_err();
+        let messages: Vec<_> = errors
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), &src[d.span.start..d.span.end]))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("E0110", "non-exhaustive patterns: `21` not covered", "{ 0..9 => 1, 10..20 => 2 }"),
+                ("E0110", "non-exhaustive patterns: `'{'` not covered", "{ 'a'..'z' => 1 }"),
+                ("E0110", "non-exhaustive patterns: `\"a\"` not covered", "{ (\"ab\" ++ _) => 1, \"\" => 2 }"),
+                ("E0134", "the lower bound of the range pattern is greater than its upper bound", "5..1"),
+                ("E0111", "unreachable pattern", "\"ab\""),
+                ("E0111", "unreachable pattern", "5"),
+            ]
+        );
     }
-}
\ No newline at end of file
+}
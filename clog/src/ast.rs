@@ -1,26 +1,104 @@
 //! The AST generated by the parser.
 
+use std::borrow::Cow;
 use crate::types::{ProtoType, Literal, BinOpcode, UnOpcode};
 
-/// A top level declaration, which is a value or a type declaration
+/// The constructors of the built-in list type, which list patterns are parsed
+/// into and lists are built with. Their names can't be written in the source
+pub const NIL: &str = "#nil";
+pub const CONS: &str = "#cons";
+
+/// A byte range `[start, end)` in the source, taken from the parser's `@L`/`@R` markers
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// smallest span covering both self and other
+    pub fn to(self, other: Span) -> Self {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+/// A top level declaration with its source span
+#[derive(Debug)]
+pub struct Binding<'input> {
+    pub kind: BindingKind<'input>,
+    pub span: Span,
+    /// lines of the doc comments preceding the declaration
+    pub doc: Vec<&'input str>,
+}
+
+/// A top level declaration, which is a value, type, trait or instance declaration
 #[derive(Debug)]
-pub enum Binding<'input> {
+pub enum BindingKind<'input> {
     /// A type declaration
     Type {
         /// name to bind the type to
-        name: &'input str, 
+        name: &'input str,
         /// names of generics
         vars: Vec<&'input str>,
-        /// variants' names and arguments' types
-        variants: Vec<(&'input str, ProtoType<'input>)> },
+        def: TypeDef<'input>,
+        /// the names of the traits to derive instances of and their spans,
+        /// e.g. `deriving (show, eq)`
+        deriving: Vec<(&'input str, Span)> },
     /// A value binding, bool for is recursive?
     Value(Pattern<'input>, Expr<'input>, bool),
-    Method(&'input str, &'input str, Expr<'input>)
+    /// def Type.name = { receiver args => ... }, a function of a type called as e.name
+    Method(&'input str, &'input str, Expr<'input>),
+    /// trait Name a = { method: type, ... }, methods over the type variable a
+    Trait {
+        name: &'input str,
+        var: &'input str,
+        /// methods' names, types and the spans of the names
+        methods: Vec<(&'input str, ProtoType<'input>, Span)> },
+    /// instance Context => Trait Head = { method = e, ... }
+    Instance {
+        /// the traits the variables of the head must have instances of, e.g. `Eq t`
+        /// or `(Eq a, Show b)`
+        context: Option<ProtoType<'input>>,
+        /// the trait applied to the type of the instance, e.g. `Eq (List t)`
+        head: ProtoType<'input>,
+        /// the definitions of the methods and the spans of their names
+        methods: Vec<(&'input str, Expr<'input>, Span)> },
+    /// Parse error
+    Error,
+}
+
+/// The right hand side of a type declaration
+#[derive(Debug)]
+pub enum TypeDef<'input> {
+    /// variants' names, arguments' types and the spans of the names
+    Sum(Vec<(&'input str, ProtoType<'input>, Span)>),
+    /// another name for a type, expanded where it's used
+    Alias(ProtoType<'input>),
+    /// a type with a single constructor named after it wrapping the representation.
+    /// The constructor is only in scope of the bindings after `with` if any,
+    /// otherwise of the rest of the module, and is never exported
+    Abstract(ProtoType<'input>, Option<Vec<Binding<'input>>>),
+    /// fields' names, types and the spans of the names
+    Record(Vec<(&'input str, ProtoType<'input>, Span)>),
+}
+
+/// A pattern with its source span
+#[derive(Debug)]
+pub struct Pattern<'input> {
+    pub kind: PatternKind<'input>,
+    pub span: Span,
 }
 
 /// A pattern or LHS of a binding to match
 #[derive(Debug)]
-pub enum Pattern<'input> {
+pub enum PatternKind<'input> {
     /// Wild card matches everything
     Wild,
     /// matches a concrete value, e.g. a number or a string
@@ -31,19 +109,45 @@ pub enum Pattern<'input> {
     Tuple(Vec<Pattern<'input>>),
     /// matches a variant of a sum type and its argument with leading path
     SumVar(&'input str, Box<Pattern<'input>>),
+    /// matches some of the fields of a record, with the spans of the fields' names
+    Record(Vec<(&'input str, Pattern<'input>, Span)>),
+    /// (p : t), the matched value has the type t
+    Annotated(Box<Pattern<'input>>, ProtoType<'input>),
+    /// p1 | p2 | ..., matches if any of the alternatives matches, which all bind
+    /// the same names to values of the same types
+    Or(Vec<Pattern<'input>>),
+    /// x @ p, binds the whole value matched by p to x
+    As(&'input str, Box<Pattern<'input>>),
+    /// lo..hi, matches the ints or chars from lo to hi inclusive
+    Range(Literal<'input>, Literal<'input>),
+    /// "prefix" ++ p, matches a string starting with the prefix whose rest p matches
+    Prefix(Cow<'input, str>, Box<Pattern<'input>>),
     /// Parse error
-    Error(usize, usize),
+    Error,
+}
+
+/// An expression with its source span
+#[derive(Debug)]
+pub struct Expr<'input> {
+    pub kind: ExprKind<'input>,
+    pub span: Span,
 }
 
 /// An expression or RHS that evaluates to a value,
 #[derive(Debug)]
-pub enum Expr<'input> {
+pub enum ExprKind<'input> {
     /// a value literal e.g int, string
     Literal(Literal<'input>),
     /// value of a name that was previously bound with leading path
     Bound(&'input str),
     /// a tuple of values
     Tuple(Vec<Expr<'input>>),
+    /// a record with all of its fields, with the spans of the fields' names
+    Record(Vec<(&'input str, Expr<'input>, Span)>),
+    /// { r with x = e }, a copy of a record with some fields replaced
+    Update(Box<Expr<'input>>, Vec<(&'input str, Expr<'input>, Span)>),
+    /// [e1, ..., en], or e1 :: ... :: en :: l if the rest of the list is l
+    List(Vec<Expr<'input>>, Option<Box<Expr<'input>>>),
 
     /// the value of applying a binary operation on two Exprs
     BinOp(Box<Expr<'input>>, BinOpcode, Box<Expr<'input>>),
@@ -52,13 +156,118 @@ pub enum Expr<'input> {
     Slice(Box<Expr<'input>>, Box<Expr<'input>>, Box<Expr<'input>>),
 
     /// A closure is a sequence of patterns and corresponsing expressions
-    Closure(Vec<(Vec<Pattern<'input>>, Expr<'input>)>),
+    Closure(Vec<Arm<'input>>),
     /// Apply an expression on an expression,
     /// including constructing values of sum types
     Application(Box<Expr<'input>>, Box<Expr<'input>>),
+    /// e.name, the field of a record or a method call
     MethodCall(Box<Expr<'input>>, &'input str),
     /// if e1 then e2 else e3
     Conditional(Box<Expr<'input>>, Box<Expr<'input>>, Box<Expr<'input>>),
+    /// let p = e1 in e2
+    Let(Pattern<'input>, Box<Expr<'input>>, Box<Expr<'input>>),
+    /// e where { p1 = e1, p2 = e2 }, the bindings are in scope of e and each
+    /// of the bindings after it
+    Where(Box<Expr<'input>>, Vec<(Pattern<'input>, Expr<'input>)>),
+    /// (e : t), e used as a value of the type t, which may be a supertype of its own
+    Annotated(Box<Expr<'input>>, ProtoType<'input>),
+    /// [a..b], the ints from a to b inclusive
+    Range(Box<Expr<'input>>, Box<Expr<'input>>),
+    /// [e | q1, ..., qn], e for each of the values the qualifiers produce
+    Comprehension(Box<Expr<'input>>, Vec<Qualifier<'input>>),
     /// Parse error
-    Error(usize, usize),
+    Error,
+}
+
+/// An arm of a closure, its patterns, the guard it's only taken if true, and
+/// its expression
+pub type Arm<'input> = (Vec<Pattern<'input>>, Option<Expr<'input>>, Expr<'input>);
+
+/// A qualifier of a list comprehension
+#[derive(Debug)]
+pub enum Qualifier<'input> {
+    /// p <- e, the bindings of p for each element of the list e
+    Generator(Pattern<'input>, Expr<'input>),
+    /// a condition the elements of the comprehension satisfy
+    Guard(Expr<'input>),
+}
+
+impl<'input> Binding<'input> {
+    pub fn new(kind: BindingKind<'input>, start: usize, end: usize) -> Self {
+        Binding { kind, span: Span::new(start, end), doc: vec![] }
+    }
+}
+
+impl<'input> Pattern<'input> {
+    pub fn new(kind: PatternKind<'input>, start: usize, end: usize) -> Self {
+        Pattern { kind, span: Span::new(start, end) }
+    }
+
+    /// `hd :: tl`, matching a list with a head and a tail
+    pub fn cons(hd: Self, tl: Self) -> Self {
+        let span = hd.span.to(tl.span);
+        let pair = Pattern { kind: PatternKind::Tuple(vec![hd, tl]), span };
+        Pattern { kind: PatternKind::SumVar(CONS, Box::new(pair)), span }
+    }
+
+    /// `[p1, ..., pn]` spanning start to end, or `[p1, ..., pn, ..rest]` if the
+    /// rest of the list is matched by a pattern
+    pub fn list(elements: Vec<Self>, rest: Option<Self>, start: usize, end: usize) -> Self {
+        let nil = || {
+            let unit = Pattern::new(PatternKind::Literal(Literal::Unit), start, end);
+            Pattern::new(PatternKind::SumVar(NIL, Box::new(unit)), start, end)
+        };
+        let mut list = rest.unwrap_or_else(nil);
+        for p in elements.into_iter().rev() {
+            list = Pattern::cons(p, list);
+        }
+        Pattern { span: Span::new(start, end), ..list }
+    }
+}
+
+impl<'input> Expr<'input> {
+    pub fn new(kind: ExprKind<'input>, start: usize, end: usize) -> Self {
+        Expr { kind, span: Span::new(start, end) }
+    }
+
+    /// Build an expression spanning from the start of `first` to the end of `last`
+    pub fn joined(kind: ExprKind<'input>, first: Span, last: Span) -> Self {
+        Expr { kind, span: first.to(last) }
+    }
+
+    /// `e1 :: ... :: en :: tl`, the list of the elements followed by a tail
+    pub fn cons(elements: Vec<Self>, tl: Self) -> Self {
+        let span = elements[0].span.to(tl.span);
+        Expr { kind: ExprKind::List(elements, Some(Box::new(tl))), span }
+    }
+
+    /// `[e1, ..., en]` spanning start to end
+    pub fn list(elements: Vec<Self>, start: usize, end: usize) -> Self {
+        Expr::new(ExprKind::List(elements, None), start, end)
+    }
+
+    /// The pattern written the same as the expression, e.g. the `(x, Some y)` of a
+    /// generator which is only known to be a pattern once its `<-` is reached
+    /// ### Errors
+    /// the span of a subexpression which isn't a pattern
+    pub fn into_pattern(self) -> Result<Pattern<'input>, Span> {
+        let kind = match self.kind {
+            ExprKind::Literal(lit) => PatternKind::Literal(lit),
+            ExprKind::Bound("_") => PatternKind::Wild,
+            ExprKind::Bound(name) => PatternKind::Bind(name),
+            ExprKind::Tuple(v) => PatternKind::Tuple(v.into_iter().map(Expr::into_pattern).collect::<Result<_, _>>()?),
+            ExprKind::Application(f, arg) => match f.kind {
+                ExprKind::Bound(name) => PatternKind::SumVar(name, Box::new(arg.into_pattern()?)),
+                _ => return Err(f.span),
+            },
+            ExprKind::Annotated(e, t) => PatternKind::Annotated(Box::new(e.into_pattern()?), t),
+            ExprKind::List(v, tl) => {
+                let elements = v.into_iter().map(Expr::into_pattern).collect::<Result<_, _>>()?;
+                let rest = tl.map(|tl| tl.into_pattern()).transpose()?;
+                return Ok(Pattern::list(elements, rest, self.span.start, self.span.end));
+            }
+            _ => return Err(self.span),
+        };
+        Ok(Pattern { kind, span: self.span })
+    }
 }
\ No newline at end of file
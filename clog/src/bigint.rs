@@ -0,0 +1,278 @@
+//! Arbitrary-precision integers, the values of the `bigint` type.
+//!
+//! A number is a sign and a magnitude stored as little-endian digits in base
+//! 10^9, so parsing and printing are done a digit at a time. Zero has no
+//! digits and is never negative, which keeps the representation unique and
+//! the derived equality structural. Division truncates towards zero like the
+//! division of `int`.
+
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        BigInt::from_decimal(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for s in ["0", "7", "-7", "1000000000", "-123456789012345678901234567890"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("000000000000012").to_string(), "12");
+        assert_eq!(BigInt::from_decimal(""), None);
+        assert_eq!(BigInt::from_decimal("1a"), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+        assert_eq!((&a + &b).to_string(), "-864197532086419753208641975320");
+        assert_eq!((&a - &b).to_string(), "1111111110111111111011111111100");
+        assert_eq!((&a * &b).to_string(), "-121932631137021795226185032733622923332237463801111263526900");
+        assert_eq!((&a - &a), BigInt::from(0));
+        let (q, r) = b.div_rem(&a).unwrap();
+        assert_eq!((q.to_string(), r.to_string()), ("-8".to_owned(), "-9000000000900000000090".to_owned()));
+        let (q, r) = big("100000000000000000000").div_rem(&big("7")).unwrap();
+        assert_eq!((q.to_string(), r.to_string()), ("14285714285714285714".to_owned(), "2".to_owned()));
+        assert_eq!(a.div_rem(&BigInt::from(0)), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        for n in [0, 1, -1, 999_999_999, 1_000_000_000, isize::MAX, isize::MIN] {
+            assert_eq!(BigInt::from(n).to_isize(), Some(n));
+        }
+        assert_eq!((&BigInt::from(isize::MAX) + &BigInt::from(1)).to_isize(), None);
+        assert!(big("-10") < big("-9") && big("-9") < big("0") && big("0") < big("1000000000"));
+    }
+}
+
+const BASE: u64 = 1_000_000_000;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BigInt {
+    negative: bool,
+    /// little-endian digits in base 10^9 without leading zeros
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    /// the number written in decimal with an optional leading `-`
+    pub fn from_decimal(s: &str) -> Option<Self> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let digits = s
+            .as_bytes()
+            .rchunks(9)
+            .map(|chunk| chunk.iter().fold(0, |n, &b| n * 10 + (b - b'0') as u32))
+            .collect();
+        Some(BigInt::new(negative, digits))
+    }
+
+    /// the number as an int, None if it doesn't fit
+    pub fn to_isize(&self) -> Option<isize> {
+        let mut n: i128 = 0;
+        for &d in self.digits.iter().rev() {
+            n = n.checked_mul(BASE as i128)?.checked_add(d as i128)?;
+        }
+        isize::try_from(if self.negative { -n } else { n }).ok()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// the quotient truncated towards zero and the remainder, which has the
+    /// sign of self. None when dividing by zero
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (q, r) = div_rem_digits(&self.digits, &other.digits);
+        Some((BigInt::new(self.negative != other.negative, q), BigInt::new(self.negative, r)))
+    }
+
+    /// normalises the digits and the sign of zero
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        trim(&mut digits);
+        BigInt { negative: negative && !digits.is_empty(), digits }
+    }
+}
+
+impl From<isize> for BigInt {
+    fn from(n: isize) -> Self {
+        let mut m = n.unsigned_abs() as u64;
+        let mut digits = vec![];
+        while m > 0 {
+            digits.push((m % BASE) as u32);
+            m /= BASE;
+        }
+        BigInt::new(n < 0, digits)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_digits(&self.digits, &other.digits),
+            (true, true) => cmp_digits(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_digits(&self.digits, &other.digits));
+        }
+        // the sign is that of the larger magnitude
+        match cmp_digits(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::new(other.negative, sub_digits(&other.digits, &self.digits)),
+            _ => BigInt::new(self.negative, sub_digits(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(self.negative != other.negative, mul_digits(&self.digits, &other.digits))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.digits.split_last() {
+            None => write!(f, "0"),
+            Some((top, rest)) => {
+                if self.negative {
+                    write!(f, "-")?;
+                }
+                write!(f, "{}", top)?;
+                rest.iter().rev().try_for_each(|d| write!(f, "{:09}", d))
+            }
+        }
+    }
+}
+
+/// drop the leading zeros
+fn trim(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+fn cmp_digits(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let d = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push((d % BASE) as u32);
+        carry = d / BASE;
+    }
+    sum.push(carry as u32);
+    trim(&mut sum);
+    sum
+}
+
+/// a - b where a >= b
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &d) in a.iter().enumerate() {
+        let sub = *b.get(i).unwrap_or(&0) as i64 + borrow;
+        let d = d as i64 - sub;
+        borrow = (d < 0) as i64;
+        diff.push((d + borrow * BASE as i64) as u32);
+    }
+    trim(&mut diff);
+    diff
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let d = product[i + j] + x as u64 * y as u64 + carry;
+            product[i + j] = d % BASE;
+            carry = d / BASE;
+        }
+        product[i + b.len()] += carry;
+    }
+    let mut product = product.into_iter().map(|d| d as u32).collect();
+    trim(&mut product);
+    product
+}
+
+/// schoolbook long division of a by a non-zero b, finding each digit of the
+/// quotient by a binary search
+fn div_rem_digits(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0; a.len()];
+    let mut rem: Vec<u32> = vec![];
+    for (i, &d) in a.iter().enumerate().rev() {
+        rem.insert(0, d);
+        trim(&mut rem);
+        let (mut low, mut high) = (0, BASE as u32 - 1);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if cmp_digits(&mul_digits(b, &[mid]), &rem) == Ordering::Greater {
+                high = mid - 1;
+            } else {
+                low = mid;
+            }
+        }
+        if low > 0 {
+            rem = sub_digits(&rem, &mul_digits(b, &[low]));
+        }
+        quotient[i] = low;
+    }
+    trim(&mut quotient);
+    (quotient, rem)
+}
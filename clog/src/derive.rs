@@ -0,0 +1,325 @@
+//! Instances generated by `deriving (show, eq, ord)` on a type declaration,
+//! and the ones of tuples.
+//!
+//! A derived instance is an instance declaration built from the variants of the
+//! type, which is then checked like one written in the source. Values in a
+//! tuple are taken apart by small projection closures and matched values are
+//! bound to names that can't be written in the source, so the generated code
+//! doesn't depend on the names in scope.
+
+use std::borrow::Cow;
+use crate::{
+    ast::{Arm, Expr, ExprKind, Pattern, PatternKind, Span},
+    types::{BinOpcode, Literal, ProtoType, Type, TypeDecl, TypeKind},
+};
+
+/// The name `show` is also bound to, used by derived instances
+pub const SHOW: &str = "#show";
+/// The name `compare` is also bound to, used by derived instances
+pub const COMPARE: &str = "#compare";
+
+/// the first and second values compared, or the value shown
+const X: &str = "#x";
+const Y: &str = "#y";
+/// the value taken out of a tuple or a record
+const V: &str = "#v";
+/// the comparison of a component of a tuple
+const C: &str = "#c";
+/// the generics of the instances for tuples, the type of each element
+const ELEMENTS: [&str; MAX_TUPLE] = ["#t1", "#t2", "#t3", "#t4", "#t5", "#t6", "#t7"];
+
+/// The number of elements of the largest tuples with instances of Show, Eq and Ord
+pub const MAX_TUPLE: usize = 7;
+
+/// context, head and methods of an instance declaration
+pub type InstanceDecl<'input> = (Option<ProtoType<'input>>, ProtoType<'input>, Vec<(&'input str, Expr<'input>, Span)>);
+
+/// A step from a matched value to a part of it
+#[derive(Clone, Copy)]
+enum Step<'input> {
+    /// the ith element of a tuple of n elements
+    Element(usize, usize),
+    Field(&'input str),
+}
+
+/// The instance of `deriving name` for a sum or record type with the generics
+/// vars, or None if name can't be derived. Each generic is required to have
+/// an instance of the trait too
+pub fn instance<'input>(name: &str, decl: &TypeDecl<'input>, vars: &[&'input str], span: Span) -> Option<InstanceDecl<'input>> {
+    let gen = Gen { span };
+    let (trait_, method, arms) = match name {
+        "show" => ("Show", "show", gen.show(decl)),
+        "eq" => ("Eq", "eq", gen.eq(decl)),
+        "ord" => ("Ord", "compare", gen.compare(decl)),
+        _ => return None,
+    };
+    let generic = |v| ProtoType::Generic(v, span);
+    let t = match vars {
+        [] => generic(decl.name),
+        [v] => ProtoType::Sum(decl.name, Box::new(generic(v)), span),
+        _ => ProtoType::Sum(decl.name, Box::new(ProtoType::Tuple(vars.iter().map(|&v| generic(v)).collect())), span),
+    };
+    Some(declaration(trait_, t, vars, (method, gen.expr(ExprKind::Closure(arms)), span), span))
+}
+
+/// The instance of the trait Show, Eq or Ord for the tuples of n elements, which
+/// like a derived one is structural and requires an instance for the type of
+/// each element. There's no declaration of tuples to derive it from, so the
+/// checker adds them for the tuples of up to MAX_TUPLE elements
+pub fn tuple_instance<'input>(trait_: &'static str, n: usize) -> InstanceDecl<'input> {
+    let span = Span::default();
+    let gen = Gen { span };
+    let t = Type::Tuple((0..n as u16).map(Type::Generic).collect());
+    let (method, arm) = match trait_ {
+        "Show" => ("show", (vec![gen.bind(X)], None, gen.show_value(&t, &[]))),
+        "Eq" => ("eq", (vec![gen.bind(X), gen.bind(Y)], None, gen.eq_value(&t, &[]))),
+        _ => ("compare", (vec![gen.bind(X), gen.bind(Y)], None, gen.compare_value(&t, &[]))),
+    };
+    let vars = &ELEMENTS[..n];
+    let t = ProtoType::Tuple(vars.iter().map(|&v| ProtoType::Generic(v, span)).collect());
+    declaration(trait_, t, vars, (method, gen.expr(ExprKind::Closure(vec![arm])), span), span)
+}
+
+/// `instance (Trait v1, ..., Trait vn) => Trait t = { method }`
+fn declaration<'input>(
+    trait_: &'input str,
+    t: ProtoType<'input>,
+    vars: &[&'input str],
+    method: (&'input str, Expr<'input>, Span),
+    span: Span,
+) -> InstanceDecl<'input> {
+    let generic = |v| ProtoType::Generic(v, span);
+    let context = match vars {
+        [] => None,
+        _ => Some(ProtoType::Tuple(
+            vars.iter().map(|&v| ProtoType::Sum(trait_, Box::new(generic(v)), span)).collect(),
+        )),
+    };
+    let head = ProtoType::Sum(trait_, Box::new(t), span);
+    (context, head, vec![method])
+}
+
+/// Builds the code of derived instances, all at the span of the derived name
+struct Gen {
+    span: Span,
+}
+
+impl Gen {
+    /// `show` writes a variant as its constructor applied to its argument in
+    /// parentheses, e.g. `cons(1, nil)`, and a record as `{ x = 1, y = 2 }`
+    fn show<'input>(&self, decl: &TypeDecl<'input>) -> Vec<Arm<'input>> {
+        if decl.kind == TypeKind::Record {
+            let mut parts = vec![self.string(Cow::Borrowed("{ "))];
+            for (i, &(field, ref t)) in decl.variants.iter().enumerate() {
+                let sep = if i == 0 { "" } else { ", " };
+                parts.push(self.string(Cow::Owned(format!("{}{} = ", sep, field))));
+                parts.push(self.show_value(t, &[Step::Field(field)]));
+            }
+            parts.push(self.string(Cow::Borrowed(" }")));
+            return vec![(vec![self.bind(X)], None, self.concat(parts))];
+        }
+        decl.variants
+            .iter()
+            .map(|&(constructor, ref t)| {
+                let shown = match t {
+                    Type::Unit => self.string(Cow::Borrowed(constructor)),
+                    Type::Tuple(_) => {
+                        self.concat(vec![self.string(Cow::Borrowed(constructor)), self.show_value(t, &[])])
+                    }
+                    _ => self.concat(vec![
+                        self.string(Cow::Owned(format!("{}(", constructor))),
+                        self.show_value(t, &[]),
+                        self.string(Cow::Borrowed(")")),
+                    ]),
+                };
+                (vec![self.variant(constructor, self.bind(X))], None, shown)
+            })
+            .collect()
+    }
+
+    /// the string of the part of X at path of type t
+    fn show_value<'input>(&self, t: &Type, path: &[Step<'input>]) -> Expr<'input> {
+        match t {
+            Type::Unit => self.string(Cow::Borrowed("()")),
+            Type::Tuple(ts) => {
+                let mut parts = vec![self.string(Cow::Borrowed("("))];
+                for (i, t) in ts.iter().enumerate() {
+                    if i > 0 {
+                        parts.push(self.string(Cow::Borrowed(", ")));
+                    }
+                    parts.push(self.show_value(t, &extend(path, Step::Element(i, ts.len()))));
+                }
+                parts.push(self.string(Cow::Borrowed(")")));
+                self.concat(parts)
+            }
+            _ => self.apply(self.bound(SHOW), self.access(X, path)),
+        }
+    }
+
+    /// `eq` is true of the same variant with equal arguments, or records with
+    /// equal fields
+    fn eq<'input>(&self, decl: &TypeDecl<'input>) -> Vec<Arm<'input>> {
+        if decl.kind == TypeKind::Record {
+            let fields = decl.variants.iter().map(|&(field, ref t)| self.eq_value(t, &[Step::Field(field)]));
+            return vec![(vec![self.bind(X), self.bind(Y)], None, self.all(fields.collect()))];
+        }
+        let mut arms: Vec<_> = decl.variants
+            .iter()
+            .map(|&(constructor, ref t)| {
+                let pats = vec![self.variant(constructor, self.bind(X)), self.variant(constructor, self.bind(Y))];
+                (pats, None, self.eq_value(t, &[]))
+            })
+            .collect();
+        if decl.variants.len() > 1 {
+            arms.push((vec![self.wild(), self.wild()], None, self.expr(ExprKind::Literal(Literal::Bool(false)))));
+        }
+        arms
+    }
+
+    /// whether the parts of X and Y at path of type t are equal
+    fn eq_value<'input>(&self, t: &Type, path: &[Step<'input>]) -> Expr<'input> {
+        match t {
+            Type::Unit => self.expr(ExprKind::Literal(Literal::Bool(true))),
+            Type::Tuple(ts) => self.all(
+                ts.iter()
+                    .enumerate()
+                    .map(|(i, t)| self.eq_value(t, &extend(path, Step::Element(i, ts.len()))))
+                    .collect(),
+            ),
+            _ => self.expr(ExprKind::BinOp(
+                Box::new(self.access(X, path)),
+                BinOpcode::Equal,
+                Box::new(self.access(Y, path)),
+            )),
+        }
+    }
+
+    /// `compare` orders variants by their position in the declaration, then by
+    /// their arguments, and records by their fields in order. Tuples are ordered
+    /// lexicographically
+    fn compare<'input>(&self, decl: &TypeDecl<'input>) -> Vec<Arm<'input>> {
+        if decl.kind == TypeKind::Record {
+            let fields = decl.variants.iter().map(|&(field, ref t)| (t, vec![Step::Field(field)])).collect();
+            return vec![(vec![self.bind(X), self.bind(Y)], None, self.lexicographic(fields))];
+        }
+        let mut arms = Vec::new();
+        for (i, &(constructor, ref t)) in decl.variants.iter().enumerate() {
+            let pats = vec![self.variant(constructor, self.bind(X)), self.variant(constructor, self.bind(Y))];
+            arms.push((pats, None, self.compare_value(t, &[])));
+            // the last variant is the only one left
+            if i + 1 < decl.variants.len() {
+                arms.push((vec![self.variant(constructor, self.wild()), self.wild()], None, self.int(-1)));
+                arms.push((vec![self.wild(), self.variant(constructor, self.wild())], None, self.int(1)));
+            }
+        }
+        arms
+    }
+
+    /// the comparison of the parts of X and Y at path of type t
+    fn compare_value<'input>(&self, t: &Type, path: &[Step<'input>]) -> Expr<'input> {
+        match t {
+            Type::Unit => self.int(0),
+            Type::Tuple(ts) => self.lexicographic(
+                ts.iter()
+                    .enumerate()
+                    .map(|(i, t)| (t, extend(path, Step::Element(i, ts.len()))))
+                    .collect(),
+            ),
+            _ => {
+                let compare = self.apply(self.bound(COMPARE), self.access(X, path));
+                self.apply(compare, self.access(Y, path))
+            }
+        }
+    }
+
+    /// the comparison of the first parts that aren't equal, e.g.
+    /// `let #c = compare x0 y0 in if #c = 0 then compare x1 y1 else #c`
+    fn lexicographic<'input>(&self, mut parts: Vec<(&Type, Vec<Step<'input>>)>) -> Expr<'input> {
+        let (t, path) = parts.remove(0);
+        let first = self.compare_value(t, &path);
+        if parts.is_empty() {
+            return first;
+        }
+        let equal = self.expr(ExprKind::BinOp(Box::new(self.bound(C)), BinOpcode::Equal, Box::new(self.int(0))));
+        let rest = self.lexicographic(parts);
+        let cond = self.expr(ExprKind::Conditional(Box::new(equal), Box::new(rest), Box::new(self.bound(C))));
+        self.expr(ExprKind::Let(self.bind(C), Box::new(first), Box::new(cond)))
+    }
+
+    /// the part of the value bound to root at path, taken out by a closure
+    /// matching each step, e.g. `{ (_, #v) => #v } #x`
+    fn access<'input>(&self, root: &'input str, path: &[Step<'input>]) -> Expr<'input> {
+        let mut e = self.bound(root);
+        for &step in path {
+            let pat = match step {
+                Step::Element(i, n) => {
+                    let elements = (0..n).map(|j| if i == j { self.bind(V) } else { self.wild() }).collect();
+                    PatternKind::Tuple(elements)
+                }
+                Step::Field(field) => PatternKind::Record(vec![(field, self.bind(V), self.span)]),
+            };
+            let project = self.expr(ExprKind::Closure(vec![(vec![self.pattern(pat)], None, self.bound(V))]));
+            e = self.apply(project, e);
+        }
+        e
+    }
+
+    /// the conjunction of all conds
+    fn all<'input>(&self, conds: Vec<Expr<'input>>) -> Expr<'input> {
+        conds
+            .into_iter()
+            .reduce(|e1, e2| self.expr(ExprKind::BinOp(Box::new(e1), BinOpcode::And, Box::new(e2))))
+            .expect("a tuple or record isn't empty")
+    }
+
+    /// the concatenation of all parts
+    fn concat<'input>(&self, parts: Vec<Expr<'input>>) -> Expr<'input> {
+        parts
+            .into_iter()
+            .reduce(|e1, e2| self.expr(ExprKind::BinOp(Box::new(e1), BinOpcode::Concat, Box::new(e2))))
+            .expect("a string is made of at least one part")
+    }
+
+    fn apply<'input>(&self, f: Expr<'input>, arg: Expr<'input>) -> Expr<'input> {
+        self.expr(ExprKind::Application(Box::new(f), Box::new(arg)))
+    }
+
+    fn bound<'input>(&self, name: &'input str) -> Expr<'input> {
+        self.expr(ExprKind::Bound(name))
+    }
+
+    fn string<'input>(&self, s: Cow<'input, str>) -> Expr<'input> {
+        self.expr(ExprKind::Literal(Literal::String(s)))
+    }
+
+    fn int<'input>(&self, n: isize) -> Expr<'input> {
+        self.expr(ExprKind::Literal(Literal::Int(n)))
+    }
+
+    fn expr<'input>(&self, kind: ExprKind<'input>) -> Expr<'input> {
+        Expr { kind, span: self.span }
+    }
+
+    fn variant<'input>(&self, constructor: &'input str, arg: Pattern<'input>) -> Pattern<'input> {
+        self.pattern(PatternKind::SumVar(constructor, Box::new(arg)))
+    }
+
+    fn bind<'input>(&self, name: &'input str) -> Pattern<'input> {
+        self.pattern(PatternKind::Bind(name))
+    }
+
+    fn wild<'input>(&self) -> Pattern<'input> {
+        self.pattern(PatternKind::Wild)
+    }
+
+    fn pattern<'input>(&self, kind: PatternKind<'input>) -> Pattern<'input> {
+        Pattern { kind, span: self.span }
+    }
+}
+
+/// path followed by step
+fn extend<'input>(path: &[Step<'input>], step: Step<'input>) -> Vec<Step<'input>> {
+    let mut path = path.to_vec();
+    path.push(step);
+    path
+}
@@ -0,0 +1,190 @@
+//! Diagnostics reported by every phase (parser, type checker, interpreter) and
+//! a renderer that prints them against the source with a caret underline.
+
+use std::fmt;
+use crate::ast::Span;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let src = "let x = 1\nlet y = x + \"a\"\n";
+        let diag = Diagnostic::error("E0100", "mismatched types `int` and `string`".to_owned(), Span::new(18, 25))
+            .with_label(Span::new(4, 5), "x is bound here".to_owned())
+            .with_note("`+` is only defined on ints".to_owned());
+        assert_eq!(
+            diag.render(src, "test.mal"),
+            "error[E0100]: mismatched types `int` and `string`\n \
+              --> test.mal:2:9\n  \
+                |\n\
+             1 | let x = 1\n  \
+                |     - x is bound here\n\
+             2 | let y = x + \"a\"\n  \
+                |         ^^^^^^^\n  \
+                = note: `+` is only defined on ints\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multibyte() {
+        let src = "ليكن س = \"a\" + 1";
+        let start = src.find('"').unwrap();
+        let diag = Diagnostic::error("E0100", "mismatched types".to_owned(), Span::new(start, start + 3));
+        let rendered = diag.render(src, "t.س");
+        assert!(rendered.contains("--> t.س:1:10\n"));
+        assert!(rendered.ends_with("  |          ^^^\n"));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A secondary span with a message explaining its relation to the diagnostic
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// a stable code identifying the kind of the diagnostic e.g. E0100
+    pub code: &'static str,
+    pub message: String,
+    /// the span the diagnostic is about, underlined with carets
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic { severity, code, message, span, labels: vec![], notes: vec![] }
+    }
+
+    pub fn error(code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic::new(Severity::Warning, code, message, span)
+    }
+
+    pub fn with_label(mut self, span: Span, message: String) -> Self {
+        self.labels.push(Label { span, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Render the diagnostic against the source it was reported on, e.g.
+    /// ```text
+    /// error[E0100]: mismatched types `int` and `string`
+    ///  --> list.mal:3:9
+    ///   |
+    /// 3 | let x = 1 + "a"
+    ///   |         ^^^^^^^
+    ///   = note: ...
+    /// ```
+    pub fn render(&self, src: &str, file_name: &str) -> String {
+        let mut marks = vec![(self.span, '^', "")];
+        marks.extend(self.labels.iter().map(|l| (l.span, '-', l.message.as_str())));
+        let lines: Vec<_> = marks.iter().map(|(span, ..)| line_of(src, span.start)).collect();
+        let gutter = lines.iter().map(|l| l.number).max().unwrap_or(1).to_string().len();
+        let pad = " ".repeat(gutter);
+
+        let mut out = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        let primary = &lines[0];
+        out += &format!("{}--> {}:{}:{}\n", pad, file_name, primary.number, primary.column);
+        out += &format!("{} |\n", pad);
+
+        // print each line once, followed by the underlines of the marks on it
+        let mut order: Vec<usize> = (0..marks.len()).collect();
+        order.sort_by_key(|&i| (lines[i].number, marks[i].0.start));
+        let mut printed = None;
+        for i in order {
+            let (span, marker, message) = marks[i];
+            let line = &lines[i];
+            let text = &src[line.start..line.end];
+            if printed != Some(line.number) {
+                out += &format!("{:>width$} | {}\n", line.number, text, width = gutter);
+                printed = Some(line.number);
+            }
+            let start = span.start.min(src.len());
+            let col = start - line.start;
+            // keep tabs so the underline stays aligned with the printed line
+            let indent: String = text[..col]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let mut end = span.end.min(line.end).max(start);
+            while !src.is_char_boundary(end) {
+                end += 1;
+            }
+            let width = src[start..end].chars().count().max(1);
+            out += &format!("{} | {}{}", pad, indent, marker.to_string().repeat(width));
+            if !message.is_empty() {
+                out += " ";
+                out += message;
+            }
+            out += "\n";
+        }
+        for note in &self.notes {
+            out += &format!("{} = note: {}\n", pad, note);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// The line containing a byte offset
+struct Line {
+    /// line number starting from 1
+    number: usize,
+    /// column in characters starting from 1
+    column: usize,
+    /// byte offsets of the line's start and end (excluding the newline)
+    start: usize,
+    end: usize,
+}
+
+fn line_of(src: &str, offset: usize) -> Line {
+    let offset = offset.min(src.len());
+    let start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = src[offset..].find('\n').map_or(src.len(), |i| offset + i);
+    Line {
+        number: src[..start].matches('\n').count() + 1,
+        column: src[start..offset].chars().count() + 1,
+        start,
+        end,
+    }
+}
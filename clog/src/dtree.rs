@@ -29,9 +29,18 @@
 //!     modify all branches with C
 //! When T = exit or empty reached:
 //!     replace with signular(C, tail = exit(i), default = T)
+//!
+//! A pattern with a guard exits with a guard node instead, which falls through
+//! to T when the guard is false. Guard nodes are leaves to the patterns
+//! inserted after them, and a guarded pattern never makes a match exhaustive.
+//!
+//! A pattern with or-patterns is inserted once for each of its alternatives,
+//! exiting through an alternative node that records which one matched and the
+//! values of the names it binds at paths that differ between alternatives.
 
 use std::{
-    collections::{BTreeMap, HashMap}
+    collections::BTreeMap,
+    iter,
 };
 use crate::{
     imper_ast::ConstraintValue,
@@ -55,13 +64,13 @@ mod test {
                     0,
                     vec![
                         (ValPath::Local(vec![0]), ConstraintValue::Finite(1, 2)),
-                        (ValPath::Local(vec![1]), ConstraintValue::Finite(2, 2)),
+                        (ValPath::Local(vec![1]), ConstraintValue::Finite(0, 2)),
                     ],
                 ),
                 (
                     1,
                     vec![
-                        (ValPath::Local(vec![0]), ConstraintValue::Finite(2, 2)),
+                        (ValPath::Local(vec![0]), ConstraintValue::Finite(0, 2)),
                         (ValPath::Local(vec![1]), ConstraintValue::Finite(1, 2)),
                     ],
                 ),
@@ -82,18 +91,18 @@ mod test {
                 (
                     0,
                     vec![
-                        (ValPath::Local(vec![0, 0]), ConstraintValue::Finite(2, 3)),
+                        (ValPath::Local(vec![0, 0]), ConstraintValue::Finite(1, 3)),
                         (ValPath::Local(vec![0, 1]), ConstraintValue::Int(13)),
                     ],
                 ),
                 (1, vec![(ValPath::Local(vec![1]), ConstraintValue::Int(5))]),
                 (
                     2,
-                    vec![(ValPath::Local(vec![0, 0]), ConstraintValue::Finite(1, 3))],
+                    vec![(ValPath::Local(vec![0, 0]), ConstraintValue::Finite(0, 3))],
                 ),
                 (
                     3,
-                    vec![(ValPath::Local(vec![0, 0]), ConstraintValue::Finite(3, 3))],
+                    vec![(ValPath::Local(vec![0, 0]), ConstraintValue::Finite(2, 3))],
                 ),
                 (4, vec![]),
             ],
@@ -101,7 +110,7 @@ mod test {
 
         for set in all_tests {
             let mut tree = DTree::new();
-            let len = set.len() as u16;
+            let len = set.len();
             for (i, array) in set.into_iter().rev() {
                 let mut map = BTreeMap::new();
                 for (valpath, constraint) in array {
@@ -109,15 +118,277 @@ mod test {
                 }
                 tree.add_pattern(map, i)
             }
-            assert!(tree.is_sound_complete(len).is_ok());
+            assert!(tree.is_sound_complete(&vec![1; len]).is_ok());
+        }
+    }
+
+    fn tree(patterns: Vec<Vec<(ValPath, ConstraintValue<'static>)>>) -> DTree<'static> {
+        let mut tree = DTree::new();
+        for (i, pat) in patterns.into_iter().enumerate().rev() {
+            tree.add_pattern(pat.into_iter().collect(), i as u16);
+        }
+        tree
+    }
+
+    #[test]
+    fn test_non_exhaustive() {
+        /*
+        (ONE _) 0 -> 0
+        (TWO _) _ -> 1
+        */
+        let errs = tree(vec![
+            vec![
+                (ValPath::Local(vec![0, 0]), ConstraintValue::Finite(0, 3)),
+                (ValPath::Local(vec![1]), ConstraintValue::Int(0)),
+            ],
+            vec![(ValPath::Local(vec![0, 0]), ConstraintValue::Finite(1, 3))],
+        ])
+        .is_sound_complete(&[1; 2])
+        .unwrap_err();
+        match &errs[..] {
+            [PatternMatchErr::NonExhaustive(missing)] => assert_eq!(
+                missing,
+                &[
+                    (ValPath::Local(vec![0, 0]), ConstraintValue::Finite(0, 3)),
+                    (ValPath::Local(vec![1]), ConstraintValue::Int(1)),
+                ]
+            ),
+            _ => panic!("expected a missing case, found {:?}", errs),
+        }
+    }
+
+    #[test]
+    fn test_redundant() {
+        /*
+        _   "a" -> 0
+        true _  -> 1
+        _   "a" -> 2
+        false _ -> 3
+        */
+        let errs = tree(vec![
+            vec![(ValPath::Local(vec![1]), ConstraintValue::Str("a".into()))],
+            vec![(ValPath::Local(vec![0]), ConstraintValue::Finite(1, 2))],
+            vec![(ValPath::Local(vec![1]), ConstraintValue::Str("a".into()))],
+            vec![(ValPath::Local(vec![0]), ConstraintValue::Finite(0, 2))],
+            vec![],
+        ])
+        .is_sound_complete(&[1; 5])
+        .unwrap_err();
+        assert!(matches!(errs[..], [PatternMatchErr::Redundant(2), PatternMatchErr::Redundant(4)]));
+    }
+
+    #[test]
+    fn test_literal_after_default() {
+        /*
+        1 true -> 0
+        2 _    -> 1
+        _ _    -> 2
+        */
+        let result = tree(vec![
+            vec![
+                (ValPath::Local(vec![0]), ConstraintValue::Int(1)),
+                (ValPath::Local(vec![1]), ConstraintValue::Finite(1, 2)),
+            ],
+            vec![(ValPath::Local(vec![0]), ConstraintValue::Int(2))],
+            vec![],
+        ])
+        .is_sound_complete(&[1; 3]);
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_guards() {
+        /*
+        true when _ -> 0
+        _    when _ -> 1
+        false       -> 2
+        */
+        let local = |b| BTreeMap::from([(ValPath::Local(vec![0]), ConstraintValue::Finite(b, 2))]);
+        let mut tree = DTree::new();
+        tree.add_pattern(local(0), 2);
+        tree.add_guarded_pattern(BTreeMap::new(), 1);
+        tree.add_guarded_pattern(local(1), 0);
+        match &tree {
+            DTree::Finite { branches, .. } => match &branches[..] {
+                [DTree::Guard { arm: 1, otherwise: f }, DTree::Guard { arm: 0, otherwise: t }] => {
+                    assert!(matches!(**f, DTree::Exit(2)));
+                    assert!(matches!(**t, DTree::Guard { arm: 1, ref otherwise } if matches!(**otherwise, DTree::Empty)));
+                }
+                _ => panic!("expected guards on both branches, found {:?}", branches),
+            },
+            _ => panic!("expected a test of the bool, found {:?}", tree),
+        }
+        // true is only matched by guarded patterns
+        let errs = tree.is_sound_complete(&[1; 3]).unwrap_err();
+        match &errs[..] {
+            [PatternMatchErr::NonExhaustive(missing)] => {
+                assert_eq!(missing, &[(ValPath::Local(vec![0]), ConstraintValue::Finite(1, 2))])
+            }
+            _ => panic!("expected a missing case, found {:?}", errs),
+        }
+    }
+
+    #[test]
+    fn test_alternatives() {
+        /*
+        (x, 0) | (0, x)          -> 0
+        (1, _) | (2, _) | (1, _) -> 1
+        _                        -> 2
+        */
+        let local = |i, n| BTreeMap::from([(ValPath::Local(vec![0, i]), ConstraintValue::Int(n))]);
+        let mut tree = DTree::new();
+        tree.add_pattern(BTreeMap::new(), 2);
+        for (j, &n) in [1, 2, 1].iter().enumerate().rev() {
+            tree.add_alternative(local(0, n), 1, j as u16, vec![], false);
+        }
+        tree.add_alternative(local(0, 0), 0, 1, vec![ValPath::Local(vec![0, 1])], false);
+        tree.add_alternative(local(1, 0), 0, 0, vec![ValPath::Local(vec![0, 0])], false);
+        match &tree {
+            DTree::Infinite { branches, .. } => match &branches[0] {
+                (ConstraintValue::Int(0), DTree::Infinite { branches, default, .. }) => {
+                    // (0, 0) is matched by the first alternative
+                    assert!(matches!(
+                        branches[..],
+                        [(ConstraintValue::Int(0), DTree::Alternative { index: 0, ref bound, .. })]
+                            if bound == &[ValPath::Local(vec![0, 0])]
+                    ));
+                    assert!(matches!(
+                        **default,
+                        DTree::Alternative { index: 1, ref bound, .. } if bound == &[ValPath::Local(vec![0, 1])]
+                    ));
+                }
+                branch => panic!("expected a test of the second element, found {:?}", branch),
+            },
+            _ => panic!("expected a test of the first element, found {:?}", tree),
+        }
+        let errs = tree.is_sound_complete(&[2, 3, 1]).unwrap_err();
+        assert!(matches!(errs[..], [PatternMatchErr::RedundantAlternative(1, 2)]), "{:?}", errs);
+    }
+
+    #[test]
+    fn test_ranges() {
+        /*
+        1..10 -> 0
+        5..20 -> 1
+        */
+        let ints = tree(vec![
+            vec![(ValPath::Local(vec![0]), ConstraintValue::IntRange(1, 10))],
+            vec![(ValPath::Local(vec![0]), ConstraintValue::IntRange(5, 20))],
+        ]);
+        match &ints {
+            DTree::Infinite { branches, .. } => assert!(matches!(
+                branches[..],
+                [
+                    (ConstraintValue::IntRange(1, 4), DTree::Exit(0)),
+                    (ConstraintValue::IntRange(5, 10), DTree::Exit(0)),
+                    (ConstraintValue::IntRange(11, 20), DTree::Exit(1)),
+                ]
+            )),
+            _ => panic!("expected a test of the int, found {:?}", ints),
+        }
+        let errs = ints.is_sound_complete(&[1; 2]).unwrap_err();
+        match &errs[..] {
+            [PatternMatchErr::NonExhaustive(missing)] => {
+                assert_eq!(missing, &[(ValPath::Local(vec![0]), ConstraintValue::Int(0))])
+            }
+            _ => panic!("expected a missing case, found {:?}", errs),
+        }
+
+        /*
+        '0'..'9'          -> 0
+        '5'               -> 1
+        '\u{0}'..'/'      -> 2
+        ':'..'\u{10FFFF}' -> 3
+        */
+        let local = |c| vec![(ValPath::Local(vec![0]), c)];
+        let errs = tree(vec![
+            local(ConstraintValue::CharRange('0', '9')),
+            local(ConstraintValue::Char('5')),
+            local(ConstraintValue::CharRange('\u{0}', '/')),
+            local(ConstraintValue::CharRange(':', char::MAX)),
+        ])
+        .is_sound_complete(&[1; 4])
+        .unwrap_err();
+        // the ranges cover every char
+        assert!(matches!(errs[..], [PatternMatchErr::Redundant(1)]), "{:?}", errs);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        /*
+        "ab" ++ _ -> 0
+        "abc"     -> 1
+        "a" ++ _  -> 2
+        ""        -> 3
+        */
+        let local = |c| vec![(ValPath::Local(vec![0]), c)];
+        let strs = tree(vec![
+            local(ConstraintValue::StrPrefix("ab".into())),
+            local(ConstraintValue::Str("abc".into())),
+            local(ConstraintValue::StrPrefix("a".into())),
+            local(ConstraintValue::Str("".into())),
+        ]);
+        match &strs {
+            DTree::Infinite { branches, .. } => {
+                let tests: Vec<_> = branches.iter().map(|(test, _)| test.clone()).collect();
+                assert_eq!(
+                    tests,
+                    [
+                        ConstraintValue::Str("".into()),
+                        ConstraintValue::Str("abc".into()),
+                        ConstraintValue::StrPrefix("ab".into()),
+                        ConstraintValue::StrPrefix("a".into()),
+                    ]
+                );
+                // "abc" is tested before the prefix that includes it
+                assert!(matches!(branches[1].1, DTree::Exit(0)));
+            }
+            _ => panic!("expected a test of the string, found {:?}", strs),
+        }
+        let errs = strs.is_sound_complete(&[1; 4]).unwrap_err();
+        match &errs[..] {
+            [PatternMatchErr::NonExhaustive(missing), PatternMatchErr::Redundant(1)] => {
+                assert_eq!(missing, &[(ValPath::Local(vec![0]), ConstraintValue::Str("b".into()))])
+            }
+            _ => panic!("expected a missing case and a redundant pattern, found {:?}", errs),
+        }
+    }
+
+    #[test]
+    fn test_mixed_kinds() {
+        // after a type error, tests of the same value can be of different types.
+        // A test that doesn't fit the node built by the later patterns never passes
+        let local = |c| vec![(ValPath::Local(vec![0]), c)];
+        let bool_of = |test: ConstraintValue<'static>| {
+            vec![
+                vec![local(test.clone()), local(ConstraintValue::Finite(1, 2)), vec![]],
+                vec![local(ConstraintValue::Finite(1, 2)), local(test), vec![]],
+            ]
+        };
+        let cases = vec![
+            bool_of(ConstraintValue::Int(5)),
+            bool_of(ConstraintValue::Char('a')),
+            bool_of(ConstraintValue::IntRange(1, 3)),
+            bool_of(ConstraintValue::Str("a".into())),
+            vec![vec![local(ConstraintValue::Str("a".into())), local(ConstraintValue::Char('b')), vec![]]],
+            // the tags of a sum with three variants and of a bool
+            vec![vec![local(ConstraintValue::Finite(1, 3)), local(ConstraintValue::Finite(1, 2)), vec![]]],
+        ];
+        for patterns in cases.into_iter().flatten() {
+            let errs = tree(patterns).is_sound_complete(&[1; 3]).unwrap_err();
+            assert!(matches!(errs[..], [PatternMatchErr::Redundant(0)]), "{:?}", errs);
         }
     }
 }
 
-pub enum PatternMatchErr {
-    // FIXME: make private and convert to general error
+#[derive(Debug)]
+pub enum PatternMatchErr<'input> {
+    /// the nth pattern is never matched
     Redundant(u16),
-    NonExhaustive,
+    /// the mth alternative of the nth pattern is never matched, while others are
+    RedundantAlternative(u16, u16),
+    /// constraints on a value that no pattern matches
+    NonExhaustive(Vec<(ValPath, ConstraintValue<'input>)>),
 }
 
 /// A decision tree for pattern matching
@@ -127,6 +398,18 @@ pub enum DTree<'input> {
     Empty,
     /// nth pattern satisfied
     Exit(u16),
+    /// nth pattern satisfied if its guard is true, else the patterns after it are tried
+    Guard {
+        arm: u16,
+        otherwise: Box<DTree<'input>>,
+    },
+    /// the nth alternative of a pattern with or-patterns satisfied, binding the values
+    /// at the paths in bound to the next local slots, then exits with a guard or an exit
+    Alternative {
+        index: u16,
+        bound: Vec<ValPath>,
+        then: Box<DTree<'input>>,
+    },
     /// bool or tagged union
     Finite {
         /// value to test
@@ -134,17 +417,48 @@ pub enum DTree<'input> {
         /// branch for each possibility in the finite set
         branches: Vec<DTree<'input>>,
     },
-    /// integer or string
+    /// integer, char or string
     Infinite {
         /// value to test
         value: ValPath,
-        /// branch for each constrained value
-        branches: HashMap<ConstraintValue<'input>, DTree<'input>>,
+        /// the tests of the value, with the branch of the values passing each test
+        /// first. The ranges of ints and chars don't overlap, and the tests of strings
+        /// come before the shorter prefixes of theirs
+        branches: Vec<(ConstraintValue<'input>, DTree<'input>)>,
         /// default path if no value in map matched
         default: Box<DTree<'input>>,
     },
 }
 
+/// how a pattern inserted in a tree exits
+struct Leaf {
+    arm: u16,
+    guarded: bool,
+    /// the index of the alternative and the paths of the values it binds
+    alternative: Option<(u16, Vec<ValPath>)>,
+}
+
+impl Leaf {
+    /// the tree replacing the leaf otherwise when the pattern is matched
+    fn tail<'input>(&self, otherwise: &DTree<'input>) -> DTree<'input> {
+        let exit = if self.guarded {
+            DTree::Guard { arm: self.arm, otherwise: Box::new(otherwise.clone()) }
+        } else {
+            DTree::Exit(self.arm)
+        };
+        match self.alternative {
+            Some((index, ref bound)) => DTree::Alternative { index, bound: bound.clone(), then: Box::new(exit) },
+            None => exit,
+        }
+    }
+}
+
+impl<'input> Default for DTree<'input> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'input> DTree<'input> {
     pub fn new() -> Self {
         DTree::Empty
@@ -175,12 +489,10 @@ impl<'input> DTree<'input> {
                         branches,
                     };
                 }
-                Int(_) | Str(_) => {
-                    let mut branches = HashMap::new();
-                    branches.insert(consted.clone(), tail);
+                _ => {
                     tail = DTree::Infinite {
                         value: value.clone(),
-                        branches,
+                        branches: vec![(consted.clone(), tail)],
                         default: Box::new(default.clone()),
                     };
                 }
@@ -192,77 +504,354 @@ impl<'input> DTree<'input> {
     /// modify the tree to match the exit pattern when the constraints in map are met
     /// ### REQUIRES
     /// exit has higher precedence that patterns in self
-    pub fn add_pattern(&mut self, mut map: BTreeMap<ValPath, ConstraintValue<'input>>, exit: u16) {
+    pub fn add_pattern(&mut self, map: BTreeMap<ValPath, ConstraintValue<'input>>, exit: u16) {
+        self.insert(map, &Leaf { arm: exit, guarded: false, alternative: None })
+    }
+
+    /// like add_pattern, for a pattern that is only matched if its guard is true
+    pub fn add_guarded_pattern(&mut self, map: BTreeMap<ValPath, ConstraintValue<'input>>, exit: u16) {
+        self.insert(map, &Leaf { arm: exit, guarded: true, alternative: None })
+    }
+
+    /// like add_pattern, for the nth alternative of a pattern with or-patterns, which
+    /// binds the values at the paths in bound to the next local slots when matched
+    /// ### REQUIRES
+    /// the alternatives of a pattern are added in reverse order
+    pub fn add_alternative(
+        &mut self,
+        map: BTreeMap<ValPath, ConstraintValue<'input>>,
+        exit: u16,
+        n: u16,
+        bound: Vec<ValPath>,
+        guarded: bool,
+    ) {
+        self.insert(map, &Leaf { arm: exit, guarded, alternative: Some((n, bound)) })
+    }
+
+    fn insert(&mut self, mut map: BTreeMap<ValPath, ConstraintValue<'input>>, leaf: &Leaf) {
         use self::DTree::*;
         match *self {
-            Empty | Exit(_) => *self = Self::make_tree(&map, Exit(exit), self),
+            Empty | Exit(_) | Guard { .. } | Alternative { .. } => {
+                let tail = leaf.tail(self);
+                *self = Self::make_tree(&map, tail, self)
+            }
             Finite { ref value, ref mut branches } if map.contains_key(value) => {
-                if let ConstraintValue::Finite(n, _) = map.remove(value).unwrap() {
-                    // !!!
-                    branches[n as usize].add_pattern(map, exit)
-                } else {
-                    panic!("infinite & finite contradiction")
+                match map.remove(value).unwrap() {
+                    ConstraintValue::Finite(n, m) if m as usize == branches.len() => {
+                        branches[n as usize].insert(map, leaf)
+                    }
+                    // a test of a value of another type, after a type error, never passes
+                    _ => {}
                 }
             }
             Finite { ref mut branches, .. } => {
                 for branch in branches {
-                    branch.add_pattern(map.clone(), exit)
+                    branch.insert(map.clone(), leaf)
                 }
             }
-            Infinite { ref value, ref mut branches, .. } if map.contains_key(value) => {
-                let key = map.remove(value).unwrap();
-                // logically this is
-                // if let _ = branches.get_mut() { add pattern } else { insert branch }
-                // but branches remains borrowed in else part, hence this structure
-                if let Some(dtree) = branches.get_mut(&key) {
-                    dtree.add_pattern(map, exit);
-                } else {
-                    branches.insert(key, Self::make_tree(&map, Exit(exit), &DTree::Empty));
+            Infinite { ref value, ref mut branches, ref default } if map.contains_key(value) => {
+                let test = map.remove(value).unwrap();
+                if same_kind(&test, &branches[0].0) {
+                    Self::insert_test(branches, default, test, &map, leaf)
                 }
             }
             Infinite { ref mut branches, ref mut default, .. } => {
-                for branch in branches.values_mut() {
-                    branch.add_pattern(map.clone(), exit);
+                for (_, branch) in branches {
+                    branch.insert(map.clone(), leaf);
                 }
-                default.add_pattern(map, exit);
+                default.insert(map, leaf);
             }
         }
     }
 
-    /// check whether a decision tree is exhaustive and non-repetitive
-    pub fn is_sound_complete(&self, num_pats: u16) -> Result<(), PatternMatchErr> {
-        let mut flags = vec![false; num_pats as usize];
-        if self.check_tree(&mut flags) {
-            for (i, p) in flags.iter().enumerate() {
+    /// insert the pattern in the branches of the values passing test. The branches
+    /// of the values partly passing it are split, and those passing it that took the
+    /// default get new branches refining it
+    fn insert_test(
+        branches: &mut Vec<(ConstraintValue<'input>, DTree<'input>)>,
+        default: &DTree<'input>,
+        test: ConstraintValue<'input>,
+        map: &BTreeMap<ValPath, ConstraintValue<'input>>,
+        leaf: &Leaf,
+    ) {
+        let refined = |tree: &DTree<'input>| {
+            let mut tree = tree.clone();
+            tree.insert(map.clone(), leaf);
+            tree
+        };
+        match Interval::of(&test) {
+            Some(interval) => {
+                let mut rest = vec![interval];
+                let mut split = Vec::new();
+                for (t, branch) in branches.drain(..) {
+                    let t = Interval::of(&t).expect("tests of a node are of the same kind");
+                    rest = rest.into_iter().flat_map(|r| r.minus(t)).collect();
+                    match t.intersect(interval) {
+                        None => split.push((t.constraint(), branch)),
+                        Some(common) => {
+                            for outside in t.minus(interval) {
+                                split.push((outside.constraint(), branch.clone()));
+                            }
+                            split.push((common.constraint(), refined(&branch)));
+                        }
+                    }
+                }
+                split.extend(rest.into_iter().map(|r| (r.constraint(), refined(default))));
+                split.sort_by_key(|(t, _)| Interval::of(t).map(|t| t.lo));
+                *branches = split;
+            }
+            // a test of a string either includes another, is included in it or is
+            // disjoint from it
+            None => {
+                for i in 0..branches.len() {
+                    let t = &branches[i].0;
+                    if includes(&test, t) {
+                        let same = includes(t, &test);
+                        branches[i].1.insert(map.clone(), leaf);
+                        if same {
+                            return;
+                        }
+                    } else if includes(t, &test) {
+                        let branch = refined(&branches[i].1);
+                        branches.insert(i, (test, branch));
+                        return;
+                    }
+                }
+                branches.push((test, refined(default)));
+            }
+        }
+    }
+
+    /// check whether a decision tree is exhaustive and non-repetitive, given the
+    /// number of alternatives of each pattern. Reports every pattern that is never
+    /// matched, the never matched alternatives of the others, and a value no pattern
+    /// matches
+    pub fn is_sound_complete(&self, alternatives: &[u16]) -> Result<(), Vec<PatternMatchErr<'input>>> {
+        let mut flags: Vec<_> = alternatives.iter().map(|&n| vec![false; n as usize]).collect();
+        let mut errs = Vec::new();
+        if !self.check_tree(&mut flags, 0) {
+            let mut missing = Vec::new();
+            self.find_empty(&mut missing);
+            errs.push(PatternMatchErr::NonExhaustive(missing));
+        }
+        for (i, alts) in flags.iter().enumerate() {
+            if !alts.contains(&true) {
+                errs.push(PatternMatchErr::Redundant(i as u16));
+                continue;
+            }
+            for (j, p) in alts.iter().enumerate() {
                 if !p {
-                    return Err(PatternMatchErr::Redundant(i as u16));
+                    errs.push(PatternMatchErr::RedundantAlternative(i as u16, j as u16));
                 }
             }
+        }
+        if errs.is_empty() {
             Ok(())
         } else {
-            Err(PatternMatchErr::NonExhaustive)
+            Err(errs)
         }
     }
 
-    /// check that dtree has no empty subtree, and sets flags for exits found
-    fn check_tree(&self, counter: &mut [bool]) -> bool {
+    /// check that dtree has no empty subtree, and sets flags for the alternatives of
+    /// the exits found, alt being the alternative of the exits below
+    fn check_tree(&self, counter: &mut [Vec<bool>], alt: u16) -> bool {
         use self::DTree::*;
         match *self {
             Empty => false,
             Exit(n) => {
-                counter[n as usize] = true;
+                counter[n as usize][alt as usize] = true;
                 true
             }
+            // the guard may be false, so it's only complete if the other patterns are
+            Guard { arm, ref otherwise } => {
+                counter[arm as usize][alt as usize] = true;
+                otherwise.check_tree(counter, 0)
+            }
+            Alternative { index, ref then, .. } => then.check_tree(counter, index),
+            // visit every branch, without short-circuiting, to flag all the reachable exits
             Finite { ref branches, .. } => {
-                branches.iter().map(|b| b.check_tree(counter)).all(|p| p)
+                let mut complete = true;
+                for branch in branches {
+                    complete &= branch.check_tree(counter, alt);
+                }
+                complete
             }
+            // the default is only taken if the tests don't cover every value
             Infinite { ref branches, ref default, .. } => {
-                let res = branches
-                    .iter()
-                    .map(|(_, b)| b.check_tree(counter))
-                    .all(|res| res);
-                res && default.check_tree(counter)
+                let mut complete = unconstrained(branches).is_none() || default.check_tree(counter, alt);
+                for (_, branch) in branches {
+                    complete &= branch.check_tree(counter, alt);
+                }
+                complete
             }
         }
     }
+
+    /// push the constraints on the path to an empty subtree to path
+    /// ### RETURNS
+    /// whether an empty subtree was found
+    fn find_empty(&self, path: &mut Vec<(ValPath, ConstraintValue<'input>)>) -> bool {
+        use self::DTree::*;
+        match *self {
+            Empty => true,
+            Exit(_) => false,
+            Guard { ref otherwise, .. } => otherwise.find_empty(path),
+            Alternative { ref then, .. } => then.find_empty(path),
+            Finite { ref value, ref branches } => {
+                for (i, branch) in branches.iter().enumerate() {
+                    path.push((value.clone(), ConstraintValue::Finite(i as u16, branches.len() as u16)));
+                    if branch.find_empty(path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+            Infinite { ref value, ref branches, ref default } => {
+                // the default is taken by a value that passes none of the tests
+                if let Some(missing) = unconstrained(branches) {
+                    path.push((value.clone(), missing));
+                    if default.find_empty(path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                for (constraint, branch) in branches {
+                    path.push((value.clone(), constraint.clone()));
+                    if branch.find_empty(path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+        }
+    }
+}
+
+/// a value that passes none of the tests of an infinite node, preferring 0, `'a'`
+/// and short strings, if there's any
+fn unconstrained<'input>(branches: &[(ConstraintValue<'input>, DTree<'input>)]) -> Option<ConstraintValue<'input>> {
+    let tests = || branches.iter().map(|(test, _)| test);
+    match branches.first().and_then(|(test, _)| Interval::of(test)) {
+        Some(first) => {
+            let intervals: Vec<_> = tests().filter_map(Interval::of).filter(|i| i.chars == first.chars).collect();
+            let full = Interval::full(first.chars);
+            let start = if first.chars { ord('a') } else { 0 };
+            // a value next to a range, the closest above the start first
+            let mut above: Vec<_> = intervals.iter().filter_map(|i| i.hi.checked_add(1)).filter(|&n| n > start).collect();
+            let mut below: Vec<_> = intervals.iter().filter_map(|i| i.lo.checked_sub(1)).filter(|&n| n < start).collect();
+            above.sort_unstable();
+            below.sort_unstable_by(|a, b| b.cmp(a));
+            iter::once(start)
+                .chain(above)
+                .chain(below)
+                .filter(|&n| full.lo <= n && n <= full.hi)
+                .find(|&n| !intervals.iter().any(|i| i.lo <= n && n <= i.hi))
+                .map(|n| Interval { lo: n, hi: n, ..first }.constraint())
+        }
+        None if tests().any(|t| *t == ConstraintValue::StrPrefix("".into())) => None,
+        None => iter::once(String::new())
+            .chain(('a'..=char::MAX).map(String::from))
+            .find(|s| !tests().any(|t| t.matches_str(s)))
+            .map(|s| ConstraintValue::Str(s.into())),
+    }
+}
+
+/// whether two tests are of values of the same type, which they are unless the
+/// patterns have type errors
+fn same_kind(a: &ConstraintValue, b: &ConstraintValue) -> bool {
+    use self::ConstraintValue::*;
+    match (a, b) {
+        (Finite(_, n), Finite(_, m)) => n == m,
+        (Int(_) | IntRange(..), Int(_) | IntRange(..))
+        | (Char(_) | CharRange(..), Char(_) | CharRange(..))
+        | (Str(_) | StrPrefix(_), Str(_) | StrPrefix(_)) => true,
+        _ => false,
+    }
+}
+
+/// whether every string passing inner passes outer, for tests of strings
+fn includes(outer: &ConstraintValue, inner: &ConstraintValue) -> bool {
+    use self::ConstraintValue::*;
+    match (outer, inner) {
+        (StrPrefix(prefix), Str(s) | StrPrefix(s)) => s.starts_with(&**prefix),
+        (Str(s), Str(t)) => s == t,
+        _ => false,
+    }
+}
+
+/// The ints or chars passing a test, from lo to hi inclusive. Chars are numbered
+/// skipping the surrogates, which aren't chars, so that adjacent chars are
+/// numbered consecutively
+#[derive(Clone, Copy, Debug)]
+struct Interval {
+    lo: isize,
+    hi: isize,
+    chars: bool,
+}
+
+impl Interval {
+    fn of(test: &ConstraintValue) -> Option<Self> {
+        use self::ConstraintValue::*;
+        match *test {
+            Int(n) => Some(Interval { lo: n, hi: n, chars: false }),
+            IntRange(lo, hi) => Some(Interval { lo, hi, chars: false }),
+            Char(c) => Some(Interval { lo: ord(c), hi: ord(c), chars: true }),
+            CharRange(lo, hi) => Some(Interval { lo: ord(lo), hi: ord(hi), chars: true }),
+            _ => None,
+        }
+    }
+
+    /// every int or every char
+    fn full(chars: bool) -> Self {
+        if chars {
+            Interval { lo: 0, hi: ord(char::MAX), chars }
+        } else {
+            Interval { lo: isize::MIN, hi: isize::MAX, chars }
+        }
+    }
+
+    fn constraint<'input>(self) -> ConstraintValue<'input> {
+        use self::ConstraintValue::*;
+        match (self.chars, self.lo == self.hi) {
+            (false, true) => Int(self.lo),
+            (false, false) => IntRange(self.lo, self.hi),
+            (true, true) => Char(chr(self.lo)),
+            (true, false) => CharRange(chr(self.lo), chr(self.hi)),
+        }
+    }
+
+    fn intersect(self, other: Self) -> Option<Self> {
+        let (lo, hi) = (self.lo.max(other.lo), self.hi.min(other.hi));
+        if lo <= hi {
+            Some(Interval { lo, hi, ..self })
+        } else {
+            None
+        }
+    }
+
+    /// the parts of self outside other
+    fn minus(self, other: Self) -> Vec<Self> {
+        let mut parts = Vec::new();
+        // other.lo - 1 and other.hi + 1 are only taken when they are in self, so they never overflow
+        if other.lo > self.lo {
+            parts.push(Interval { hi: self.hi.min(other.lo - 1), ..self });
+        }
+        if other.hi < self.hi {
+            parts.push(Interval { lo: self.lo.max(other.hi + 1), ..self });
+        }
+        parts
+    }
+}
+
+/// the number of a char, skipping the surrogates
+fn ord(c: char) -> isize {
+    let n = c as u32;
+    (if n > 0xDFFF { n - 0x800 } else { n }) as isize
+}
+
+fn chr(n: isize) -> char {
+    let n = n as u32;
+    char::from_u32(if n >= 0xD800 { n + 0x800 } else { n }).expect("numbers of chars are valid")
 }
\ No newline at end of file
@@ -1,27 +1,311 @@
 use std::{
     io::Error as ioErr,
     convert::From,
+    fmt,
+    sync::Arc,
+};
+use crate::{
+    ast::Span,
+    diagnostic::{Diagnostic, Severity},
+    types::{Type, TypeDecl},
 };
-use crate::types::Type;
 
 
-/// All errors from AST -> imperAST phase, not used yet!
+/// All errors from AST -> imperAST phase
 #[derive(Debug)]
 pub enum Error<'input> {
     IOErr(ioErr),
     ParseErr(usize),
-    TypeMismatch(Type, Type),
-    ConstructorUnification,
-    NameNotFound(&'input str),
-    MultBindPattern(&'input str),
-    ConstructorNotFound(&'input str),
-    NonConstAppPattern(&'input str),
-    TypeNotDefined(&'input str),
-    VariablePatsNum,
+    TypeMismatch(Type, Type, Span),
+    /// a variable unified with a type containing it
+    InfiniteType(Type, Type, Span),
+    ConstructorUnification(Span),
+    NameNotFound(&'input str, Span),
+    MultBindPattern(&'input str, Span),
+    ConstructorNotFound(&'input str, Span),
+    NonConstAppPattern(&'input str, Span),
+    TypeNotDefined(&'input str, Span),
+    /// a type applied to the wrong number of arguments, expected and found
+    TypeArity(&'input str, u16, usize, Span),
+    /// a constructor name already used by a type
+    DuplicateConstructor(&'input str, Span),
+    DuplicateType(&'input str, Span),
+    /// an alias that expands to itself, with the aliases it expands through
+    CyclicAlias(Vec<&'input str>, Span),
+    /// a field that isn't in any record
+    FieldNotFound(&'input str, Span),
+    /// a field of another record, with the name of the record
+    UnknownField(&'input str, &'input str, Span),
+    /// a field of the record left out of its literal, with the name of the record
+    MissingField(&'input str, &'input str, Span),
+    DuplicateField(&'input str, Span),
+    /// fields that two records, named, both have, e.g. a literal of either
+    AmbiguousRecord(&'input str, &'input str, Span),
+    /// `e.name` where the type of e has no such field or method
+    MemberNotFound(Type, &'input str, Span),
+    /// `e.name` where the type of e isn't known and no single type has the member
+    UnknownReceiver(&'input str, Span),
+    DuplicateMethod(&'input str, Span),
+    /// a trait used at a type without an instance, with the name of the trait
+    NoInstance(&'input str, Type, Span),
+    /// a trait used at a type that is never inferred
+    AmbiguousInstance(&'input str, Span),
+    TraitNotDefined(&'input str, Span),
+    DuplicateTrait(&'input str, Span),
+    /// a second instance of the trait for the same type
+    DuplicateInstance(&'input str, Span),
+    /// a method of the trait left out of an instance, with the name of the trait
+    MissingMethod(&'input str, &'input str, Span),
+    /// a method that isn't in the trait of the instance, with the name of the trait
+    UnknownMethod(&'input str, &'input str, Span),
+    /// an instance whose methods only work for some of the types its head covers
+    InstanceNotGeneral(&'input str, Span),
+    /// a type used as a trait type without one of its methods, named last
+    NotSubtype(Type, Type, Arc<str>, Span),
+    /// a name in `deriving` other than show, eq and ord
+    NotDerivable(&'input str, Span),
+    /// `=` or an ordering used at a type with functions in its values
+    Incomparable(Type, Span),
+    /// a name bound by some alternatives of an or-pattern, with the span of one that doesn't
+    UnboundInAlternative(&'input str, Span),
+    /// a range pattern whose lower bound is greater than its upper bound
+    EmptyRange(Span),
+    /// a local name used at another type than its first use, which isn't generalised
+    /// because its binding uses traits, with the types of the uses
+    NotGeneralised(&'input str, Type, Type, Span),
+    VariablePatsNum(Span),
+    /// a construct the parser accepts but the checker doesn't support yet
+    Unsupported(&'static str, Span),
+    /// a match with a counterexample pattern no arm matches
+    NonExhaustive(String, Span),
+    /// an arm that is never matched, only a warning
+    RedundantArm(Span),
+}
+
+impl<'input> Error<'input> {
+    /// stable code of the error reported in diagnostics
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::IOErr(_) => "E0000",
+            Error::ParseErr(_) => "E0001",
+            Error::TypeMismatch(..) => "E0100",
+            Error::ConstructorUnification(_) => "E0101",
+            Error::NameNotFound(..) => "E0102",
+            Error::MultBindPattern(..) => "E0103",
+            Error::ConstructorNotFound(..) => "E0104",
+            Error::NonConstAppPattern(..) => "E0105",
+            Error::TypeNotDefined(..) => "E0106",
+            Error::VariablePatsNum(_) => "E0107",
+            Error::Unsupported(..) => "E0108",
+            Error::InfiniteType(..) => "E0109",
+            Error::NonExhaustive(..) => "E0110",
+            Error::RedundantArm(_) => "E0111",
+            Error::TypeArity(..) => "E0112",
+            Error::DuplicateConstructor(..) => "E0113",
+            Error::DuplicateType(..) => "E0114",
+            Error::CyclicAlias(..) => "E0135",
+            Error::AmbiguousRecord(..) => "E0136",
+            Error::FieldNotFound(..) => "E0115",
+            Error::UnknownField(..) => "E0116",
+            Error::MissingField(..) => "E0117",
+            Error::DuplicateField(..) => "E0118",
+            Error::MemberNotFound(..) => "E0119",
+            Error::UnknownReceiver(..) => "E0120",
+            Error::DuplicateMethod(..) => "E0121",
+            Error::NoInstance(..) => "E0122",
+            Error::AmbiguousInstance(..) => "E0123",
+            Error::TraitNotDefined(..) => "E0124",
+            Error::DuplicateTrait(..) => "E0125",
+            Error::DuplicateInstance(..) => "E0126",
+            Error::MissingMethod(..) => "E0127",
+            Error::UnknownMethod(..) => "E0128",
+            Error::InstanceNotGeneral(..) => "E0129",
+            Error::NotSubtype(..) => "E0130",
+            Error::NotDerivable(..) => "E0131",
+            Error::Incomparable(..) => "E0132",
+            Error::UnboundInAlternative(..) => "E0133",
+            Error::EmptyRange(_) => "E0134",
+            Error::NotGeneralised(..) => "E0135",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Error::RedundantArm(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match *self {
+            Error::IOErr(_) => Span::default(),
+            Error::ParseErr(offset) => Span::new(offset, offset + 1),
+            Error::TypeMismatch(_, _, span)
+            | Error::InfiniteType(_, _, span)
+            | Error::ConstructorUnification(span)
+            | Error::NameNotFound(_, span)
+            | Error::MultBindPattern(_, span)
+            | Error::ConstructorNotFound(_, span)
+            | Error::NonConstAppPattern(_, span)
+            | Error::VariablePatsNum(span)
+            | Error::EmptyRange(span)
+            | Error::Unsupported(_, span)
+            | Error::NonExhaustive(_, span)
+            | Error::RedundantArm(span)
+            | Error::TypeNotDefined(_, span)
+            | Error::TypeArity(.., span)
+            | Error::DuplicateConstructor(_, span)
+            | Error::DuplicateType(_, span)
+            | Error::CyclicAlias(_, span)
+            | Error::FieldNotFound(_, span)
+            | Error::UnknownField(_, _, span)
+            | Error::MissingField(_, _, span)
+            | Error::DuplicateField(_, span)
+            | Error::AmbiguousRecord(_, _, span)
+            | Error::MemberNotFound(_, _, span)
+            | Error::UnknownReceiver(_, span)
+            | Error::DuplicateMethod(_, span)
+            | Error::NoInstance(_, _, span)
+            | Error::AmbiguousInstance(_, span)
+            | Error::TraitNotDefined(_, span)
+            | Error::DuplicateTrait(_, span)
+            | Error::DuplicateInstance(_, span)
+            | Error::MissingMethod(_, _, span)
+            | Error::UnknownMethod(_, _, span)
+            | Error::InstanceNotGeneral(_, span)
+            | Error::NotSubtype(.., span)
+            | Error::NotDerivable(_, span)
+            | Error::Incomparable(_, span)
+            | Error::UnboundInAlternative(_, span)
+            | Error::NotGeneralised(.., span) => span,
+        }
+    }
+
+    /// convert to a diagnostic, printing types with their declared names and their
+    /// variables named in order of appearance
+    pub fn to_diagnostic(&self, types: &[TypeDecl]) -> Diagnostic {
+        let names = |ts: &[&Type]| {
+            let mut ts: Vec<Type> = ts.iter().map(|&t| t.clone()).collect();
+            Type::name_vars(&mut ts);
+            ts.iter()
+                .map(|t| {
+                    let mut s = String::new();
+                    t.pretty_format(&mut s, types);
+                    s
+                })
+                .collect::<Vec<_>>()
+        };
+        let message = match self {
+            Error::TypeMismatch(t1, t2, _) => {
+                let s = names(&[t1, t2]);
+                format!("mismatched types `{}` and `{}`", s[0], s[1])
+            }
+            Error::InfiniteType(var, t, _) => {
+                let s = names(&[var, t]);
+                format!("cannot construct the infinite type `{} = {}`", s[0], s[1])
+            }
+            Error::MemberNotFound(t, name, _) => {
+                format!("no field or method `{}` on type `{}`", name, names(&[t])[0])
+            }
+            Error::NoInstance(name, t, _) => {
+                format!("no instance of `{}` for type `{}`", name, names(&[t])[0])
+            }
+            Error::NotSubtype(t1, t2, method, _) => {
+                let s = names(&[t1, t2]);
+                format!("type `{}` is not a subtype of `{}`, it has no method `{}`", s[0], s[1], method)
+            }
+            Error::Incomparable(t, _) => {
+                format!("values of type `{}` contain functions and can't be compared", names(&[t])[0])
+            }
+            Error::NotGeneralised(name, t1, t2, _) => {
+                let s = names(&[t1, t2]);
+                format!(
+                    "`{}` is used at types `{}` and `{}`, a local binding that uses traits isn't generic",
+                    name, s[0], s[1]
+                )
+            }
+            _ => self.to_string(),
+        };
+        Diagnostic::new(self.severity(), self.code(), message, self.span())
+    }
+}
+
+impl<'input> fmt::Display for Error<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IOErr(e) => write!(f, "{}", e),
+            Error::ParseErr(_) => write!(f, "syntax error"),
+            Error::TypeMismatch(t1, t2, _) => write!(f, "mismatched types `{:?}` and `{:?}`", t1, t2),
+            Error::InfiniteType(var, t, _) => write!(f, "cannot construct the infinite type `{:?} = {:?}`", var, t),
+            Error::ConstructorUnification(_) => write!(f, "constructor used where a value is expected"),
+            Error::NameNotFound(name, _) => write!(f, "cannot find value `{}` in this scope", name),
+            Error::MultBindPattern(name, _) => write!(f, "`{}` is bound more than once in the same pattern", name),
+            Error::ConstructorNotFound(name, _) => write!(f, "cannot find constructor `{}`", name),
+            Error::NonConstAppPattern(name, _) => write!(f, "`{}` is not a constructor", name),
+            Error::TypeNotDefined(name, _) => write!(f, "cannot find type `{}`", name),
+            Error::TypeArity(name, expected, found, _) => write!(
+                f,
+                "type `{}` expects {} type argument{}, found {}",
+                name,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found
+            ),
+            Error::DuplicateConstructor(name, _) => write!(f, "constructor `{}` is defined more than once", name),
+            Error::DuplicateType(name, _) => write!(f, "type `{}` is defined more than once", name),
+            Error::CyclicAlias(cycle, _) => {
+                write!(f, "type alias `{}` expands to itself", cycle[0])?;
+                if cycle.len() > 1 {
+                    write!(f, " through `{}`", cycle[1..].join("`, `"))?;
+                }
+                Ok(())
+            }
+            Error::FieldNotFound(name, _) => write!(f, "cannot find field `{}`", name),
+            Error::UnknownField(record, name, _) => write!(f, "record `{}` has no field `{}`", record, name),
+            Error::MissingField(record, name, _) => write!(f, "missing field `{}` of record `{}`", name, record),
+            Error::DuplicateField(name, _) => write!(f, "field `{}` is specified more than once", name),
+            Error::AmbiguousRecord(first, second, _) => {
+                write!(f, "the fields could be of record `{}` or `{}`", first, second)
+            }
+            Error::MemberNotFound(t, name, _) => write!(f, "no field or method `{}` on type `{:?}`", name, t),
+            Error::UnknownReceiver(name, _) => write!(f, "cannot infer the type of the receiver of `.{}`", name),
+            Error::DuplicateMethod(name, _) => write!(f, "method `{}` is defined more than once", name),
+            Error::NoInstance(name, t, _) => write!(f, "no instance of `{}` for type `{:?}`", name, t),
+            Error::AmbiguousInstance(name, _) => write!(f, "cannot infer the type of the instance of `{}` to use", name),
+            Error::TraitNotDefined(name, _) => write!(f, "cannot find trait `{}`", name),
+            Error::DuplicateTrait(name, _) => write!(f, "trait `{}` is defined more than once", name),
+            Error::DuplicateInstance(name, _) => write!(f, "conflicting instances of `{}` for the same type", name),
+            Error::MissingMethod(name, method, _) => write!(f, "missing method `{}` of trait `{}`", method, name),
+            Error::UnknownMethod(name, method, _) => write!(f, "trait `{}` has no method `{}`", name, method),
+            Error::InstanceNotGeneral(name, _) => {
+                write!(f, "the methods of the instance of `{}` are less general than its type", name)
+            }
+            Error::NotSubtype(t1, t2, method, _) => {
+                write!(f, "type `{:?}` is not a subtype of `{:?}`, it has no method `{}`", t1, t2, method)
+            }
+            Error::NotDerivable(name, _) => {
+                write!(f, "cannot derive `{}`, only `show`, `eq` and `ord` can be derived", name)
+            }
+            Error::Incomparable(t, _) => write!(f, "values of type `{:?}` contain functions and can't be compared", t),
+            Error::UnboundInAlternative(name, _) => {
+                write!(f, "`{}` isn't bound in every alternative of the pattern", name)
+            }
+            Error::EmptyRange(_) => write!(f, "the lower bound of the range pattern is greater than its upper bound"),
+            Error::NotGeneralised(name, t1, t2, _) => write!(
+                f,
+                "`{}` is used at types `{:?}` and `{:?}`, a local binding that uses traits isn't generic",
+                name, t1, t2
+            ),
+            Error::VariablePatsNum(_) => write!(f, "arms of a closure take different numbers of arguments"),
+            Error::Unsupported(what, _) => write!(f, "{} are not supported yet", what),
+            Error::NonExhaustive(pat, _) => write!(f, "non-exhaustive patterns: `{}` not covered", pat),
+            Error::RedundantArm(_) => write!(f, "unreachable pattern"),
+        }
+    }
 }
 
 impl<'input> From<ioErr> for Error<'input> {
     fn from(e: ioErr) -> Self {
         Error::IOErr(e)
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
-// auto-generated: "lalrpop 0.19.6"
-// sha3: c33c41de143d6d3f9a99eece92bb5a9dee91798575acffbb8a5915881375b
+// auto-generated: "lalrpop 0.19.12"
+// sha3: c97b1db1e0d35c998fa1ff2fa9e0a5cf7c3ecbf008fde34ad2aae7586fe93da9
+use std::borrow::Cow;
 use crate::{
     ast::*,
     types::{ProtoType, Literal, BinOpcode, UnOpcode}
@@ -13,8 +14,9 @@ extern crate alloc;
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
 mod __parse__Expr {
-    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens)]
+    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens, clippy::all)]
 
+    use std::borrow::Cow;
     use crate::{
     ast::*,
     types::{ProtoType, Literal, BinOpcode, UnOpcode}
@@ -54,8 +56,8 @@ mod __parse__Expr {
         Variant22(UnOpcode),
         Variant23(Vec<Binding<'input>>),
         Variant24(alloc::vec::Vec<Binding<'input>>),
-        Variant25((&'input str, ProtoType<'input>)),
-        Variant26(alloc::vec::Vec<(&'input str, ProtoType<'input>)>),
+        Variant25((&'input str, ProtoType<'input>, Span)),
+        Variant26(alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>),
         Variant27(core::option::Option<Vec<&'input str>>),
     }
     const __ACTION: &[i16] = &[
@@ -2131,7 +2133,7 @@ mod __parse__Expr {
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, (&'input str, ProtoType<'input>), usize)
+    ) -> (usize, (&'input str, ProtoType<'input>, Span), usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant25(__v), __r)) => (__l, __v, __r),
@@ -2307,7 +2309,7 @@ mod __parse__Expr {
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize)
+    ) -> (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant26(__v), __r)) => (__l, __v, __r),
@@ -2792,10 +2794,10 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // @L =  => ActionFn(88);
+        // @L =  => ActionFn(99);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action88::<>(errors, input, &__start, &__end);
+        let __nt = super::__action99::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant12(__nt), __end));
         (0, 11)
     }
@@ -2810,10 +2812,10 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // @R =  => ActionFn(87);
+        // @R =  => ActionFn(94);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action87::<>(errors, input, &__start, &__end);
+        let __nt = super::__action94::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant12(__nt), __end));
         (0, 12)
     }
@@ -2887,7 +2889,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "لو", Expr, "إذن", Expr, "وإلا", Expr, "تم" => ActionFn(42);
+        // Base = "لو", Expr, "إذن", Expr, "وإلا", Expr, "تم" => ActionFn(164);
         assert!(__symbols.len() >= 7);
         let __sym6 = __pop_Variant0(__symbols);
         let __sym5 = __pop_Variant5(__symbols);
@@ -2898,7 +2900,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym6.2.clone();
-        let __nt = super::__action42::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
+        let __nt = super::__action164::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (7, 15)
     }
@@ -2913,14 +2915,14 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "{", Comma<Arm>, "}" => ActionFn(43);
+        // Base = "{", Comma<Arm>, "}" => ActionFn(165);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant17(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action43::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action165::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 15)
     }
@@ -2935,11 +2937,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = Literal => ActionFn(44);
+        // Base = Literal => ActionFn(166);
         let __sym0 = __pop_Variant20(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action44::<>(errors, input, __sym0);
+        let __nt = super::__action166::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -2954,11 +2956,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = ID => ActionFn(45);
+        // Base = ID => ActionFn(167);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action45::<>(errors, input, __sym0);
+        let __nt = super::__action167::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -2973,14 +2975,14 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "(", Comma2<Expr>, ")" => ActionFn(46);
+        // Base = "(", Comma2<Expr>, ")" => ActionFn(168);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant14(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action46::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action168::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 15)
     }
@@ -3017,11 +3019,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = error => ActionFn(146);
+        // Base = error => ActionFn(169);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action146::<>(errors, input, __sym0);
+        let __nt = super::__action169::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -3447,11 +3449,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = Arm => ActionFn(92);
+        // Comma<Arm> = Arm => ActionFn(90);
         let __sym0 = __pop_Variant3(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action92::<>(errors, input, __sym0);
+        let __nt = super::__action90::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (1, 26)
     }
@@ -3466,13 +3468,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = (<Arm> "،")+, Arm => ActionFn(149);
+        // Comma<Arm> = (<Arm> "،")+, Arm => ActionFn(185);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant3(__symbols);
         let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action149::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action185::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (2, 26)
     }
@@ -3487,11 +3489,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = (<Arm> "،")+ => ActionFn(150);
+        // Comma<Arm> = (<Arm> "،")+ => ActionFn(186);
         let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action150::<>(errors, input, __sym0);
+        let __nt = super::__action186::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (1, 26)
     }
@@ -3506,11 +3508,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = ID => ActionFn(94);
+        // Comma<ID> = ID => ActionFn(92);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action94::<>(errors, input, __sym0);
+        let __nt = super::__action92::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (1, 27)
     }
@@ -3525,13 +3527,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = (<ID> "،")+, ID => ActionFn(153);
+        // Comma<ID> = (<ID> "،")+, ID => ActionFn(189);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
         let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action153::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action189::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (2, 27)
     }
@@ -3546,11 +3548,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = (<ID> "،")+ => ActionFn(154);
+        // Comma<ID> = (<ID> "،")+ => ActionFn(190);
         let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action154::<>(errors, input, __sym0);
+        let __nt = super::__action190::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (1, 27)
     }
@@ -3565,7 +3567,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Expr = Expr, "[", Expr, "]" => ActionFn(33);
+        // Expr = Expr, "[", Expr, "]" => ActionFn(170);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant0(__symbols);
         let __sym2 = __pop_Variant5(__symbols);
@@ -3573,7 +3575,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action33::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action170::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (4, 28)
     }
@@ -3721,7 +3723,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // FnBinding = "رد", ID, "=", "{", Comma<Arm>, "}" => ActionFn(12);
+        // FnBinding = "رد", ID, "=", "{", Comma<Arm>, "}" => ActionFn(171);
         assert!(__symbols.len() >= 6);
         let __sym5 = __pop_Variant0(__symbols);
         let __sym4 = __pop_Variant17(__symbols);
@@ -3731,7 +3733,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym5.2.clone();
-        let __nt = super::__action12::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5);
+        let __nt = super::__action171::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (6, 35)
     }
@@ -4201,11 +4203,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = Literal => ActionFn(14);
+        // Pattern = Literal => ActionFn(172);
         let __sym0 = __pop_Variant20(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action14::<>(errors, input, __sym0);
+        let __nt = super::__action172::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -4220,11 +4222,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = "_" => ActionFn(15);
+        // Pattern = "_" => ActionFn(173);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action15::<>(errors, input, __sym0);
+        let __nt = super::__action173::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -4239,11 +4241,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = ID => ActionFn(16);
+        // Pattern = ID => ActionFn(174);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action16::<>(errors, input, __sym0);
+        let __nt = super::__action174::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -4258,14 +4260,14 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = "(", Comma2<PatternH>, ")" => ActionFn(17);
+        // Pattern = "(", Comma2<PatternH>, ")" => ActionFn(175);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant15(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action17::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action175::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (3, 45)
     }
@@ -4302,11 +4304,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = error => ActionFn(147);
+        // Pattern = error => ActionFn(176);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action147::<>(errors, input, __sym0);
+        let __nt = super::__action176::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -4321,11 +4323,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern+ = Pattern => ActionFn(90);
+        // Pattern+ = Pattern => ActionFn(88);
         let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action90::<>(errors, input, __sym0);
+        let __nt = super::__action88::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant9(__nt), __end));
         (1, 46)
     }
@@ -4340,13 +4342,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern+ = Pattern+, Pattern => ActionFn(91);
+        // Pattern+ = Pattern+, Pattern => ActionFn(89);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant8(__symbols);
         let __sym0 = __pop_Variant9(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action91::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action89::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant9(__nt), __end));
         (2, 46)
     }
@@ -4361,13 +4363,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // PatternH = ID, Pattern => ActionFn(20);
+        // PatternH = ID, Pattern => ActionFn(177);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant8(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action20::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action177::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (2, 47)
     }
@@ -4401,10 +4403,10 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Program =  => ActionFn(155);
+        // Program =  => ActionFn(191);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action155::<>(errors, input, &__start, &__end);
+        let __nt = super::__action191::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant23(__nt), __end));
         (0, 48)
     }
@@ -4419,11 +4421,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Program = Statement+ => ActionFn(156);
+        // Program = Statement+ => ActionFn(192);
         let __sym0 = __pop_Variant24(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action156::<>(errors, input, __sym0);
+        let __nt = super::__action192::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant23(__nt), __end));
         (1, 48)
     }
@@ -4536,11 +4538,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = ID => ActionFn(29);
+        // SimpleType = ID => ActionFn(178);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action29::<>(errors, input, __sym0);
+        let __nt = super::__action178::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (1, 49)
     }
@@ -4555,13 +4557,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = ID, SimpleType => ActionFn(30);
+        // SimpleType = ID, SimpleType => ActionFn(179);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant10(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action30::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action179::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (2, 49)
     }
@@ -4598,11 +4600,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = error => ActionFn(148);
+        // SimpleType = error => ActionFn(180);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action148::<>(errors, input, __sym0);
+        let __nt = super::__action180::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (1, 49)
     }
@@ -4751,14 +4753,14 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl = "|", ID, Type => ActionFn(10);
+        // SumVarDecl = "|", ID, Type => ActionFn(181);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant10(__symbols);
         let __sym1 = __pop_Variant0(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action10::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action181::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant25(__nt), __end));
         (3, 53)
     }
@@ -4773,11 +4775,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl+ = SumVarDecl => ActionFn(96);
+        // SumVarDecl+ = SumVarDecl => ActionFn(95);
         let __sym0 = __pop_Variant25(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action96::<>(errors, input, __sym0);
+        let __nt = super::__action95::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant26(__nt), __end));
         (1, 54)
     }
@@ -4792,13 +4794,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl+ = SumVarDecl+, SumVarDecl => ActionFn(97);
+        // SumVarDecl+ = SumVarDecl+, SumVarDecl => ActionFn(96);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant25(__symbols);
         let __sym0 = __pop_Variant26(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action97::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action96::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant26(__nt), __end));
         (2, 54)
     }
@@ -4813,11 +4815,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Term = Base => ActionFn(151);
+        // Term = Base => ActionFn(187);
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action151::<>(errors, input, __sym0);
+        let __nt = super::__action187::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 55)
     }
@@ -4832,13 +4834,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Term = Base, Base+ => ActionFn(152);
+        // Term = Base, Base+ => ActionFn(188);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant6(__symbols);
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action152::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action188::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 55)
     }
@@ -4894,7 +4896,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeDecl = "نمط", ID, TypeVars, "=", SumVarDecl+ => ActionFn(157);
+        // TypeDecl = "نمط", ID, TypeVars, "=", SumVarDecl+ => ActionFn(193);
         assert!(__symbols.len() >= 5);
         let __sym4 = __pop_Variant26(__symbols);
         let __sym3 = __pop_Variant0(__symbols);
@@ -4903,7 +4905,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym4.2.clone();
-        let __nt = super::__action157::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4);
+        let __nt = super::__action193::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (5, 57)
     }
@@ -4918,7 +4920,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeDecl = "نمط", ID, "=", SumVarDecl+ => ActionFn(158);
+        // TypeDecl = "نمط", ID, "=", SumVarDecl+ => ActionFn(194);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant26(__symbols);
         let __sym2 = __pop_Variant0(__symbols);
@@ -4926,7 +4928,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action158::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action194::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (4, 57)
     }
@@ -5001,11 +5003,11 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeVars? = TypeVars => ActionFn(98);
+        // TypeVars? = TypeVars => ActionFn(97);
         let __sym0 = __pop_Variant18(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action98::<>(errors, input, __sym0);
+        let __nt = super::__action97::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant27(__nt), __end));
         (1, 59)
     }
@@ -5020,10 +5022,10 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeVars? =  => ActionFn(99);
+        // TypeVars? =  => ActionFn(98);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action99::<>(errors, input, &__start, &__end);
+        let __nt = super::__action98::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant27(__nt), __end));
         (0, 59)
     }
@@ -5038,13 +5040,13 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // UnaryClass<Op5, Term> = Op5, UnaryClass<Op5, Term> => ActionFn(74);
+        // UnaryClass<Op5, Term> = Op5, UnaryClass<Op5, Term> => ActionFn(183);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant5(__symbols);
         let __sym0 = __pop_Variant22(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action74::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action183::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 60)
     }
@@ -5078,7 +5080,7 @@ mod __parse__Expr {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // ValBinding = "ليكن", Pattern, "=", Expr => ActionFn(11);
+        // ValBinding = "ليكن", Pattern, "=", Expr => ActionFn(184);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant5(__symbols);
         let __sym2 = __pop_Variant0(__symbols);
@@ -5086,7 +5088,7 @@ mod __parse__Expr {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action11::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action184::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (4, 61)
     }
@@ -5114,8 +5116,9 @@ pub use self::__parse__Expr::ExprParser;
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
 mod __parse__Program {
-    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens)]
+    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens, clippy::all)]
 
+    use std::borrow::Cow;
     use crate::{
     ast::*,
     types::{ProtoType, Literal, BinOpcode, UnOpcode}
@@ -5155,8 +5158,8 @@ mod __parse__Program {
         Variant22(UnOpcode),
         Variant23(Vec<Binding<'input>>),
         Variant24(alloc::vec::Vec<Binding<'input>>),
-        Variant25((&'input str, ProtoType<'input>)),
-        Variant26(alloc::vec::Vec<(&'input str, ProtoType<'input>)>),
+        Variant25((&'input str, ProtoType<'input>, Span)),
+        Variant26(alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>),
         Variant27(core::option::Option<Vec<&'input str>>),
     }
     const __ACTION: &[i16] = &[
@@ -7503,7 +7506,7 @@ mod __parse__Program {
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, (&'input str, ProtoType<'input>), usize)
+    ) -> (usize, (&'input str, ProtoType<'input>, Span), usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant25(__v), __r)) => (__l, __v, __r),
@@ -7679,7 +7682,7 @@ mod __parse__Program {
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize)
+    ) -> (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant26(__v), __r)) => (__l, __v, __r),
@@ -8164,10 +8167,10 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // @L =  => ActionFn(88);
+        // @L =  => ActionFn(99);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action88::<>(errors, input, &__start, &__end);
+        let __nt = super::__action99::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant12(__nt), __end));
         (0, 11)
     }
@@ -8182,10 +8185,10 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // @R =  => ActionFn(87);
+        // @R =  => ActionFn(94);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action87::<>(errors, input, &__start, &__end);
+        let __nt = super::__action94::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant12(__nt), __end));
         (0, 12)
     }
@@ -8259,7 +8262,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "لو", Expr, "إذن", Expr, "وإلا", Expr, "تم" => ActionFn(42);
+        // Base = "لو", Expr, "إذن", Expr, "وإلا", Expr, "تم" => ActionFn(164);
         assert!(__symbols.len() >= 7);
         let __sym6 = __pop_Variant0(__symbols);
         let __sym5 = __pop_Variant5(__symbols);
@@ -8270,7 +8273,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym6.2.clone();
-        let __nt = super::__action42::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
+        let __nt = super::__action164::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (7, 15)
     }
@@ -8285,14 +8288,14 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "{", Comma<Arm>, "}" => ActionFn(43);
+        // Base = "{", Comma<Arm>, "}" => ActionFn(165);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant17(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action43::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action165::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 15)
     }
@@ -8307,11 +8310,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = Literal => ActionFn(44);
+        // Base = Literal => ActionFn(166);
         let __sym0 = __pop_Variant20(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action44::<>(errors, input, __sym0);
+        let __nt = super::__action166::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -8326,11 +8329,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = ID => ActionFn(45);
+        // Base = ID => ActionFn(167);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action45::<>(errors, input, __sym0);
+        let __nt = super::__action167::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -8345,14 +8348,14 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = "(", Comma2<Expr>, ")" => ActionFn(46);
+        // Base = "(", Comma2<Expr>, ")" => ActionFn(168);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant14(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action46::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action168::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 15)
     }
@@ -8389,11 +8392,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Base = error => ActionFn(146);
+        // Base = error => ActionFn(169);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action146::<>(errors, input, __sym0);
+        let __nt = super::__action169::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 15)
     }
@@ -8819,11 +8822,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = Arm => ActionFn(92);
+        // Comma<Arm> = Arm => ActionFn(90);
         let __sym0 = __pop_Variant3(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action92::<>(errors, input, __sym0);
+        let __nt = super::__action90::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (1, 26)
     }
@@ -8838,13 +8841,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = (<Arm> "،")+, Arm => ActionFn(149);
+        // Comma<Arm> = (<Arm> "،")+, Arm => ActionFn(185);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant3(__symbols);
         let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action149::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action185::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (2, 26)
     }
@@ -8859,11 +8862,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<Arm> = (<Arm> "،")+ => ActionFn(150);
+        // Comma<Arm> = (<Arm> "،")+ => ActionFn(186);
         let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action150::<>(errors, input, __sym0);
+        let __nt = super::__action186::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant17(__nt), __end));
         (1, 26)
     }
@@ -8878,11 +8881,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = ID => ActionFn(94);
+        // Comma<ID> = ID => ActionFn(92);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action94::<>(errors, input, __sym0);
+        let __nt = super::__action92::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (1, 27)
     }
@@ -8897,13 +8900,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = (<ID> "،")+, ID => ActionFn(153);
+        // Comma<ID> = (<ID> "،")+, ID => ActionFn(189);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
         let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action153::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action189::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (2, 27)
     }
@@ -8918,11 +8921,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Comma<ID> = (<ID> "،")+ => ActionFn(154);
+        // Comma<ID> = (<ID> "،")+ => ActionFn(190);
         let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action154::<>(errors, input, __sym0);
+        let __nt = super::__action190::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant18(__nt), __end));
         (1, 27)
     }
@@ -8937,7 +8940,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Expr = Expr, "[", Expr, "]" => ActionFn(33);
+        // Expr = Expr, "[", Expr, "]" => ActionFn(170);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant0(__symbols);
         let __sym2 = __pop_Variant5(__symbols);
@@ -8945,7 +8948,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action33::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action170::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (4, 28)
     }
@@ -9093,7 +9096,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // FnBinding = "رد", ID, "=", "{", Comma<Arm>, "}" => ActionFn(12);
+        // FnBinding = "رد", ID, "=", "{", Comma<Arm>, "}" => ActionFn(171);
         assert!(__symbols.len() >= 6);
         let __sym5 = __pop_Variant0(__symbols);
         let __sym4 = __pop_Variant17(__symbols);
@@ -9103,7 +9106,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym5.2.clone();
-        let __nt = super::__action12::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5);
+        let __nt = super::__action171::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (6, 35)
     }
@@ -9573,11 +9576,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = Literal => ActionFn(14);
+        // Pattern = Literal => ActionFn(172);
         let __sym0 = __pop_Variant20(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action14::<>(errors, input, __sym0);
+        let __nt = super::__action172::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -9592,11 +9595,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = "_" => ActionFn(15);
+        // Pattern = "_" => ActionFn(173);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action15::<>(errors, input, __sym0);
+        let __nt = super::__action173::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -9611,11 +9614,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = ID => ActionFn(16);
+        // Pattern = ID => ActionFn(174);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action16::<>(errors, input, __sym0);
+        let __nt = super::__action174::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -9630,14 +9633,14 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = "(", Comma2<PatternH>, ")" => ActionFn(17);
+        // Pattern = "(", Comma2<PatternH>, ")" => ActionFn(175);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
         let __sym1 = __pop_Variant15(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action17::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action175::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (3, 45)
     }
@@ -9674,11 +9677,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern = error => ActionFn(147);
+        // Pattern = error => ActionFn(176);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action147::<>(errors, input, __sym0);
+        let __nt = super::__action176::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 45)
     }
@@ -9693,11 +9696,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern+ = Pattern => ActionFn(90);
+        // Pattern+ = Pattern => ActionFn(88);
         let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action90::<>(errors, input, __sym0);
+        let __nt = super::__action88::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant9(__nt), __end));
         (1, 46)
     }
@@ -9712,13 +9715,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Pattern+ = Pattern+, Pattern => ActionFn(91);
+        // Pattern+ = Pattern+, Pattern => ActionFn(89);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant8(__symbols);
         let __sym0 = __pop_Variant9(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action91::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action89::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant9(__nt), __end));
         (2, 46)
     }
@@ -9733,13 +9736,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // PatternH = ID, Pattern => ActionFn(20);
+        // PatternH = ID, Pattern => ActionFn(177);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant8(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action20::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action177::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (2, 47)
     }
@@ -9773,10 +9776,10 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Program =  => ActionFn(155);
+        // Program =  => ActionFn(191);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action155::<>(errors, input, &__start, &__end);
+        let __nt = super::__action191::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant23(__nt), __end));
         (0, 48)
     }
@@ -9791,11 +9794,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Program = Statement+ => ActionFn(156);
+        // Program = Statement+ => ActionFn(192);
         let __sym0 = __pop_Variant24(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action156::<>(errors, input, __sym0);
+        let __nt = super::__action192::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant23(__nt), __end));
         (1, 48)
     }
@@ -9908,11 +9911,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = ID => ActionFn(29);
+        // SimpleType = ID => ActionFn(178);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action29::<>(errors, input, __sym0);
+        let __nt = super::__action178::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (1, 49)
     }
@@ -9927,13 +9930,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = ID, SimpleType => ActionFn(30);
+        // SimpleType = ID, SimpleType => ActionFn(179);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant10(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action30::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action179::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (2, 49)
     }
@@ -9970,11 +9973,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SimpleType = error => ActionFn(148);
+        // SimpleType = error => ActionFn(180);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action148::<>(errors, input, __sym0);
+        let __nt = super::__action180::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant10(__nt), __end));
         (1, 49)
     }
@@ -10123,14 +10126,14 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl = "|", ID, Type => ActionFn(10);
+        // SumVarDecl = "|", ID, Type => ActionFn(181);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant10(__symbols);
         let __sym1 = __pop_Variant0(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action10::<>(errors, input, __sym0, __sym1, __sym2);
+        let __nt = super::__action181::<>(errors, input, __sym0, __sym1, __sym2);
         __symbols.push((__start, __Symbol::Variant25(__nt), __end));
         (3, 53)
     }
@@ -10145,11 +10148,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl+ = SumVarDecl => ActionFn(96);
+        // SumVarDecl+ = SumVarDecl => ActionFn(95);
         let __sym0 = __pop_Variant25(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action96::<>(errors, input, __sym0);
+        let __nt = super::__action95::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant26(__nt), __end));
         (1, 54)
     }
@@ -10164,13 +10167,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // SumVarDecl+ = SumVarDecl+, SumVarDecl => ActionFn(97);
+        // SumVarDecl+ = SumVarDecl+, SumVarDecl => ActionFn(96);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant25(__symbols);
         let __sym0 = __pop_Variant26(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action97::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action96::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant26(__nt), __end));
         (2, 54)
     }
@@ -10185,11 +10188,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Term = Base => ActionFn(151);
+        // Term = Base => ActionFn(187);
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action151::<>(errors, input, __sym0);
+        let __nt = super::__action187::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 55)
     }
@@ -10204,13 +10207,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // Term = Base, Base+ => ActionFn(152);
+        // Term = Base, Base+ => ActionFn(188);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant6(__symbols);
         let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action152::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action188::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 55)
     }
@@ -10266,7 +10269,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeDecl = "نمط", ID, TypeVars, "=", SumVarDecl+ => ActionFn(157);
+        // TypeDecl = "نمط", ID, TypeVars, "=", SumVarDecl+ => ActionFn(193);
         assert!(__symbols.len() >= 5);
         let __sym4 = __pop_Variant26(__symbols);
         let __sym3 = __pop_Variant0(__symbols);
@@ -10275,7 +10278,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym4.2.clone();
-        let __nt = super::__action157::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4);
+        let __nt = super::__action193::<>(errors, input, __sym0, __sym1, __sym2, __sym3, __sym4);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (5, 57)
     }
@@ -10290,7 +10293,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeDecl = "نمط", ID, "=", SumVarDecl+ => ActionFn(158);
+        // TypeDecl = "نمط", ID, "=", SumVarDecl+ => ActionFn(194);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant26(__symbols);
         let __sym2 = __pop_Variant0(__symbols);
@@ -10298,7 +10301,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action158::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action194::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (4, 57)
     }
@@ -10373,11 +10376,11 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeVars? = TypeVars => ActionFn(98);
+        // TypeVars? = TypeVars => ActionFn(97);
         let __sym0 = __pop_Variant18(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action98::<>(errors, input, __sym0);
+        let __nt = super::__action97::<>(errors, input, __sym0);
         __symbols.push((__start, __Symbol::Variant27(__nt), __end));
         (1, 59)
     }
@@ -10392,10 +10395,10 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // TypeVars? =  => ActionFn(99);
+        // TypeVars? =  => ActionFn(98);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action99::<>(errors, input, &__start, &__end);
+        let __nt = super::__action98::<>(errors, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant27(__nt), __end));
         (0, 59)
     }
@@ -10410,13 +10413,13 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // UnaryClass<Op5, Term> = Op5, UnaryClass<Op5, Term> => ActionFn(74);
+        // UnaryClass<Op5, Term> = Op5, UnaryClass<Op5, Term> => ActionFn(183);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant5(__symbols);
         let __sym0 = __pop_Variant22(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action74::<>(errors, input, __sym0, __sym1);
+        let __nt = super::__action183::<>(errors, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 60)
     }
@@ -10450,7 +10453,7 @@ mod __parse__Program {
         _: core::marker::PhantomData<(&'input (), &'err ())>,
     ) -> (usize, usize)
     {
-        // ValBinding = "ليكن", Pattern, "=", Expr => ActionFn(11);
+        // ValBinding = "ليكن", Pattern, "=", Expr => ActionFn(184);
         assert!(__symbols.len() >= 4);
         let __sym3 = __pop_Variant5(__symbols);
         let __sym2 = __pop_Variant0(__symbols);
@@ -10458,7 +10461,7 @@ mod __parse__Program {
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym3.2.clone();
-        let __nt = super::__action11::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
+        let __nt = super::__action184::<>(errors, input, __sym0, __sym1, __sym2, __sym3);
         __symbols.push((__start, __Symbol::Variant19(__nt), __end));
         (4, 61)
     }
@@ -10486,6 +10489,7 @@ pub use self::__parse__Program::ProgramParser;
 #[cfg_attr(rustfmt, rustfmt_skip)]
 mod __intern_token {
     #![allow(unused_imports)]
+    use std::borrow::Cow;
     use crate::{
     ast::*,
     types::{ProtoType, Literal, BinOpcode, UnOpcode}
@@ -10498,9 +10502,9 @@ mod __intern_token {
     extern crate alloc;
     pub fn new_builder() -> __lalrpop_util::lexer::MatcherBuilder {
         let __strs: &[(&str, bool)] = &[
-            ("^(\"(\\\\[\u{0}-\t\u{b}-\u{10ffff}]|[\u{0}-!\\#-\\[\\]-\u{10ffff}])*\")", false),
+            ("^(\"(\\\\[\0-\t\u{b}-\u{10ffff}]|[\0-!\\#-\\[\\]-\u{10ffff}])*\")", false),
             ("^([0-9]+)", false),
-            ("^([A-Za-zªµºÀ-ÖØ-öø-ˁˆ-ˑˠ-ˤˬˮͰ-ʹͶ-ͷͺ-ͽͿΆΈ-ΊΌΎ-ΡΣ-ϵϷ-ҁҊ-ԯԱ-Ֆՙՠ-ֈא-תׯ-ײؠ-يٮ-ٯٱ-ۓەۥ-ۦۮ-ۯۺ-ۼۿܐܒ-ܯݍ-ޥޱߊ-ߪߴ-ߵߺࠀ-ࠕࠚࠤࠨࡀ-ࡘࡠ-ࡪࢠ-ࢴࢶ-ࣇऄ-हऽॐक़-ॡॱ-ঀঅ-ঌএ-ঐও-নপ-রলশ-হঽৎড়-ঢ়য়-ৡৰ-ৱৼਅ-ਊਏ-ਐਓ-ਨਪ-ਰਲ-ਲ਼ਵ-ਸ਼ਸ-ਹਖ਼-ੜਫ਼ੲ-ੴઅ-ઍએ-ઑઓ-નપ-રલ-ળવ-હઽૐૠ-ૡૹଅ-ଌଏ-ଐଓ-ନପ-ରଲ-ଳଵ-ହଽଡ଼-ଢ଼ୟ-ୡୱஃஅ-ஊஎ-ஐஒ-கங-சஜஞ-டண-தந-பம-ஹௐఅ-ఌఎ-ఐఒ-నప-హఽౘ-ౚౠ-ౡಀಅ-ಌಎ-ಐಒ-ನಪ-ಳವ-ಹಽೞೠ-ೡೱ-ೲഄ-ഌഎ-ഐഒ-ഺഽൎൔ-ൖൟ-ൡൺ-ൿඅ-ඖක-නඳ-රලව-ෆก-ะา-ำเ-ๆກ-ຂຄຆ-ຊຌ-ຣລວ-ະາ-ຳຽເ-ໄໆໜ-ໟༀཀ-ཇཉ-ཬྈ-ྌက-ဪဿၐ-ၕၚ-ၝၡၥ-ၦၮ-ၰၵ-ႁႎႠ-ჅჇჍა-ჺჼ-ቈቊ-ቍቐ-ቖቘቚ-ቝበ-ኈኊ-ኍነ-ኰኲ-ኵኸ-ኾዀዂ-ዅወ-ዖዘ-ጐጒ-ጕጘ-ፚᎀ-ᎏᎠ-Ᏽᏸ-ᏽᐁ-ᙬᙯ-ᙿᚁ-ᚚᚠ-ᛪᛱ-ᛸᜀ-ᜌᜎ-ᜑᜠ-ᜱᝀ-ᝑᝠ-ᝬᝮ-ᝰក-ឳៗៜᠠ-ᡸᢀ-ᢄᢇ-ᢨᢪᢰ-ᣵᤀ-ᤞᥐ-ᥭᥰ-ᥴᦀ-ᦫᦰ-ᧉᨀ-ᨖᨠ-ᩔᪧᬅ-ᬳᭅ-ᭋᮃ-ᮠᮮ-ᮯᮺ-ᯥᰀ-ᰣᱍ-ᱏᱚ-ᱽᲀ-ᲈᲐ-ᲺᲽ-Ჿᳩ-ᳬᳮ-ᳳᳵ-ᳶᳺᴀ-ᶿḀ-ἕἘ-Ἕἠ-ὅὈ-Ὅὐ-ὗὙὛὝὟ-ώᾀ-ᾴᾶ-ᾼιῂ-ῄῆ-ῌῐ-ΐῖ-Ίῠ-Ῥῲ-ῴῶ-ῼⁱⁿₐ-ₜℂℇℊ-ℓℕℙ-ℝℤΩℨK-ℭℯ-ℹℼ-ℿⅅ-ⅉⅎↃ-ↄⰀ-Ⱞⰰ-ⱞⱠ-ⳤⳫ-ⳮⳲ-ⳳⴀ-ⴥⴧⴭⴰ-ⵧⵯⶀ-ⶖⶠ-ⶦⶨ-ⶮⶰ-ⶶⶸ-ⶾⷀ-ⷆⷈ-ⷎⷐ-ⷖⷘ-ⷞⸯ々-〆〱-〵〻-〼ぁ-ゖゝ-ゟァ-ヺー-ヿㄅ-ㄯㄱ-ㆎㆠ-ㆿㇰ-ㇿ㐀-䶿一-鿼ꀀ-ꒌꓐ-ꓽꔀ-ꘌꘐ-ꘟꘪ-ꘫꙀ-ꙮꙿ-ꚝꚠ-ꛥꜗ-ꜟꜢ-ꞈꞋ-ꞿꟂ-ꟊꟵ-ꠁꠃ-ꠅꠇ-ꠊꠌ-ꠢꡀ-ꡳꢂ-ꢳꣲ-ꣷꣻꣽ-ꣾꤊ-ꤥꤰ-ꥆꥠ-ꥼꦄ-ꦲꧏꧠ-ꧤꧦ-ꧯꧺ-ꧾꨀ-ꨨꩀ-ꩂꩄ-ꩋꩠ-ꩶꩺꩾ-ꪯꪱꪵ-ꪶꪹ-ꪽꫀꫂꫛ-ꫝꫠ-ꫪꫲ-ꫴꬁ-ꬆꬉ-ꬎꬑ-ꬖꬠ-ꬦꬨ-ꬮꬰ-ꭚꭜ-ꭩꭰ-ꯢ가-힣ힰ-ퟆퟋ-ퟻ豈-舘並-龎ﬀ-ﬆﬓ-ﬗיִײַ-ﬨשׁ-זּטּ-לּמּנּ-סּףּ-פּצּ-ﮱﯓ-ﴽﵐ-ﶏﶒ-ﷇﷰ-ﷻﹰ-ﹴﹶ-ﻼＡ-Ｚａ-ｚｦ-ﾾￂ-ￇￊ-ￏￒ-ￗￚ-ￜ𐀀-𐀋𐀍-𐀦𐀨-𐀺𐀼-𐀽𐀿-𐁍𐁐-𐁝𐂀-𐃺𐊀-𐊜𐊠-𐋐𐌀-𐌟𐌭-𐍀𐍂-𐍉𐍐-𐍵𐎀-𐎝𐎠-𐏃𐏈-𐏏𐐀-𐒝𐒰-𐓓𐓘-𐓻𐔀-𐔧𐔰-𐕣𐘀-𐜶𐝀-𐝕𐝠-𐝧𐠀-𐠅𐠈𐠊-𐠵𐠷-𐠸𐠼𐠿-𐡕𐡠-𐡶𐢀-𐢞𐣠-𐣲𐣴-𐣵𐤀-𐤕𐤠-𐤹𐦀-𐦷𐦾-𐦿𐨀𐨐-𐨓𐨕-𐨗𐨙-𐨵𐩠-𐩼𐪀-𐪜𐫀-𐫇𐫉-𐫤𐬀-𐬵𐭀-𐭕𐭠-𐭲𐮀-𐮑𐰀-𐱈𐲀-𐲲𐳀-𐳲𐴀-𐴣𐺀-𐺩𐺰-𐺱𐼀-𐼜𐼧𐼰-𐽅𐾰-𐿄𐿠-𐿶𑀃-𑀷𑂃-𑂯𑃐-𑃨𑄃-𑄦𑅄𑅇𑅐-𑅲𑅶𑆃-𑆲𑇁-𑇄𑇚𑇜𑈀-𑈑𑈓-𑈫𑊀-𑊆𑊈𑊊-𑊍𑊏-𑊝𑊟-𑊨𑊰-𑋞𑌅-𑌌𑌏-𑌐𑌓-𑌨𑌪-𑌰𑌲-𑌳𑌵-𑌹𑌽𑍐𑍝-𑍡𑐀-𑐴𑑇-𑑊𑑟-𑑡𑒀-𑒯𑓄-𑓅𑓇𑖀-𑖮𑗘-𑗛𑘀-𑘯𑙄𑚀-𑚪𑚸𑜀-𑜚𑠀-𑠫𑢠-𑣟𑣿-𑤆𑤉𑤌-𑤓𑤕-𑤖𑤘-𑤯𑤿𑥁𑦠-𑦧𑦪-𑧐𑧡𑧣𑨀𑨋-𑨲𑨺𑩐𑩜-𑪉𑪝𑫀-𑫸𑰀-𑰈𑰊-𑰮𑱀𑱲-𑲏𑴀-𑴆𑴈-𑴉𑴋-𑴰𑵆𑵠-𑵥𑵧-𑵨𑵪-𑶉𑶘𑻠-𑻲𑾰𒀀-𒎙𒒀-𒕃𓀀-𓐮𔐀-𔙆𖠀-𖨸𖩀-𖩞𖫐-𖫭𖬀-𖬯𖭀-𖭃𖭣-𖭷𖭽-𖮏𖹀-𖹿𖼀-𖽊𖽐𖾓-𖾟𖿠-𖿡𖿣𗀀-𘟷𘠀-𘳕𘴀-𘴈𛀀-𛄞𛅐-𛅒𛅤-𛅧𛅰-𛋻𛰀-𛱪𛱰-𛱼𛲀-𛲈𛲐-𛲙𝐀-𝑔𝑖-𝒜𝒞-𝒟𝒢𝒥-𝒦𝒩-𝒬𝒮-𝒹𝒻𝒽-𝓃𝓅-𝔅𝔇-𝔊𝔍-𝔔𝔖-𝔜𝔞-𝔹𝔻-𝔾𝕀-𝕄𝕆𝕊-𝕐𝕒-𝚥𝚨-𝛀𝛂-𝛚𝛜-𝛺𝛼-𝜔𝜖-𝜴𝜶-𝝎𝝐-𝝮𝝰-𝞈𝞊-𝞨𝞪-𝟂𝟄-𝟋𞄀-𞄬𞄷-𞄽𞅎𞋀-𞋫𞠀-𞣄𞤀-𞥃𞥋𞸀-𞸃𞸅-𞸟𞸡-𞸢𞸤𞸧𞸩-𞸲𞸴-𞸷𞸹𞸻𞹂𞹇𞹉𞹋𞹍-𞹏𞹑-𞹒𞹔𞹗𞹙𞹛𞹝𞹟𞹡-𞹢𞹤𞹧-𞹪𞹬-𞹲𞹴-𞹷𞹹-𞹼𞹾𞺀-𞺉𞺋-𞺛𞺡-𞺣𞺥-𞺩𞺫-𞺻𠀀-𪛝𪜀-𫜴𫝀-𫠝𫠠-𬺡𬺰-𮯠丽-𪘀𰀀-𱍊][0-9A-Z_a-zªµºÀ-ÖØ-öø-ˁˆ-ˑˠ-ˤˬˮ\u{300}-ʹͶ-ͷͺ-ͽͿΆΈ-ΊΌΎ-ΡΣ-ϵϷ-ҁ\u{483}-ԯԱ-Ֆՙՠ-ֈ\u{591}-\u{5bd}\u{5bf}\u{5c1}-\u{5c2}\u{5c4}-\u{5c5}\u{5c7}א-תׯ-ײ\u{610}-\u{61a}ؠ-٩ٮ-ۓە-\u{6dc}\u{6df}-\u{6e8}\u{6ea}-ۼۿܐ-\u{74a}ݍ-ޱ߀-ߵߺ\u{7fd}ࠀ-\u{82d}ࡀ-\u{85b}ࡠ-ࡪࢠ-ࢴࢶ-ࣇ\u{8d3}-\u{8e1}\u{8e3}-\u{963}०-९ॱ-ঃঅ-ঌএ-ঐও-নপ-রলশ-হ\u{9bc}-\u{9c4}ে-ৈো-ৎ\u{9d7}ড়-ঢ়য়-\u{9e3}০-ৱৼ\u{9fe}\u{a01}-ਃਅ-ਊਏ-ਐਓ-ਨਪ-ਰਲ-ਲ਼ਵ-ਸ਼ਸ-ਹ\u{a3c}ਾ-\u{a42}\u{a47}-\u{a48}\u{a4b}-\u{a4d}\u{a51}ਖ਼-ੜਫ਼੦-\u{a75}\u{a81}-ઃઅ-ઍએ-ઑઓ-નપ-રલ-ળવ-હ\u{abc}-\u{ac5}\u{ac7}-ૉો-\u{acd}ૐૠ-\u{ae3}૦-૯ૹ-\u{aff}\u{b01}-ଃଅ-ଌଏ-ଐଓ-ନପ-ରଲ-ଳଵ-ହ\u{b3c}-\u{b44}େ-ୈୋ-\u{b4d}\u{b55}-\u{b57}ଡ଼-ଢ଼ୟ-\u{b63}୦-୯ୱ\u{b82}-ஃஅ-ஊஎ-ஐஒ-கங-சஜஞ-டண-தந-பம-ஹ\u{bbe}-ூெ-ைொ-\u{bcd}ௐ\u{bd7}௦-௯\u{c00}-ఌఎ-ఐఒ-నప-హఽ-ౄ\u{c46}-\u{c48}\u{c4a}-\u{c4d}\u{c55}-\u{c56}ౘ-ౚౠ-\u{c63}౦-౯ಀ-ಃಅ-ಌಎ-ಐಒ-ನಪ-ಳವ-ಹ\u{cbc}-ೄ\u{cc6}-ೈೊ-\u{ccd}\u{cd5}-\u{cd6}ೞೠ-\u{ce3}೦-೯ೱ-ೲ\u{d00}-ഌഎ-ഐഒ-\u{d44}െ-ൈൊ-ൎൔ-\u{d57}ൟ-\u{d63}൦-൯ൺ-ൿ\u{d81}-ඃඅ-ඖක-නඳ-රලව-ෆ\u{dca}\u{dcf}-\u{dd4}\u{dd6}ෘ-\u{ddf}෦-෯ෲ-ෳก-\u{e3a}เ-\u{e4e}๐-๙ກ-ຂຄຆ-ຊຌ-ຣລວ-ຽເ-ໄໆ\u{ec8}-\u{ecd}໐-໙ໜ-ໟༀ\u{f18}-\u{f19}༠-༩\u{f35}\u{f37}\u{f39}༾-ཇཉ-ཬ\u{f71}-\u{f84}\u{f86}-\u{f97}\u{f99}-\u{fbc}\u{fc6}က-၉ၐ-\u{109d}Ⴀ-ჅჇჍა-ჺჼ-ቈቊ-ቍቐ-ቖቘቚ-ቝበ-ኈኊ-ኍነ-ኰኲ-ኵኸ-ኾዀዂ-ዅወ-ዖዘ-ጐጒ-ጕጘ-ፚ\u{135d}-\u{135f}ᎀ-ᎏᎠ-Ᏽᏸ-ᏽᐁ-ᙬᙯ-ᙿᚁ-ᚚᚠ-ᛪᛮ-ᛸᜀ-ᜌᜎ-\u{1714}ᜠ-\u{1734}ᝀ-\u{1753}ᝠ-ᝬᝮ-ᝰ\u{1772}-\u{1773}ក-\u{17d3}ៗៜ-\u{17dd}០-៩\u{180b}-\u{180d}᠐-᠙ᠠ-ᡸᢀ-ᢪᢰ-ᣵᤀ-ᤞ\u{1920}-ᤫᤰ-\u{193b}᥆-ᥭᥰ-ᥴᦀ-ᦫᦰ-ᧉ᧐-᧙ᨀ-\u{1a1b}ᨠ-\u{1a5e}\u{1a60}-\u{1a7c}\u{1a7f}-᪉᪐-᪙ᪧ\u{1ab0}-\u{1ac0}\u{1b00}-ᭋ᭐-᭙\u{1b6b}-\u{1b73}\u{1b80}-᯳ᰀ-\u{1c37}᱀-᱉ᱍ-ᱽᲀ-ᲈᲐ-ᲺᲽ-Ჿ\u{1cd0}-\u{1cd2}\u{1cd4}-ᳺᴀ-\u{1df9}\u{1dfb}-ἕἘ-Ἕἠ-ὅὈ-Ὅὐ-ὗὙὛὝὟ-ώᾀ-ᾴᾶ-ᾼιῂ-ῄῆ-ῌῐ-ΐῖ-Ίῠ-Ῥῲ-ῴῶ-ῼ\u{200c}-\u{200d}‿-⁀⁔ⁱⁿₐ-ₜ\u{20d0}-\u{20f0}ℂℇℊ-ℓℕℙ-ℝℤΩℨK-ℭℯ-ℹℼ-ℿⅅ-ⅉⅎⅠ-ↈⒶ-ⓩⰀ-Ⱞⰰ-ⱞⱠ-ⳤⳫ-ⳳⴀ-ⴥⴧⴭⴰ-ⵧⵯ\u{2d7f}-ⶖⶠ-ⶦⶨ-ⶮⶰ-ⶶⶸ-ⶾⷀ-ⷆⷈ-ⷎⷐ-ⷖⷘ-ⷞ\u{2de0}-\u{2dff}ⸯ々-〇〡-\u{302f}〱-〵〸-〼ぁ-ゖ\u{3099}-\u{309a}ゝ-ゟァ-ヺー-ヿㄅ-ㄯㄱ-ㆎㆠ-ㆿㇰ-ㇿ㐀-䶿一-鿼ꀀ-ꒌꓐ-ꓽꔀ-ꘌꘐ-ꘫꙀ-\u{a672}\u{a674}-\u{a67d}ꙿ-\u{a6f1}ꜗ-ꜟꜢ-ꞈꞋ-ꞿꟂ-ꟊꟵ-ꠧ\u{a82c}ꡀ-ꡳꢀ-\u{a8c5}꣐-꣙\u{a8e0}-ꣷꣻꣽ-\u{a92d}ꤰ-꥓ꥠ-ꥼ\u{a980}-꧀ꧏ-꧙ꧠ-ꧾꨀ-\u{aa36}ꩀ-ꩍ꩐-꩙ꩠ-ꩶꩺ-ꫂꫛ-ꫝꫠ-ꫯꫲ-\u{aaf6}ꬁ-ꬆꬉ-ꬎꬑ-ꬖꬠ-ꬦꬨ-ꬮꬰ-ꭚꭜ-ꭩꭰ-ꯪ꯬-\u{abed}꯰-꯹가-힣ힰ-ퟆퟋ-ퟻ豈-舘並-龎ﬀ-ﬆﬓ-ﬗיִ-ﬨשׁ-זּטּ-לּמּנּ-סּףּ-פּצּ-ﮱﯓ-ﴽﵐ-ﶏﶒ-ﷇﷰ-ﷻ\u{fe00}-\u{fe0f}\u{fe20}-\u{fe2f}︳-︴﹍-﹏ﹰ-ﹴﹶ-ﻼ０-９Ａ-Ｚ＿ａ-ｚｦ-ﾾￂ-ￇￊ-ￏￒ-ￗￚ-ￜ𐀀-𐀋𐀍-𐀦𐀨-𐀺𐀼-𐀽𐀿-𐁍𐁐-𐁝𐂀-𐃺𐅀-𐅴\u{101fd}𐊀-𐊜𐊠-𐋐\u{102e0}𐌀-𐌟𐌭-𐍊𐍐-\u{1037a}𐎀-𐎝𐎠-𐏃𐏈-𐏏𐏑-𐏕𐐀-𐒝𐒠-𐒩𐒰-𐓓𐓘-𐓻𐔀-𐔧𐔰-𐕣𐘀-𐜶𐝀-𐝕𐝠-𐝧𐠀-𐠅𐠈𐠊-𐠵𐠷-𐠸𐠼𐠿-𐡕𐡠-𐡶𐢀-𐢞𐣠-𐣲𐣴-𐣵𐤀-𐤕𐤠-𐤹𐦀-𐦷𐦾-𐦿𐨀-\u{10a03}\u{10a05}-\u{10a06}\u{10a0c}-𐨓𐨕-𐨗𐨙-𐨵\u{10a38}-\u{10a3a}\u{10a3f}𐩠-𐩼𐪀-𐪜𐫀-𐫇𐫉-\u{10ae6}𐬀-𐬵𐭀-𐭕𐭠-𐭲𐮀-𐮑𐰀-𐱈𐲀-𐲲𐳀-𐳲𐴀-\u{10d27}𐴰-𐴹𐺀-𐺩\u{10eab}-\u{10eac}𐺰-𐺱𐼀-𐼜𐼧𐼰-\u{10f50}𐾰-𐿄𐿠-𐿶𑀀-\u{11046}𑁦-𑁯\u{1107f}-\u{110ba}𑃐-𑃨𑃰-𑃹\u{11100}-\u{11134}𑄶-𑄿𑅄-𑅇𑅐-\u{11173}𑅶\u{11180}-𑇄\u{111c9}-\u{111cc}𑇎-𑇚𑇜𑈀-𑈑𑈓-\u{11237}\u{1123e}𑊀-𑊆𑊈𑊊-𑊍𑊏-𑊝𑊟-𑊨𑊰-\u{112ea}𑋰-𑋹\u{11300}-𑌃𑌅-𑌌𑌏-𑌐𑌓-𑌨𑌪-𑌰𑌲-𑌳𑌵-𑌹\u{1133b}-𑍄𑍇-𑍈𑍋-𑍍𑍐\u{11357}𑍝-𑍣\u{11366}-\u{1136c}\u{11370}-\u{11374}𑐀-𑑊𑑐-𑑙\u{1145e}-𑑡𑒀-𑓅𑓇𑓐-𑓙𑖀-\u{115b5}𑖸-\u{115c0}𑗘-\u{115dd}𑘀-\u{11640}𑙄𑙐-𑙙𑚀-𑚸𑛀-𑛉𑜀-𑜚\u{1171d}-\u{1172b}𑜰-𑜹𑠀-\u{1183a}𑢠-𑣩𑣿-𑤆𑤉𑤌-𑤓𑤕-𑤖𑤘-𑤵𑤷-𑤸\u{1193b}-\u{11943}𑥐-𑥙𑦠-𑦧𑦪-\u{119d7}\u{119da}-𑧡𑧣-𑧤𑨀-\u{11a3e}\u{11a47}𑩐-\u{11a99}𑪝𑫀-𑫸𑰀-𑰈𑰊-\u{11c36}\u{11c38}-𑱀𑱐-𑱙𑱲-𑲏\u{11c92}-\u{11ca7}𑲩-\u{11cb6}𑴀-𑴆𑴈-𑴉𑴋-\u{11d36}\u{11d3a}\u{11d3c}-\u{11d3d}\u{11d3f}-\u{11d47}𑵐-𑵙𑵠-𑵥𑵧-𑵨𑵪-𑶎\u{11d90}-\u{11d91}𑶓-𑶘𑶠-𑶩𑻠-𑻶𑾰𒀀-𒎙𒐀-𒑮𒒀-𒕃𓀀-𓐮𔐀-𔙆𖠀-𖨸𖩀-𖩞𖩠-𖩩𖫐-𖫭\u{16af0}-\u{16af4}𖬀-\u{16b36}𖭀-𖭃𖭐-𖭙𖭣-𖭷𖭽-𖮏𖹀-𖹿𖼀-𖽊\u{16f4f}-𖾇\u{16f8f}-𖾟𖿠-𖿡𖿣-\u{16fe4}𖿰-𖿱𗀀-𘟷𘠀-𘳕𘴀-𘴈𛀀-𛄞𛅐-𛅒𛅤-𛅧𛅰-𛋻𛰀-𛱪𛱰-𛱼𛲀-𛲈𛲐-𛲙\u{1bc9d}-\u{1bc9e}\u{1d165}-\u{1d169}𝅭-\u{1d172}\u{1d17b}-\u{1d182}\u{1d185}-\u{1d18b}\u{1d1aa}-\u{1d1ad}\u{1d242}-\u{1d244}𝐀-𝑔𝑖-𝒜𝒞-𝒟𝒢𝒥-𝒦𝒩-𝒬𝒮-𝒹𝒻𝒽-𝓃𝓅-𝔅𝔇-𝔊𝔍-𝔔𝔖-𝔜𝔞-𝔹𝔻-𝔾𝕀-𝕄𝕆𝕊-𝕐𝕒-𝚥𝚨-𝛀𝛂-𝛚𝛜-𝛺𝛼-𝜔𝜖-𝜴𝜶-𝝎𝝐-𝝮𝝰-𝞈𝞊-𝞨𝞪-𝟂𝟄-𝟋𝟎-𝟿\u{1da00}-\u{1da36}\u{1da3b}-\u{1da6c}\u{1da75}\u{1da84}\u{1da9b}-\u{1da9f}\u{1daa1}-\u{1daaf}\u{1e000}-\u{1e006}\u{1e008}-\u{1e018}\u{1e01b}-\u{1e021}\u{1e023}-\u{1e024}\u{1e026}-\u{1e02a}𞄀-𞄬\u{1e130}-𞄽𞅀-𞅉𞅎𞋀-𞋹𞠀-𞣄\u{1e8d0}-\u{1e8d6}𞤀-𞥋𞥐-𞥙𞸀-𞸃𞸅-𞸟𞸡-𞸢𞸤𞸧𞸩-𞸲𞸴-𞸷𞸹𞸻𞹂𞹇𞹉𞹋𞹍-𞹏𞹑-𞹒𞹔𞹗𞹙𞹛𞹝𞹟𞹡-𞹢𞹤𞹧-𞹪𞹬-𞹲𞹴-𞹷𞹹-𞹼𞹾𞺀-𞺉𞺋-𞺛𞺡-𞺣𞺥-𞺩𞺫-𞺻🄰-🅉🅐-🅩🅰-🆉🯰-🯹𠀀-𪛝𪜀-𫜴𫝀-𫠝𫠠-𬺡𬺰-𮯠丽-𪘀𰀀-𱍊\u{e0100}-\u{e01ef}]*)", false),
+            ("^([A-Za-zªµºÀ-ÖØ-öø-ˁˆ-ˑˠ-ˤˬˮͰ-ʹͶ-ͷͺ-ͽͿΆΈ-ΊΌΎ-ΡΣ-ϵϷ-ҁҊ-ԯԱ-Ֆՙՠ-ֈא-תׯ-ײؠ-يٮ-ٯٱ-ۓەۥ-ۦۮ-ۯۺ-ۼۿܐܒ-ܯݍ-ޥޱߊ-ߪߴ-ߵߺࠀ-ࠕࠚࠤࠨࡀ-ࡘࡠ-ࡪࡰ-ࢇࢉ-ࢎࢠ-ࣉऄ-हऽॐक़-ॡॱ-ঀঅ-ঌএ-ঐও-নপ-রলশ-হঽৎড়-ঢ়য়-ৡৰ-ৱৼਅ-ਊਏ-ਐਓ-ਨਪ-ਰਲ-ਲ਼ਵ-ਸ਼ਸ-ਹਖ਼-ੜਫ਼ੲ-ੴઅ-ઍએ-ઑઓ-નપ-રલ-ળવ-હઽૐૠ-ૡૹଅ-ଌଏ-ଐଓ-ନପ-ରଲ-ଳଵ-ହଽଡ଼-ଢ଼ୟ-ୡୱஃஅ-ஊஎ-ஐஒ-கங-சஜஞ-டண-தந-பம-ஹௐఅ-ఌఎ-ఐఒ-నప-హఽౘ-ౚౝౠ-ౡಀಅ-ಌಎ-ಐಒ-ನಪ-ಳವ-ಹಽೝ-ೞೠ-ೡೱ-ೲഄ-ഌഎ-ഐഒ-ഺഽൎൔ-ൖൟ-ൡൺ-ൿඅ-ඖක-නඳ-රලව-ෆก-ะา-ำเ-ๆກ-ຂຄຆ-ຊຌ-ຣລວ-ະາ-ຳຽເ-ໄໆໜ-ໟༀཀ-ཇཉ-ཬྈ-ྌက-ဪဿၐ-ၕၚ-ၝၡၥ-ၦၮ-ၰၵ-ႁႎႠ-ჅჇჍა-ჺჼ-ቈቊ-ቍቐ-ቖቘቚ-ቝበ-ኈኊ-ኍነ-ኰኲ-ኵኸ-ኾዀዂ-ዅወ-ዖዘ-ጐጒ-ጕጘ-ፚᎀ-ᎏᎠ-Ᏽᏸ-ᏽᐁ-ᙬᙯ-ᙿᚁ-ᚚᚠ-ᛪᛱ-ᛸᜀ-ᜑᜟ-ᜱᝀ-ᝑᝠ-ᝬᝮ-ᝰក-ឳៗៜᠠ-ᡸᢀ-ᢄᢇ-ᢨᢪᢰ-ᣵᤀ-ᤞᥐ-ᥭᥰ-ᥴᦀ-ᦫᦰ-ᧉᨀ-ᨖᨠ-ᩔᪧᬅ-ᬳᭅ-ᭌᮃ-ᮠᮮ-ᮯᮺ-ᯥᰀ-ᰣᱍ-ᱏᱚ-ᱽᲀ-ᲈᲐ-ᲺᲽ-Ჿᳩ-ᳬᳮ-ᳳᳵ-ᳶᳺᴀ-ᶿḀ-ἕἘ-Ἕἠ-ὅὈ-Ὅὐ-ὗὙὛὝὟ-ώᾀ-ᾴᾶ-ᾼιῂ-ῄῆ-ῌῐ-ΐῖ-Ίῠ-Ῥῲ-ῴῶ-ῼⁱⁿₐ-ₜℂℇℊ-ℓℕℙ-ℝℤΩℨK-ℭℯ-ℹℼ-ℿⅅ-ⅉⅎↃ-ↄⰀ-ⳤⳫ-ⳮⳲ-ⳳⴀ-ⴥⴧⴭⴰ-ⵧⵯⶀ-ⶖⶠ-ⶦⶨ-ⶮⶰ-ⶶⶸ-ⶾⷀ-ⷆⷈ-ⷎⷐ-ⷖⷘ-ⷞⸯ々-〆〱-〵〻-〼ぁ-ゖゝ-ゟァ-ヺー-ヿㄅ-ㄯㄱ-ㆎㆠ-ㆿㇰ-ㇿ㐀-䶿一-ꒌꓐ-ꓽꔀ-ꘌꘐ-ꘟꘪ-ꘫꙀ-ꙮꙿ-ꚝꚠ-ꛥꜗ-ꜟꜢ-ꞈꞋ-ꟊꟐ-ꟑꟓꟕ-ꟙꟲ-ꠁꠃ-ꠅꠇ-ꠊꠌ-ꠢꡀ-ꡳꢂ-ꢳꣲ-ꣷꣻꣽ-ꣾꤊ-ꤥꤰ-ꥆꥠ-ꥼꦄ-ꦲꧏꧠ-ꧤꧦ-ꧯꧺ-ꧾꨀ-ꨨꩀ-ꩂꩄ-ꩋꩠ-ꩶꩺꩾ-ꪯꪱꪵ-ꪶꪹ-ꪽꫀꫂꫛ-ꫝꫠ-ꫪꫲ-ꫴꬁ-ꬆꬉ-ꬎꬑ-ꬖꬠ-ꬦꬨ-ꬮꬰ-ꭚꭜ-ꭩꭰ-ꯢ가-힣ힰ-ퟆퟋ-ퟻ豈-舘並-龎ﬀ-ﬆﬓ-ﬗיִײַ-ﬨשׁ-זּטּ-לּמּנּ-סּףּ-פּצּ-ﮱﯓ-ﴽﵐ-ﶏﶒ-ﷇﷰ-ﷻﹰ-ﹴﹶ-ﻼＡ-Ｚａ-ｚｦ-ﾾￂ-ￇￊ-ￏￒ-ￗￚ-ￜ𐀀-𐀋𐀍-𐀦𐀨-𐀺𐀼-𐀽𐀿-𐁍𐁐-𐁝𐂀-𐃺𐊀-𐊜𐊠-𐋐𐌀-𐌟𐌭-𐍀𐍂-𐍉𐍐-𐍵𐎀-𐎝𐎠-𐏃𐏈-𐏏𐐀-𐒝𐒰-𐓓𐓘-𐓻𐔀-𐔧𐔰-𐕣𐕰-𐕺𐕼-𐖊𐖌-𐖒𐖔-𐖕𐖗-𐖡𐖣-𐖱𐖳-𐖹𐖻-𐖼𐘀-𐜶𐝀-𐝕𐝠-𐝧𐞀-𐞅𐞇-𐞰𐞲-𐞺𐠀-𐠅𐠈𐠊-𐠵𐠷-𐠸𐠼𐠿-𐡕𐡠-𐡶𐢀-𐢞𐣠-𐣲𐣴-𐣵𐤀-𐤕𐤠-𐤹𐦀-𐦷𐦾-𐦿𐨀𐨐-𐨓𐨕-𐨗𐨙-𐨵𐩠-𐩼𐪀-𐪜𐫀-𐫇𐫉-𐫤𐬀-𐬵𐭀-𐭕𐭠-𐭲𐮀-𐮑𐰀-𐱈𐲀-𐲲𐳀-𐳲𐴀-𐴣𐺀-𐺩𐺰-𐺱𐼀-𐼜𐼧𐼰-𐽅𐽰-𐾁𐾰-𐿄𐿠-𐿶𑀃-𑀷𑁱-𑁲𑁵𑂃-𑂯𑃐-𑃨𑄃-𑄦𑅄𑅇𑅐-𑅲𑅶𑆃-𑆲𑇁-𑇄𑇚𑇜𑈀-𑈑𑈓-𑈫𑈿-𑉀𑊀-𑊆𑊈𑊊-𑊍𑊏-𑊝𑊟-𑊨𑊰-𑋞𑌅-𑌌𑌏-𑌐𑌓-𑌨𑌪-𑌰𑌲-𑌳𑌵-𑌹𑌽𑍐𑍝-𑍡𑐀-𑐴𑑇-𑑊𑑟-𑑡𑒀-𑒯𑓄-𑓅𑓇𑖀-𑖮𑗘-𑗛𑘀-𑘯𑙄𑚀-𑚪𑚸𑜀-𑜚𑝀-𑝆𑠀-𑠫𑢠-𑣟𑣿-𑤆𑤉𑤌-𑤓𑤕-𑤖𑤘-𑤯𑤿𑥁𑦠-𑦧𑦪-𑧐𑧡𑧣𑨀𑨋-𑨲𑨺𑩐𑩜-𑪉𑪝𑪰-𑫸𑰀-𑰈𑰊-𑰮𑱀𑱲-𑲏𑴀-𑴆𑴈-𑴉𑴋-𑴰𑵆𑵠-𑵥𑵧-𑵨𑵪-𑶉𑶘𑻠-𑻲𑼂𑼄-𑼐𑼒-𑼳𑾰𒀀-𒎙𒒀-𒕃𒾐-𒿰𓀀-𓐯𓑁-𓑆𔐀-𔙆𖠀-𖨸𖩀-𖩞𖩰-𖪾𖫐-𖫭𖬀-𖬯𖭀-𖭃𖭣-𖭷𖭽-𖮏𖹀-𖹿𖼀-𖽊𖽐𖾓-𖾟𖿠-𖿡𖿣𗀀-𘟷𘠀-𘳕𘴀-𘴈𚿰-𚿳𚿵-𚿻𚿽-𚿾𛀀-𛄢𛄲𛅐-𛅒𛅕𛅤-𛅧𛅰-𛋻𛰀-𛱪𛱰-𛱼𛲀-𛲈𛲐-𛲙𝐀-𝑔𝑖-𝒜𝒞-𝒟𝒢𝒥-𝒦𝒩-𝒬𝒮-𝒹𝒻𝒽-𝓃𝓅-𝔅𝔇-𝔊𝔍-𝔔𝔖-𝔜𝔞-𝔹𝔻-𝔾𝕀-𝕄𝕆𝕊-𝕐𝕒-𝚥𝚨-𝛀𝛂-𝛚𝛜-𝛺𝛼-𝜔𝜖-𝜴𝜶-𝝎𝝐-𝝮𝝰-𝞈𝞊-𝞨𝞪-𝟂𝟄-𝟋𝼀-𝼞𝼥-𝼪𞀰-𞁭𞄀-𞄬𞄷-𞄽𞅎𞊐-𞊭𞋀-𞋫𞓐-𞓫𞟠-𞟦𞟨-𞟫𞟭-𞟮𞟰-𞟾𞠀-𞣄𞤀-𞥃𞥋𞸀-𞸃𞸅-𞸟𞸡-𞸢𞸤𞸧𞸩-𞸲𞸴-𞸷𞸹𞸻𞹂𞹇𞹉𞹋𞹍-𞹏𞹑-𞹒𞹔𞹗𞹙𞹛𞹝𞹟𞹡-𞹢𞹤𞹧-𞹪𞹬-𞹲𞹴-𞹷𞹹-𞹼𞹾𞺀-𞺉𞺋-𞺛𞺡-𞺣𞺥-𞺩𞺫-𞺻𠀀-𪛟𪜀-𫜹𫝀-𫠝𫠠-𬺡𬺰-𮯠丽-𪘀𰀀-𱍊𱍐-𲎯][0-9A-Z_a-zªµºÀ-ÖØ-öø-ˁˆ-ˑˠ-ˤˬˮ\u{300}-ʹͶ-ͷͺ-ͽͿΆΈ-ΊΌΎ-ΡΣ-ϵϷ-ҁ\u{483}-ԯԱ-Ֆՙՠ-ֈ\u{591}-\u{5bd}\u{5bf}\u{5c1}-\u{5c2}\u{5c4}-\u{5c5}\u{5c7}א-תׯ-ײ\u{610}-\u{61a}ؠ-٩ٮ-ۓە-\u{6dc}\u{6df}-\u{6e8}\u{6ea}-ۼۿܐ-\u{74a}ݍ-ޱ߀-ߵߺ\u{7fd}ࠀ-\u{82d}ࡀ-\u{85b}ࡠ-ࡪࡰ-ࢇࢉ-ࢎ\u{898}-\u{8e1}\u{8e3}-\u{963}०-९ॱ-ঃঅ-ঌএ-ঐও-নপ-রলশ-হ\u{9bc}-\u{9c4}ে-ৈো-ৎ\u{9d7}ড়-ঢ়য়-\u{9e3}০-ৱৼ\u{9fe}\u{a01}-ਃਅ-ਊਏ-ਐਓ-ਨਪ-ਰਲ-ਲ਼ਵ-ਸ਼ਸ-ਹ\u{a3c}ਾ-\u{a42}\u{a47}-\u{a48}\u{a4b}-\u{a4d}\u{a51}ਖ਼-ੜਫ਼੦-\u{a75}\u{a81}-ઃઅ-ઍએ-ઑઓ-નપ-રલ-ળવ-હ\u{abc}-\u{ac5}\u{ac7}-ૉો-\u{acd}ૐૠ-\u{ae3}૦-૯ૹ-\u{aff}\u{b01}-ଃଅ-ଌଏ-ଐଓ-ନପ-ରଲ-ଳଵ-ହ\u{b3c}-\u{b44}େ-ୈୋ-\u{b4d}\u{b55}-\u{b57}ଡ଼-ଢ଼ୟ-\u{b63}୦-୯ୱ\u{b82}-ஃஅ-ஊஎ-ஐஒ-கங-சஜஞ-டண-தந-பம-ஹ\u{bbe}-ூெ-ைொ-\u{bcd}ௐ\u{bd7}௦-௯\u{c00}-ఌఎ-ఐఒ-నప-హ\u{c3c}-ౄ\u{c46}-\u{c48}\u{c4a}-\u{c4d}\u{c55}-\u{c56}ౘ-ౚౝౠ-\u{c63}౦-౯ಀ-ಃಅ-ಌಎ-ಐಒ-ನಪ-ಳವ-ಹ\u{cbc}-ೄ\u{cc6}-\u{cc8}\u{cca}-\u{ccd}\u{cd5}-\u{cd6}ೝ-ೞೠ-\u{ce3}೦-೯ೱ-ೳ\u{d00}-ഌഎ-ഐഒ-\u{d44}െ-ൈൊ-ൎൔ-\u{d57}ൟ-\u{d63}൦-൯ൺ-ൿ\u{d81}-ඃඅ-ඖක-නඳ-රලව-ෆ\u{dca}\u{dcf}-\u{dd4}\u{dd6}ෘ-\u{ddf}෦-෯ෲ-ෳก-\u{e3a}เ-\u{e4e}๐-๙ກ-ຂຄຆ-ຊຌ-ຣລວ-ຽເ-ໄໆ\u{ec8}-\u{ece}໐-໙ໜ-ໟༀ\u{f18}-\u{f19}༠-༩\u{f35}\u{f37}\u{f39}༾-ཇཉ-ཬ\u{f71}-\u{f84}\u{f86}-\u{f97}\u{f99}-\u{fbc}\u{fc6}က-၉ၐ-\u{109d}Ⴀ-ჅჇჍა-ჺჼ-ቈቊ-ቍቐ-ቖቘቚ-ቝበ-ኈኊ-ኍነ-ኰኲ-ኵኸ-ኾዀዂ-ዅወ-ዖዘ-ጐጒ-ጕጘ-ፚ\u{135d}-\u{135f}ᎀ-ᎏᎠ-Ᏽᏸ-ᏽᐁ-ᙬᙯ-ᙿᚁ-ᚚᚠ-ᛪᛮ-ᛸᜀ-\u{1715}ᜟ-\u{1734}ᝀ-\u{1753}ᝠ-ᝬᝮ-ᝰ\u{1772}-\u{1773}ក-\u{17d3}ៗៜ-\u{17dd}០-៩\u{180b}-\u{180d}\u{180f}-᠙ᠠ-ᡸᢀ-ᢪᢰ-ᣵᤀ-ᤞ\u{1920}-ᤫᤰ-\u{193b}᥆-ᥭᥰ-ᥴᦀ-ᦫᦰ-ᧉ᧐-᧙ᨀ-\u{1a1b}ᨠ-\u{1a5e}\u{1a60}-\u{1a7c}\u{1a7f}-᪉᪐-᪙ᪧ\u{1ab0}-\u{1ace}\u{1b00}-ᭌ᭐-᭙\u{1b6b}-\u{1b73}\u{1b80}-\u{1bf3}ᰀ-\u{1c37}᱀-᱉ᱍ-ᱽᲀ-ᲈᲐ-ᲺᲽ-Ჿ\u{1cd0}-\u{1cd2}\u{1cd4}-ᳺᴀ-ἕἘ-Ἕἠ-ὅὈ-Ὅὐ-ὗὙὛὝὟ-ώᾀ-ᾴᾶ-ᾼιῂ-ῄῆ-ῌῐ-ΐῖ-Ίῠ-Ῥῲ-ῴῶ-ῼ\u{200c}-\u{200d}‿-⁀⁔ⁱⁿₐ-ₜ\u{20d0}-\u{20f0}ℂℇℊ-ℓℕℙ-ℝℤΩℨK-ℭℯ-ℹℼ-ℿⅅ-ⅉⅎⅠ-ↈⒶ-ⓩⰀ-ⳤⳫ-ⳳⴀ-ⴥⴧⴭⴰ-ⵧⵯ\u{2d7f}-ⶖⶠ-ⶦⶨ-ⶮⶰ-ⶶⶸ-ⶾⷀ-ⷆⷈ-ⷎⷐ-ⷖⷘ-ⷞ\u{2de0}-\u{2dff}ⸯ々-〇〡-\u{302f}〱-〵〸-〼ぁ-ゖ\u{3099}-\u{309a}ゝ-ゟァ-ヺー-ヿㄅ-ㄯㄱ-ㆎㆠ-ㆿㇰ-ㇿ㐀-䶿一-ꒌꓐ-ꓽꔀ-ꘌꘐ-ꘫꙀ-\u{a672}\u{a674}-\u{a67d}ꙿ-\u{a6f1}ꜗ-ꜟꜢ-ꞈꞋ-ꟊꟐ-ꟑꟓꟕ-ꟙꟲ-ꠧ\u{a82c}ꡀ-ꡳꢀ-\u{a8c5}꣐-꣙\u{a8e0}-ꣷꣻꣽ-\u{a92d}ꤰ-\u{a953}ꥠ-ꥼ\u{a980}-\u{a9c0}ꧏ-꧙ꧠ-ꧾꨀ-\u{aa36}ꩀ-ꩍ꩐-꩙ꩠ-ꩶꩺ-ꫂꫛ-ꫝꫠ-ꫯꫲ-\u{aaf6}ꬁ-ꬆꬉ-ꬎꬑ-ꬖꬠ-ꬦꬨ-ꬮꬰ-ꭚꭜ-ꭩꭰ-ꯪ꯬-\u{abed}꯰-꯹가-힣ힰ-ퟆퟋ-ퟻ豈-舘並-龎ﬀ-ﬆﬓ-ﬗיִ-ﬨשׁ-זּטּ-לּמּנּ-סּףּ-פּצּ-ﮱﯓ-ﴽﵐ-ﶏﶒ-ﷇﷰ-ﷻ\u{fe00}-\u{fe0f}\u{fe20}-\u{fe2f}︳-︴﹍-﹏ﹰ-ﹴﹶ-ﻼ０-９Ａ-Ｚ＿ａ-ｚｦ-ﾾￂ-ￇￊ-ￏￒ-ￗￚ-ￜ𐀀-𐀋𐀍-𐀦𐀨-𐀺𐀼-𐀽𐀿-𐁍𐁐-𐁝𐂀-𐃺𐅀-𐅴\u{101fd}𐊀-𐊜𐊠-𐋐\u{102e0}𐌀-𐌟𐌭-𐍊𐍐-\u{1037a}𐎀-𐎝𐎠-𐏃𐏈-𐏏𐏑-𐏕𐐀-𐒝𐒠-𐒩𐒰-𐓓𐓘-𐓻𐔀-𐔧𐔰-𐕣𐕰-𐕺𐕼-𐖊𐖌-𐖒𐖔-𐖕𐖗-𐖡𐖣-𐖱𐖳-𐖹𐖻-𐖼𐘀-𐜶𐝀-𐝕𐝠-𐝧𐞀-𐞅𐞇-𐞰𐞲-𐞺𐠀-𐠅𐠈𐠊-𐠵𐠷-𐠸𐠼𐠿-𐡕𐡠-𐡶𐢀-𐢞𐣠-𐣲𐣴-𐣵𐤀-𐤕𐤠-𐤹𐦀-𐦷𐦾-𐦿𐨀-\u{10a03}\u{10a05}-\u{10a06}\u{10a0c}-𐨓𐨕-𐨗𐨙-𐨵\u{10a38}-\u{10a3a}\u{10a3f}𐩠-𐩼𐪀-𐪜𐫀-𐫇𐫉-\u{10ae6}𐬀-𐬵𐭀-𐭕𐭠-𐭲𐮀-𐮑𐰀-𐱈𐲀-𐲲𐳀-𐳲𐴀-\u{10d27}𐴰-𐴹𐺀-𐺩\u{10eab}-\u{10eac}𐺰-𐺱\u{10efd}-𐼜𐼧𐼰-\u{10f50}𐽰-\u{10f85}𐾰-𐿄𐿠-𐿶𑀀-\u{11046}𑁦-𑁵\u{1107f}-\u{110ba}\u{110c2}𑃐-𑃨𑃰-𑃹\u{11100}-\u{11134}𑄶-𑄿𑅄-𑅇𑅐-\u{11173}𑅶\u{11180}-𑇄\u{111c9}-\u{111cc}𑇎-𑇚𑇜𑈀-𑈑𑈓-\u{11237}\u{1123e}-\u{11241}𑊀-𑊆𑊈𑊊-𑊍𑊏-𑊝𑊟-𑊨𑊰-\u{112ea}𑋰-𑋹\u{11300}-𑌃𑌅-𑌌𑌏-𑌐𑌓-𑌨𑌪-𑌰𑌲-𑌳𑌵-𑌹\u{1133b}-𑍄𑍇-𑍈𑍋-\u{1134d}𑍐\u{11357}𑍝-𑍣\u{11366}-\u{1136c}\u{11370}-\u{11374}𑐀-𑑊𑑐-𑑙\u{1145e}-𑑡𑒀-𑓅𑓇𑓐-𑓙𑖀-\u{115b5}𑖸-\u{115c0}𑗘-\u{115dd}𑘀-\u{11640}𑙄𑙐-𑙙𑚀-𑚸𑛀-𑛉𑜀-𑜚\u{1171d}-\u{1172b}𑜰-𑜹𑝀-𑝆𑠀-\u{1183a}𑢠-𑣩𑣿-𑤆𑤉𑤌-𑤓𑤕-𑤖𑤘-𑤵𑤷-𑤸\u{1193b}-\u{11943}𑥐-𑥙𑦠-𑦧𑦪-\u{119d7}\u{119da}-𑧡𑧣-𑧤𑨀-\u{11a3e}\u{11a47}𑩐-\u{11a99}𑪝𑪰-𑫸𑰀-𑰈𑰊-\u{11c36}\u{11c38}-𑱀𑱐-𑱙𑱲-𑲏\u{11c92}-\u{11ca7}𑲩-\u{11cb6}𑴀-𑴆𑴈-𑴉𑴋-\u{11d36}\u{11d3a}\u{11d3c}-\u{11d3d}\u{11d3f}-\u{11d47}𑵐-𑵙𑵠-𑵥𑵧-𑵨𑵪-𑶎\u{11d90}-\u{11d91}𑶓-𑶘𑶠-𑶩𑻠-𑻶\u{11f00}-𑼐𑼒-\u{11f3a}𑼾-\u{11f42}𑽐-𑽙𑾰𒀀-𒎙𒐀-𒑮𒒀-𒕃𒾐-𒿰𓀀-𓐯\u{13440}-\u{13455}𔐀-𔙆𖠀-𖨸𖩀-𖩞𖩠-𖩩𖩰-𖪾𖫀-𖫉𖫐-𖫭\u{16af0}-\u{16af4}𖬀-\u{16b36}𖭀-𖭃𖭐-𖭙𖭣-𖭷𖭽-𖮏𖹀-𖹿𖼀-𖽊\u{16f4f}-𖾇\u{16f8f}-𖾟𖿠-𖿡𖿣-\u{16fe4}\u{16ff0}-\u{16ff1}𗀀-𘟷𘠀-𘳕𘴀-𘴈𚿰-𚿳𚿵-𚿻𚿽-𚿾𛀀-𛄢𛄲𛅐-𛅒𛅕𛅤-𛅧𛅰-𛋻𛰀-𛱪𛱰-𛱼𛲀-𛲈𛲐-𛲙\u{1bc9d}-\u{1bc9e}\u{1cf00}-\u{1cf2d}\u{1cf30}-\u{1cf46}\u{1d165}-\u{1d169}\u{1d16d}-\u{1d172}\u{1d17b}-\u{1d182}\u{1d185}-\u{1d18b}\u{1d1aa}-\u{1d1ad}\u{1d242}-\u{1d244}𝐀-𝑔𝑖-𝒜𝒞-𝒟𝒢𝒥-𝒦𝒩-𝒬𝒮-𝒹𝒻𝒽-𝓃𝓅-𝔅𝔇-𝔊𝔍-𝔔𝔖-𝔜𝔞-𝔹𝔻-𝔾𝕀-𝕄𝕆𝕊-𝕐𝕒-𝚥𝚨-𝛀𝛂-𝛚𝛜-𝛺𝛼-𝜔𝜖-𝜴𝜶-𝝎𝝐-𝝮𝝰-𝞈𝞊-𝞨𝞪-𝟂𝟄-𝟋𝟎-𝟿\u{1da00}-\u{1da36}\u{1da3b}-\u{1da6c}\u{1da75}\u{1da84}\u{1da9b}-\u{1da9f}\u{1daa1}-\u{1daaf}𝼀-𝼞𝼥-𝼪\u{1e000}-\u{1e006}\u{1e008}-\u{1e018}\u{1e01b}-\u{1e021}\u{1e023}-\u{1e024}\u{1e026}-\u{1e02a}𞀰-𞁭\u{1e08f}𞄀-𞄬\u{1e130}-𞄽𞅀-𞅉𞅎𞊐-\u{1e2ae}𞋀-𞋹𞓐-𞓹𞟠-𞟦𞟨-𞟫𞟭-𞟮𞟰-𞟾𞠀-𞣄\u{1e8d0}-\u{1e8d6}𞤀-𞥋𞥐-𞥙𞸀-𞸃𞸅-𞸟𞸡-𞸢𞸤𞸧𞸩-𞸲𞸴-𞸷𞸹𞸻𞹂𞹇𞹉𞹋𞹍-𞹏𞹑-𞹒𞹔𞹗𞹙𞹛𞹝𞹟𞹡-𞹢𞹤𞹧-𞹪𞹬-𞹲𞹴-𞹷𞹹-𞹼𞹾𞺀-𞺉𞺋-𞺛𞺡-𞺣𞺥-𞺩𞺫-𞺻🄰-🅉🅐-🅩🅰-🆉🯰-🯹𠀀-𪛟𪜀-𫜹𫝀-𫠝𫠠-𬺡𬺰-𮯠丽-𪘀𰀀-𱍊𱍐-𲎯\u{e0100}-\u{e01ef}]*)", false),
             ("^(%)", false),
             ("^(\\()", false),
             ("^(\\(\\))", false),
@@ -10631,15 +10635,17 @@ fn __action6<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, name, _): (usize, &'input str, usize),
     (_, vars, _): (usize, core::option::Option<Vec<&'input str>>, usize),
     (_, _, _): (usize, &'input str, usize),
-    (_, variants, _): (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize),
+    (_, variants, _): (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Binding<'input>
 {
     {
-        Binding::Type { name, vars: vars.unwrap_or(vec![]), variants }
+        Binding::new(BindingKind::Type { name, vars: vars.unwrap_or(vec![]), def: TypeDef::Sum(variants) }, l, r)
     }
 }
 
@@ -10692,11 +10698,13 @@ fn __action10<
     errors: &'err mut Vec<usize>,
     input: &'input str,
     (_, _, _): (usize, &'input str, usize),
-    (_, __0, _): (usize, &'input str, usize),
-    (_, __1, _): (usize, ProtoType<'input>, usize),
-) -> (&'input str, ProtoType<'input>)
+    (_, l, _): (usize, usize, usize),
+    (_, name, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
+    (_, t, _): (usize, ProtoType<'input>, usize),
+) -> (&'input str, ProtoType<'input>, Span)
 {
-    (__0, __1)
+    (name, t, Span::new(l, r))
 }
 
 #[allow(unused_variables)]
@@ -10706,13 +10714,15 @@ fn __action11<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
-    (_, __0, _): (usize, Pattern<'input>, usize),
+    (_, p, _): (usize, Pattern<'input>, usize),
     (_, _, _): (usize, &'input str, usize),
-    (_, __1, _): (usize, Expr<'input>, usize),
+    (_, e, _): (usize, Expr<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Binding<'input>
 {
-    Binding::Value(__0, __1, false)
+    Binding::new(BindingKind::Value(p, e, false), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10722,15 +10732,25 @@ fn __action12<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, nl, _): (usize, usize, usize),
     (_, name, _): (usize, &'input str, usize),
+    (_, nr, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, cl, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, v, _): (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Binding<'input>
 {
-    Binding::Value(Pattern::Bind(name), Expr::Closure(v), true)
+    Binding::new(
+        BindingKind::Value(
+            Pattern::new(PatternKind::Bind(name), nl, nr),
+            Expr::new(ExprKind::Closure(v), cl, r),
+            true,
+        ), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10755,10 +10775,12 @@ fn __action14<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, Literal<'input>, usize),
+    (_, l, _): (usize, usize, usize),
+    (_, lit, _): (usize, Literal<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    Pattern::Literal(__0)
+    Pattern::new(PatternKind::Literal(lit), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10768,10 +10790,12 @@ fn __action15<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
+    (_, l, _): (usize, usize, usize),
+    (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    Pattern::Wild
+    Pattern::new(PatternKind::Wild, l, r)
 }
 
 #[allow(unused_variables)]
@@ -10781,10 +10805,12 @@ fn __action16<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
+    (_, l, _): (usize, usize, usize),
+    (_, id, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    Pattern::Bind(__0)
+    Pattern::new(PatternKind::Bind(id), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10794,12 +10820,14 @@ fn __action17<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, v, _): (usize, Vec<Pattern<'input>>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    Pattern::Tuple(v)
+    Pattern::new(PatternKind::Tuple(v), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10829,7 +10857,7 @@ fn __action19<
     (_, end, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    { errors.push(start); Pattern::Error(start, end) }
+    { errors.push(start); Pattern::new(PatternKind::Error, start, end) }
 }
 
 #[allow(unused_variables)]
@@ -10839,11 +10867,13 @@ fn __action20<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, id, _): (usize, &'input str, usize),
     (_, field, _): (usize, Pattern<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Pattern<'input>
 {
-    Pattern::SumVar(id, Box::new(field))
+    Pattern::new(PatternKind::SumVar(id, Box::new(field)), l, r)
 }
 
 #[allow(unused_variables)]
@@ -10961,10 +10991,12 @@ fn __action29<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
+    (_, l, _): (usize, usize, usize),
+    (_, name, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> ProtoType<'input>
 {
-    ProtoType::Generic(__0)
+    ProtoType::Generic(name, Span::new(l, r))
 }
 
 #[allow(unused_variables)]
@@ -10974,11 +11006,13 @@ fn __action30<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, name, _): (usize, &'input str, usize),
     (_, tp, _): (usize, ProtoType<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> ProtoType<'input>
 {
-    ProtoType::Sum(name, Box::new(tp))
+    ProtoType::Sum(name, Box::new(tp), Span::new(l, r))
 }
 
 #[allow(unused_variables)]
@@ -11018,13 +11052,15 @@ fn __action33<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, e1, _): (usize, Expr<'input>, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, e2, _): (usize, Expr<'input>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(e1), BinOpcode::Index, Box::new(e2))
+    Expr::new(ExprKind::BinOp(Box::new(e1), BinOpcode::Index, Box::new(e2)), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11132,7 +11168,8 @@ fn __action41<
     {
         let mut expr = hd;
         for e in v {
-            expr = Expr::Application(Box::new(expr), Box::new(e));
+            let (first, last) = (expr.span, e.span);
+            expr = Expr::joined(ExprKind::Application(Box::new(expr), Box::new(e)), first, last);
         }
         expr
     }
@@ -11145,6 +11182,7 @@ fn __action42<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, cond, _): (usize, Expr<'input>, usize),
     (_, _, _): (usize, &'input str, usize),
@@ -11152,10 +11190,11 @@ fn __action42<
     (_, _, _): (usize, &'input str, usize),
     (_, b, _): (usize, Expr<'input>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
     {
-        Expr::Conditional(Box::new(cond), Box::new(a), Box::new(b))
+        Expr::new(ExprKind::Conditional(Box::new(cond), Box::new(a), Box::new(b)), l, r)
     }
 }
 
@@ -11166,12 +11205,14 @@ fn __action43<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
     (_, v, _): (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::Closure(v)
+    Expr::new(ExprKind::Closure(v), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11181,10 +11222,12 @@ fn __action44<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, Literal<'input>, usize),
+    (_, l, _): (usize, usize, usize),
+    (_, lit, _): (usize, Literal<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::Literal(__0)
+    Expr::new(ExprKind::Literal(lit), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11194,10 +11237,12 @@ fn __action45<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, bound, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::Bound(bound)
+    Expr::new(ExprKind::Bound(bound), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11207,12 +11252,14 @@ fn __action46<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, _, _): (usize, &'input str, usize),
-    (_, __0, _): (usize, Vec<Expr<'input>>, usize),
+    (_, v, _): (usize, Vec<Expr<'input>>, usize),
     (_, _, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::Tuple(__0)
+    Expr::new(ExprKind::Tuple(v), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11242,7 +11289,7 @@ fn __action48<
     (_, end, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    { errors.push(start); Expr::Error(start, end) }
+    { errors.push(start); Expr::new(ExprKind::Error, start, end) }
 }
 
 #[allow(unused_variables)]
@@ -11255,7 +11302,7 @@ fn __action49<
     (_, __0, _): (usize, &'input str, usize),
 ) -> Literal<'input>
 {
-    Literal::String(&__0[1..(__0.len()-1)])
+    Literal::String(Cow::Borrowed(&__0[1..(__0.len()-1)]))
 }
 
 #[allow(unused_variables)]
@@ -11584,11 +11631,13 @@ fn __action74<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
+    (_, l, _): (usize, usize, usize),
     (_, op, _): (usize, UnOpcode, usize),
     (_, e, _): (usize, Expr<'input>, usize),
+    (_, r, _): (usize, usize, usize),
 ) -> Expr<'input>
 {
-    Expr::UnOp(op, Box::new(e))
+    Expr::new(ExprKind::UnOp(op, Box::new(e)), l, r)
 }
 
 #[allow(unused_variables)]
@@ -11616,7 +11665,10 @@ fn __action76<
     (_, r, _): (usize, Expr<'input>, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(l), op, Box::new(r))
+    {
+        let (first, last) = (l.span, r.span);
+        Expr::joined(ExprKind::BinOp(Box::new(l), op, Box::new(r)), first, last)
+    }
 }
 
 #[allow(unused_variables)]
@@ -11644,7 +11696,10 @@ fn __action78<
     (_, r, _): (usize, Expr<'input>, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(l), op, Box::new(r))
+    {
+        let (first, last) = (l.span, r.span);
+        Expr::joined(ExprKind::BinOp(Box::new(l), op, Box::new(r)), first, last)
+    }
 }
 
 #[allow(unused_variables)]
@@ -11672,7 +11727,10 @@ fn __action80<
     (_, r, _): (usize, Expr<'input>, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(l), op, Box::new(r))
+    {
+        let (first, last) = (l.span, r.span);
+        Expr::joined(ExprKind::BinOp(Box::new(l), op, Box::new(r)), first, last)
+    }
 }
 
 #[allow(unused_variables)]
@@ -11700,7 +11758,10 @@ fn __action82<
     (_, r, _): (usize, Expr<'input>, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(l), op, Box::new(r))
+    {
+        let (first, last) = (l.span, r.span);
+        Expr::joined(ExprKind::BinOp(Box::new(l), op, Box::new(r)), first, last)
+    }
 }
 
 #[allow(unused_variables)]
@@ -11728,7 +11789,10 @@ fn __action84<
     (_, r, _): (usize, Expr<'input>, usize),
 ) -> Expr<'input>
 {
-    Expr::BinOp(Box::new(l), op, Box::new(r))
+    {
+        let (first, last) = (l.span, r.span);
+        Expr::joined(ExprKind::BinOp(Box::new(l), op, Box::new(r)), first, last)
+    }
 }
 
 #[allow(unused_variables)]
@@ -11767,34 +11831,6 @@ fn __action86<
 fn __action87<
     'input,
     'err,
->(
-    errors: &'err mut Vec<usize>,
-    input: &'input str,
-    __lookbehind: &usize,
-    __lookahead: &usize,
-) -> usize
-{
-    __lookbehind.clone()
-}
-
-#[allow(unused_variables)]
-fn __action88<
-    'input,
-    'err,
->(
-    errors: &'err mut Vec<usize>,
-    input: &'input str,
-    __lookbehind: &usize,
-    __lookahead: &usize,
-) -> usize
-{
-    __lookahead.clone()
-}
-
-#[allow(unused_variables)]
-fn __action89<
-    'input,
-    'err,
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
@@ -11811,7 +11847,7 @@ fn __action89<
 }
 
 #[allow(unused_variables)]
-fn __action90<
+fn __action88<
     'input,
     'err,
 >(
@@ -11824,7 +11860,7 @@ fn __action90<
 }
 
 #[allow(unused_variables)]
-fn __action91<
+fn __action89<
     'input,
     'err,
 >(
@@ -11838,7 +11874,7 @@ fn __action91<
 }
 
 #[allow(unused_variables)]
-fn __action92<
+fn __action90<
     'input,
     'err,
 >(
@@ -11851,7 +11887,7 @@ fn __action92<
 }
 
 #[allow(unused_variables)]
-fn __action93<
+fn __action91<
     'input,
     'err,
 >(
@@ -11871,7 +11907,7 @@ fn __action93<
 }
 
 #[allow(unused_variables)]
-fn __action94<
+fn __action92<
     'input,
     'err,
 >(
@@ -11884,7 +11920,7 @@ fn __action94<
 }
 
 #[allow(unused_variables)]
-fn __action95<
+fn __action93<
     'input,
     'err,
 >(
@@ -11904,34 +11940,48 @@ fn __action95<
 }
 
 #[allow(unused_variables)]
-fn __action96<
+fn __action94<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> usize
+{
+    __lookbehind.clone()
+}
+
+#[allow(unused_variables)]
+fn __action95<
     'input,
     'err,
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, __0, _): (usize, (&'input str, ProtoType<'input>), usize),
-) -> alloc::vec::Vec<(&'input str, ProtoType<'input>)>
+    (_, __0, _): (usize, (&'input str, ProtoType<'input>, Span), usize),
+) -> alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>
 {
     alloc::vec![__0]
 }
 
 #[allow(unused_variables)]
-fn __action97<
+fn __action96<
     'input,
     'err,
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    (_, v, _): (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize),
-    (_, e, _): (usize, (&'input str, ProtoType<'input>), usize),
-) -> alloc::vec::Vec<(&'input str, ProtoType<'input>)>
+    (_, v, _): (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
+    (_, e, _): (usize, (&'input str, ProtoType<'input>, Span), usize),
+) -> alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>
 {
     { let mut v = v; v.push(e); v }
 }
 
 #[allow(unused_variables)]
-fn __action98<
+fn __action97<
     'input,
     'err,
 >(
@@ -11944,7 +11994,7 @@ fn __action98<
 }
 
 #[allow(unused_variables)]
-fn __action99<
+fn __action98<
     'input,
     'err,
 >(
@@ -11957,6 +12007,20 @@ fn __action99<
     None
 }
 
+#[allow(unused_variables)]
+fn __action99<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> usize
+{
+    __lookahead.clone()
+}
+
 #[allow(unused_variables)]
 fn __action100<
     'input,
@@ -12402,7 +12466,7 @@ fn __action129<
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action89(
+    __action87(
         errors,
         input,
         __0,
@@ -12431,7 +12495,7 @@ fn __action130<
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action89(
+    __action87(
         errors,
         input,
         __0,
@@ -12785,25 +12849,37 @@ fn __action143<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
-    __1: (usize, usize, usize),
+    __0: (usize, &'input str, usize),
+    __1: (usize, Expr<'input>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, Expr<'input>, usize),
+    __4: (usize, &'input str, usize),
+    __5: (usize, Expr<'input>, usize),
+    __6: (usize, &'input str, usize),
+    __7: (usize, usize, usize),
 ) -> Expr<'input>
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.0.clone();
-    let __temp0 = __action88(
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action48(
+    __action42(
         errors,
         input,
         __temp0,
         __0,
         __1,
+        __2,
+        __3,
+        __4,
+        __5,
+        __6,
+        __7,
     )
 }
 
@@ -12814,25 +12890,29 @@ fn __action144<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
-    __1: (usize, usize, usize),
-) -> Pattern<'input>
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, usize, usize),
+) -> Expr<'input>
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.0.clone();
-    let __temp0 = __action88(
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action19(
+    __action43(
         errors,
         input,
         __temp0,
         __0,
         __1,
+        __2,
+        __3,
     )
 }
 
@@ -12843,20 +12923,20 @@ fn __action145<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+    __0: (usize, Literal<'input>, usize),
     __1: (usize, usize, usize),
-) -> ProtoType<'input>
+) -> Expr<'input>
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.0.clone();
-    let __temp0 = __action88(
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action32(
+    __action44(
         errors,
         input,
         __temp0,
@@ -12872,23 +12952,25 @@ fn __action146<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
 ) -> Expr<'input>
 {
-    let __start0 = __0.2.clone();
-    let __end0 = __0.2.clone();
-    let __temp0 = __action87(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action143(
+    __action45(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __1,
     )
 }
 
@@ -12899,23 +12981,29 @@ fn __action147<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
-) -> Pattern<'input>
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<Expr<'input>>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, usize, usize),
+) -> Expr<'input>
 {
-    let __start0 = __0.2.clone();
-    let __end0 = __0.2.clone();
-    let __temp0 = __action87(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action144(
+    __action46(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __1,
+        __2,
+        __3,
     )
 }
 
@@ -12927,22 +13015,24 @@ fn __action148<
     errors: &'err mut Vec<usize>,
     input: &'input str,
     __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
-) -> ProtoType<'input>
+    __1: (usize, usize, usize),
+) -> Expr<'input>
 {
-    let __start0 = __0.2.clone();
-    let __end0 = __0.2.clone();
-    let __temp0 = __action87(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action145(
+    __action48(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __1,
     )
 }
 
@@ -12953,23 +13043,31 @@ fn __action149<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, alloc::vec::Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
-    __1: (usize, (alloc::vec::Vec<Pattern<'input>>, Expr<'input>), usize),
-) -> Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>
+    __0: (usize, Expr<'input>, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, Expr<'input>, usize),
+    __3: (usize, &'input str, usize),
+    __4: (usize, usize, usize),
+) -> Expr<'input>
 {
-    let __start0 = __1.0.clone();
-    let __end0 = __1.2.clone();
-    let __temp0 = __action109(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
-        __1,
+        &__start0,
+        &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action93(
+    __action33(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __1,
+        __2,
+        __3,
+        __4,
     )
 }
 
@@ -12980,23 +13078,57 @@ fn __action150<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, alloc::vec::Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
-) -> Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, usize, usize),
+    __3: (usize, &'input str, usize),
+    __4: (usize, &'input str, usize),
+    __5: (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+    __6: (usize, &'input str, usize),
+    __7: (usize, usize, usize),
+) -> Binding<'input>
 {
-    let __start0 = __0.2.clone();
-    let __end0 = __0.2.clone();
-    let __temp0 = __action110(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __start1 = __0.2.clone();
+    let __end1 = __1.0.clone();
+    let __start2 = __3.2.clone();
+    let __end2 = __4.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action93(
+    let __temp1 = __action99(
+        errors,
+        input,
+        &__start1,
+        &__end1,
+    );
+    let __temp1 = (__start1, __temp1, __end1);
+    let __temp2 = __action99(
+        errors,
+        input,
+        &__start2,
+        &__end2,
+    );
+    let __temp2 = (__start2, __temp2, __end2);
+    __action12(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __temp1,
+        __1,
+        __2,
+        __3,
+        __temp2,
+        __4,
+        __5,
+        __6,
+        __7,
     )
 }
 
@@ -13007,23 +13139,25 @@ fn __action151<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, Expr<'input>, usize),
-) -> Expr<'input>
+    __0: (usize, Literal<'input>, usize),
+    __1: (usize, usize, usize),
+) -> Pattern<'input>
 {
-    let __start0 = __0.2.clone();
-    let __end0 = __0.2.clone();
-    let __temp0 = __action72(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action41(
+    __action14(
         errors,
         input,
-        __0,
         __temp0,
+        __0,
+        __1,
     )
 }
 
@@ -13034,28 +13168,1126 @@ fn __action152<
 >(
     errors: &'err mut Vec<usize>,
     input: &'input str,
-    __0: (usize, Expr<'input>, usize),
-    __1: (usize, alloc::vec::Vec<Expr<'input>>, usize),
-) -> Expr<'input>
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
+) -> Pattern<'input>
 {
-    let __start0 = __1.0.clone();
-    let __end0 = __1.2.clone();
-    let __temp0 = __action73(
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
         errors,
         input,
-        __1,
+        &__start0,
+        &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action41(
+    __action15(
         errors,
         input,
-        __0,
         __temp0,
-    )
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action153<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action16(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action154<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<Pattern<'input>>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, usize, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action17(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+        __3,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action155<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+    __1: (usize, usize, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action19(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action156<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Pattern<'input>, usize),
+    __2: (usize, usize, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action20(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action157<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action29(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action158<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, ProtoType<'input>, usize),
+    __2: (usize, usize, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action30(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action159<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+    __1: (usize, usize, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action32(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action160<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, usize, usize),
+    __3: (usize, ProtoType<'input>, usize),
+) -> (&'input str, ProtoType<'input>, Span)
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __1.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action10(
+        errors,
+        input,
+        __0,
+        __temp0,
+        __1,
+        __2,
+        __3,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action161<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, core::option::Option<Vec<&'input str>>, usize),
+    __3: (usize, &'input str, usize),
+    __4: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
+    __5: (usize, usize, usize),
+) -> Binding<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action6(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+        __3,
+        __4,
+        __5,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action162<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, UnOpcode, usize),
+    __1: (usize, Expr<'input>, usize),
+    __2: (usize, usize, usize),
+) -> Expr<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action74(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action163<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Pattern<'input>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, Expr<'input>, usize),
+    __4: (usize, usize, usize),
+) -> Binding<'input>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action99(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action11(
+        errors,
+        input,
+        __temp0,
+        __0,
+        __1,
+        __2,
+        __3,
+        __4,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action164<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Expr<'input>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, Expr<'input>, usize),
+    __4: (usize, &'input str, usize),
+    __5: (usize, Expr<'input>, usize),
+    __6: (usize, &'input str, usize),
+) -> Expr<'input>
+{
+    let __start0 = __6.2.clone();
+    let __end0 = __6.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action143(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __3,
+        __4,
+        __5,
+        __6,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action165<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+    __2: (usize, &'input str, usize),
+) -> Expr<'input>
+{
+    let __start0 = __2.2.clone();
+    let __end0 = __2.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action144(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action166<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, Literal<'input>, usize),
+) -> Expr<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action145(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action167<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> Expr<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action146(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action168<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<Expr<'input>>, usize),
+    __2: (usize, &'input str, usize),
+) -> Expr<'input>
+{
+    let __start0 = __2.2.clone();
+    let __end0 = __2.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action147(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action169<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+) -> Expr<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action148(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action170<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, Expr<'input>, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, Expr<'input>, usize),
+    __3: (usize, &'input str, usize),
+) -> Expr<'input>
+{
+    let __start0 = __3.2.clone();
+    let __end0 = __3.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action149(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __3,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action171<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, &'input str, usize),
+    __4: (usize, Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+    __5: (usize, &'input str, usize),
+) -> Binding<'input>
+{
+    let __start0 = __1.2.clone();
+    let __end0 = __2.0.clone();
+    let __start1 = __5.2.clone();
+    let __end1 = __5.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    let __temp1 = __action94(
+        errors,
+        input,
+        &__start1,
+        &__end1,
+    );
+    let __temp1 = (__start1, __temp1, __end1);
+    __action150(
+        errors,
+        input,
+        __0,
+        __1,
+        __temp0,
+        __2,
+        __3,
+        __4,
+        __5,
+        __temp1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action172<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, Literal<'input>, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action151(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action173<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action152(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action174<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action153(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action175<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Vec<Pattern<'input>>, usize),
+    __2: (usize, &'input str, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __2.2.clone();
+    let __end0 = __2.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action154(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action176<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action155(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action177<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Pattern<'input>, usize),
+) -> Pattern<'input>
+{
+    let __start0 = __1.2.clone();
+    let __end0 = __1.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action156(
+        errors,
+        input,
+        __0,
+        __1,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action178<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action157(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action179<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, ProtoType<'input>, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __1.2.clone();
+    let __end0 = __1.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action158(
+        errors,
+        input,
+        __0,
+        __1,
+        __temp0,
+    )
 }
 
 #[allow(unused_variables)]
-fn __action153<
+fn __action180<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, __lalrpop_util::ErrorRecovery<usize, Token<'input>, &'static str>, usize),
+) -> ProtoType<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action159(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action181<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, ProtoType<'input>, usize),
+) -> (&'input str, ProtoType<'input>, Span)
+{
+    let __start0 = __1.2.clone();
+    let __end0 = __2.0.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action160(
+        errors,
+        input,
+        __0,
+        __1,
+        __temp0,
+        __2,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action182<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, &'input str, usize),
+    __2: (usize, core::option::Option<Vec<&'input str>>, usize),
+    __3: (usize, &'input str, usize),
+    __4: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
+) -> Binding<'input>
+{
+    let __start0 = __4.2.clone();
+    let __end0 = __4.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action161(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __3,
+        __4,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action183<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, UnOpcode, usize),
+    __1: (usize, Expr<'input>, usize),
+) -> Expr<'input>
+{
+    let __start0 = __1.2.clone();
+    let __end0 = __1.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action162(
+        errors,
+        input,
+        __0,
+        __1,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action184<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, Pattern<'input>, usize),
+    __2: (usize, &'input str, usize),
+    __3: (usize, Expr<'input>, usize),
+) -> Binding<'input>
+{
+    let __start0 = __3.2.clone();
+    let __end0 = __3.2.clone();
+    let __temp0 = __action94(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action163(
+        errors,
+        input,
+        __0,
+        __1,
+        __2,
+        __3,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action185<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, alloc::vec::Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+    __1: (usize, (alloc::vec::Vec<Pattern<'input>>, Expr<'input>), usize),
+) -> Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>
+{
+    let __start0 = __1.0.clone();
+    let __end0 = __1.2.clone();
+    let __temp0 = __action109(
+        errors,
+        input,
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action91(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action186<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, alloc::vec::Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>, usize),
+) -> Vec<(alloc::vec::Vec<Pattern<'input>>, Expr<'input>)>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action110(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action91(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action187<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, Expr<'input>, usize),
+) -> Expr<'input>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action72(
+        errors,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action41(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action188<
+    'input,
+    'err,
+>(
+    errors: &'err mut Vec<usize>,
+    input: &'input str,
+    __0: (usize, Expr<'input>, usize),
+    __1: (usize, alloc::vec::Vec<Expr<'input>>, usize),
+) -> Expr<'input>
+{
+    let __start0 = __1.0.clone();
+    let __end0 = __1.2.clone();
+    let __temp0 = __action73(
+        errors,
+        input,
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action41(
+        errors,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action189<
     'input,
     'err,
 >(
@@ -13073,7 +14305,7 @@ fn __action153<
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action95(
+    __action93(
         errors,
         input,
         __0,
@@ -13082,7 +14314,7 @@ fn __action153<
 }
 
 #[allow(unused_variables)]
-fn __action154<
+fn __action190<
     'input,
     'err,
 >(
@@ -13100,7 +14332,7 @@ fn __action154<
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action95(
+    __action93(
         errors,
         input,
         __0,
@@ -13109,7 +14341,7 @@ fn __action154<
 }
 
 #[allow(unused_variables)]
-fn __action155<
+fn __action191<
     'input,
     'err,
 >(
@@ -13136,7 +14368,7 @@ fn __action155<
 }
 
 #[allow(unused_variables)]
-fn __action156<
+fn __action192<
     'input,
     'err,
 >(
@@ -13161,7 +14393,7 @@ fn __action156<
 }
 
 #[allow(unused_variables)]
-fn __action157<
+fn __action193<
     'input,
     'err,
 >(
@@ -13171,18 +14403,18 @@ fn __action157<
     __1: (usize, &'input str, usize),
     __2: (usize, Vec<&'input str>, usize),
     __3: (usize, &'input str, usize),
-    __4: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize),
+    __4: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
 ) -> Binding<'input>
 {
     let __start0 = __2.0.clone();
     let __end0 = __2.2.clone();
-    let __temp0 = __action98(
+    let __temp0 = __action97(
         errors,
         input,
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action6(
+    __action182(
         errors,
         input,
         __0,
@@ -13194,7 +14426,7 @@ fn __action157<
 }
 
 #[allow(unused_variables)]
-fn __action158<
+fn __action194<
     'input,
     'err,
 >(
@@ -13203,19 +14435,19 @@ fn __action158<
     __0: (usize, &'input str, usize),
     __1: (usize, &'input str, usize),
     __2: (usize, &'input str, usize),
-    __3: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>)>, usize),
+    __3: (usize, alloc::vec::Vec<(&'input str, ProtoType<'input>, Span)>, usize),
 ) -> Binding<'input>
 {
     let __start0 = __1.2.clone();
     let __end0 = __2.0.clone();
-    let __temp0 = __action99(
+    let __temp0 = __action98(
         errors,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action6(
+    __action182(
         errors,
         input,
         __0,
@@ -13226,16 +14458,19 @@ fn __action158<
     )
 }
 
-pub trait __ToTriple<'input, 'err, > {
+pub trait __ToTriple<'input, 'err, >
+{
     fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>>;
 }
 
-impl<'input, 'err, > __ToTriple<'input, 'err, > for (usize, Token<'input>, usize) {
+impl<'input, 'err, > __ToTriple<'input, 'err, > for (usize, Token<'input>, usize)
+{
     fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
         Ok(value)
     }
 }
-impl<'input, 'err, > __ToTriple<'input, 'err, > for Result<(usize, Token<'input>, usize), &'static str> {
+impl<'input, 'err, > __ToTriple<'input, 'err, > for Result<(usize, Token<'input>, usize), &'static str>
+{
     fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
         match value {
             Ok(v) => Ok(v),
@@ -3,13 +3,16 @@
 //! 
 //! 
 
+use std::borrow::Cow;
 use std::collections::{
     HashMap,
     BTreeMap,
 };
 use crate::{
+    ast::Span,
+    diagnostic::Diagnostic,
     dtree::DTree,
-    types::{Type, Literal, BinOpcode, UnOpcode, TypeDecl},
+    types::{Type, TypeScheme, Literal, BinOpcode, UnOpcode, TypeDecl, TraitDecl},
 };
 
 /// represents a compilation module (a single file)
@@ -21,10 +24,13 @@ pub struct Module<'input> {
     /// e.g. (x, y) = (1, 2) is a single value. The BTreeMap has any
     /// literal constraints on global values e.g.
     /// (1, 2) = f 5;
-    pub globals: Vec<(Expr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, Type)>,
+    pub globals: Vec<(Expr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, TypeScheme)>,
     /// path of exported global name including top-level functions
     pub globals_names: HashMap<&'input str, ValPath>,
     pub type_decls: Vec<TypeDecl<'input>>,
+    pub traits: Vec<TraitDecl<'input>>,
+    /// warnings found while checking the module
+    pub warnings: Vec<Diagnostic>,
 }
 
 /// The path of a value. Together with the type, it can give the actual position
@@ -34,6 +40,9 @@ pub struct Module<'input> {
 /// In a sum(i, v) that has path p, [..p, 0] is the path of the tag of the type and
 /// [..p, i] is the path for the v in the ith variant. This means, [..p, 0] should be checked
 /// before accessing [..p, i] otherwise it can be unsafe.
+/// In a record with path p, the path of its ith declared field is [..p, i].
+/// In a string with path p that starts with a prefix of n bytes, [..p, n] is the path of the
+/// rest of the string, so the prefix should be checked first.
 /// A captured value has index in captured values and the capture path in parent scope
 /// if a closure captures a value from a higher scope, all closures in between have to capture it.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -55,6 +64,9 @@ pub enum ValPath {
     /// just a marker, constructors are not stored anywhere
     Constructor(u16, u16),
     Imported(&'static str),
+    /// the nth method of a trait. Uses are replaced by the method in the dictionary
+    /// of the instance, so it's never in a checked module
+    TraitMethod(u16, u16),
 }
 
 /// Represents both static (top-level functions) and dynamic closures
@@ -67,28 +79,86 @@ pub struct Closure<'input> {
     /// decision tree of args pattern matching
     pub dtree: DTree<'input>,
     pub branches: Vec<Expr<'input>>,
+    /// the guard of each branch, checked by the decision tree once its patterns
+    /// match
+    pub guards: Vec<Option<Expr<'input>>>,
+    /// source of the closure literal
+    pub span: Span,
 }
 
 /// A pattern is a set of constraints on a value, which are categorized as follows
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
 pub enum ConstraintValue<'input> {
     /// nth option out of x finitely many option, includes Booleans and union tags
-    /// indexing starting from 1 upto x.
+    /// indexing starting from 0 upto x - 1. false is 0 and true is 1
     Finite(u16, u16),
     /// integer constraint which is technically finite but represented sparsely, so
     /// is practically inifinite
     Int(isize),
+    /// character constraint, chars are too many to be treated as finite
+    Char(char),
     /// string constraint, we allow strings in pattern matching
-    Str(&'input str),
+    Str(Cow<'input, str>),
+    /// the integers from the first to the second inclusive
+    IntRange(isize, isize),
+    /// the characters from the first to the second inclusive
+    CharRange(char, char),
+    /// the strings starting with a prefix
+    StrPrefix(Cow<'input, str>),
+}
+
+impl<'input> ConstraintValue<'input> {
+    /// whether an integer meets the constraint
+    pub fn matches_int(&self, n: isize) -> bool {
+        match *self {
+            ConstraintValue::Int(m) => n == m,
+            ConstraintValue::IntRange(lo, hi) => lo <= n && n <= hi,
+            _ => false,
+        }
+    }
+
+    /// whether a character meets the constraint
+    pub fn matches_char(&self, c: char) -> bool {
+        match *self {
+            ConstraintValue::Char(d) => c == d,
+            ConstraintValue::CharRange(lo, hi) => lo <= c && c <= hi,
+            _ => false,
+        }
+    }
+
+    /// whether a string meets the constraint
+    pub fn matches_str(&self, s: &str) -> bool {
+        match self {
+            ConstraintValue::Str(t) => s == t,
+            ConstraintValue::StrPrefix(prefix) => s.starts_with(&**prefix),
+            _ => false,
+        }
+    }
+}
+
+/// An expression with the span of the source it was generated from
+#[derive(Debug)]
+pub struct Expr<'input> {
+    pub kind: ExprKind<'input>,
+    pub span: Span,
 }
 
 /// Representation of an expression
 #[derive(Debug)]
-pub enum Expr<'input> {
+pub enum ExprKind<'input> {
     Literal(Literal<'input>),
     /// named value
     Bound(ValPath),
     Tuple(Vec<Expr<'input>>),
+    /// record of the type target, with the fields in their declared order
+    Record(u16, Vec<Expr<'input>>),
+    /// the nth field of a record, or of a tuple of trait methods
+    Field(Box<Expr<'input>>, u16),
+    /// a copy of a record with the nth fields replaced
+    Update(Box<Expr<'input>>, Vec<(u16, Expr<'input>)>),
+    /// the list of the elements followed by the list e if any, otherwise by the
+    /// empty list
+    List(Vec<Expr<'input>>, Option<Box<Expr<'input>>>),
 
     Slice(Box<Expr<'input>>, Box<Expr<'input>>, Box<Expr<'input>>),
 
@@ -108,6 +178,20 @@ pub enum Expr<'input> {
 
     /// if e1 then e2 else e3
     Conditional(Box<Expr<'input>>, Box<Expr<'input>>, Box<Expr<'input>>),
+    /// evaluate e1 into the next local slot, check it against the pattern's decision
+    /// tree, then evaluate e2 with the slot in scope
+    Let(Box<Expr<'input>>, DTree<'input>, Box<Expr<'input>>),
+
+    /// the dictionary of a trait instance, or an overloaded value applied to its
+    /// dictionaries, which is filled in by the type checker once the types of
+    /// the top-level binding are solved
+    Overloaded(u32),
 
     Error,
+}
+
+impl<'input> Expr<'input> {
+    pub fn new(kind: ExprKind<'input>, span: Span) -> Self {
+        Expr { kind, span }
+    }
 }
\ No newline at end of file
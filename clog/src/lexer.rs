@@ -0,0 +1,550 @@
+//! A hand-written tokenizer for the grammar's external lexer interface.
+//!
+//! Comments are skipped here instead of being blanked out of the source, so
+//! offsets of tokens are offsets in the original source. Doc comments (`///`
+//! and `/** */`) right before a declaration are kept as tokens so the parser
+//! can attach them to it, and skipped like other comments anywhere else.
+//! Lexical errors don't stop the token stream: they are yielded as
+//! `Tok::Error` tokens the parser recovers from. A `[` right after an
+//! operand, as in `s[0]`, is an index and not the start of a list, so `f [0]`
+//! applies f to a list.
+
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt,
+    iter::Peekable,
+    str::CharIndices,
+};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lex(src: &str) -> Vec<Tok<'_>> {
+        Lexer::new(src).map(|t| t.unwrap().1).collect()
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            lex("let f = {x' => x' >= 10}() _a _"),
+            vec![
+                Tok::Let, Tok::Id("f"), Tok::Eq, Tok::LBrace, Tok::Id("x'"), Tok::FatArrow,
+                Tok::Id("x'"), Tok::GreaterEq, Tok::Int("10"), Tok::RBrace, Tok::Unit,
+                Tok::Id("_a"), Tok::Underscore,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            lex("s[0] f [x, ..l] x::l [1..2] [x | x <- l, x < -1]"),
+            vec![
+                Tok::Id("s"), Tok::Index, Tok::Int("0"), Tok::RBracket,
+                Tok::Id("f"), Tok::LBracket, Tok::Id("x"), Tok::Comma, Tok::DotDot, Tok::Id("l"), Tok::RBracket,
+                Tok::Id("x"), Tok::ColonColon, Tok::Id("l"),
+                Tok::LBracket, Tok::Int("1"), Tok::DotDot, Tok::Int("2"), Tok::RBracket,
+                Tok::LBracket, Tok::Id("x"), Tok::Bar, Tok::Id("x"), Tok::LArrow, Tok::Id("l"), Tok::Comma,
+                Tok::Id("x"), Tok::Less, Tok::Minus, Tok::Int("1"), Tok::RBracket,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!(
+            lex("l@(x :: _) | 1|2"),
+            vec![
+                Tok::Id("l"), Tok::At, Tok::LParen, Tok::Id("x"), Tok::ColonColon, Tok::Underscore, Tok::RParen,
+                Tok::Bar, Tok::Int("1"), Tok::Bar, Tok::Int("2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            lex("1.5 1e-3 2E+4 0.25e2 7 1.f 3."),
+            vec![
+                Tok::Float("1.5"), Tok::Float("1e-3"), Tok::Float("2E+4"), Tok::Float("0.25e2"), Tok::Int("7"),
+                Tok::Int("1"), Tok::Dot, Tok::Id("f"), Tok::Int("3"), Tok::Dot,
+            ]
+        );
+        assert_eq!(
+            lex("12n 3 n 4nx 99999999999999999999"),
+            vec![
+                Tok::BigInt("12"), Tok::Int("3"), Tok::Id("n"), Tok::Int("4"), Tok::Id("nx"),
+                Tok::Error(LexError::IntTooLarge),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let src = "a // b\n/* c /* nested */ d */ e\n/// doc\n//// not doc\n/** block doc */ let";
+        assert_eq!(
+            lex(src),
+            vec![Tok::Id("a"), Tok::Id("e"), Tok::DocComment(" doc"), Tok::DocComment(" block doc "), Tok::Let]
+        );
+        let spans: Vec<_> = Lexer::new(src).map(|t| t.unwrap()).map(|(l, _, r)| &src[l..r]).collect();
+        assert_eq!(spans, ["a", "e", "/// doc", "/** block doc */", "let"]);
+        // doc comments that aren't before a declaration are skipped
+        assert_eq!(
+            lex("= /// a\nlet x /// b\n+ /** c */ y /// d"),
+            vec![Tok::Eq, Tok::Let, Tok::Id("x"), Tok::Plus, Tok::Id("y")]
+        );
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(lex(r#""a // b""#), vec![Tok::Str(Cow::Borrowed("a // b"))]);
+        assert_eq!(
+            lex(r#""a\n\t\"\\\u{623}""#),
+            vec![Tok::Str(Cow::Owned("a\n\t\"\\\u{623}".to_owned()))]
+        );
+    }
+
+    #[test]
+    fn test_chars() {
+        assert_eq!(
+            lex(r"'a' '\n' '\'' '\u{623}' 'أ' x'"),
+            vec![Tok::Char('a'), Tok::Char('\n'), Tok::Char('\''), Tok::Char('\u{623}'), Tok::Char('أ'), Tok::Id("x'")]
+        );
+        assert_eq!(
+            lex("'' 'ab' 'a"),
+            vec![
+                Tok::Error(LexError::InvalidChar),
+                Tok::Error(LexError::InvalidChar),
+                Tok::Error(LexError::UnterminatedChar),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let src = "x # \"a\\q\" \"open";
+        let toks: Vec<_> = Lexer::new(src).map(|t| t.unwrap()).collect();
+        assert_eq!(toks[1], (2, Tok::Error(LexError::UnexpectedChar('#')), 3));
+        assert_eq!(toks[2], (4, Tok::Error(LexError::InvalidEscape('q')), 9));
+        assert_eq!(toks[3], (10, Tok::Error(LexError::UnterminatedString), 15));
+        assert_eq!(lex("/* /* */"), vec![Tok::Error(LexError::UnterminatedComment)]);
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Tok<'input> {
+    // keywords
+    Type, Let, Rec, Def, Trait, Instance,
+    In, Where, When, Abstract, With, Deriving,
+    If, Then, Else, End,
+    And, Or, Not,
+    True, False,
+    IntType, FloatType, BigIntType, BoolType, CharType, StringType,
+
+    Id(&'input str),
+    /// decimal digits of an integer literal that fits in an int
+    Int(&'input str),
+    /// decimal digits of a bigint literal, written with the suffix `n`
+    BigInt(&'input str),
+    /// a float literal, digits with a fraction, an exponent or both
+    Float(&'input str),
+    /// string literal with escapes processed
+    Str(Cow<'input, str>),
+    /// character literal with its escape processed, e.g. `'a'` or `'\n'`
+    Char(char),
+    /// text of a doc comment without its delimiters
+    DocComment(&'input str),
+
+    // punctuation
+    LParen, RParen, Unit,
+    LBrace, RBrace,
+    LBracket, RBracket,
+    /// a `[` with no space after an operand
+    Index,
+    Comma, Dot, DotDot, Colon, ColonColon, Bar, At, Underscore,
+    Eq, FatArrow, Arrow, LArrow,
+
+    // operators
+    Plus, Minus, Star, Slash, Percent, Concat,
+    Greater, Less, GreaterEq, LessEq, NotEq,
+
+    /// a lexical error, the parser reports it when it can't shift it
+    Error(LexError),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LexError {
+    UnexpectedChar(char),
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidEscape(char),
+    InvalidUnicodeEscape,
+    IntTooLarge,
+    UnterminatedChar,
+    /// a character literal with no or several characters
+    InvalidChar,
+}
+
+/// lexical errors are `Tok::Error` tokens, so getting the next token never fails
+pub type Spanned<'input> = Result<(usize, Tok<'input>, usize), Infallible>;
+
+#[derive(Clone)]
+pub struct Lexer<'input> {
+    src: &'input str,
+    chars: Peekable<CharIndices<'input>>,
+    /// the end of the last token if it ends an operand
+    operand_end: Option<usize>,
+    /// whether a declaration can start after the last token, not counting doc
+    /// comments
+    decl_can_follow: bool,
+}
+
+impl<'input> Lexer<'input> {
+    pub fn new(src: &'input str) -> Self {
+        Lexer { src, chars: src.char_indices().peekable(), operand_end: None, decl_can_follow: true }
+    }
+
+    /// offset of the next character
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.src.len(), |&(i, _)| i)
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    /// consume the next char if it's c
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek_char() == Some(c) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// the char after the next n chars
+    fn nth_char(&self, n: usize) -> Option<char> {
+        self.chars.clone().nth(n).map(|(_, c)| c)
+    }
+
+    /// an integer, bigint or float literal starting at start. A `.` is only part
+    /// of a float if a digit follows it, and likewise an `e` and its sign, so
+    /// `1.f` is a method call on 1. The suffix `n` makes an integer a bigint. A minus
+    /// is never part of a literal, so the least int can't be written as one, but as
+    /// `-9223372036854775807 - 1`
+    fn number(&mut self, start: usize) -> Tok<'input> {
+        let mut end = self.take_while(|c| c.is_ascii_digit());
+        let mut float = false;
+        if self.peek_char() == Some('.') && self.nth_char(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.chars.next();
+            end = self.take_while(|c| c.is_ascii_digit());
+            float = true;
+        }
+        if matches!(self.peek_char(), Some('e' | 'E')) {
+            let digits = if matches!(self.nth_char(1), Some('+' | '-')) { 2 } else { 1 };
+            if self.nth_char(digits).is_some_and(|c| c.is_ascii_digit()) {
+                for _ in 0..digits {
+                    self.chars.next();
+                }
+                end = self.take_while(|c| c.is_ascii_digit());
+                float = true;
+            }
+        }
+        let s = &self.src[start..end];
+        if float {
+            Tok::Float(s)
+        } else if self.peek_char() == Some('n') && !self.nth_char(1).is_some_and(is_id_char) {
+            self.chars.next();
+            Tok::BigInt(s)
+        } else if s.parse::<isize>().is_err() {
+            Tok::Error(LexError::IntTooLarge)
+        } else {
+            Tok::Int(s)
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> usize {
+        while let Some(c) = self.peek_char() {
+            if !f(c) {
+                break;
+            }
+            self.chars.next();
+        }
+        self.offset()
+    }
+
+    /// whether a doc comment that was just skipped is right before a declaration,
+    /// which the parser attaches it to. In `{ x =>\n /// y\n let z = x in z }`
+    /// no declaration can follow `=>`, so the `let` starts an expression
+    fn documents_decl(&self) -> bool {
+        self.decl_can_follow
+            && match self.clone().next() {
+                Some(Ok((_, tok, _))) => starts_decl(&tok) || matches!(tok, Tok::DocComment(_)),
+                _ => false,
+            }
+    }
+
+    /// skip a line comment after its `//`, returning the doc text for a `///`
+    /// before a declaration
+    fn line_comment(&mut self, start: usize) -> Option<Tok<'input>> {
+        let end = self.take_while(|c| c != '\n');
+        let text = &self.src[start + 2..end];
+        if text.starts_with('/') && !text.starts_with("//") && self.documents_decl() {
+            Some(Tok::DocComment(&text[1..]))
+        } else {
+            None
+        }
+    }
+
+    /// skip a (nested) block comment after its `/*`, returning the doc text for a
+    /// `/**` before a declaration
+    fn block_comment(&mut self, start: usize) -> Option<Tok<'input>> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.chars.next() {
+                None => return Some(Tok::Error(LexError::UnterminatedComment)),
+                Some((_, '/')) if self.eat('*') => depth += 1,
+                Some((_, '*')) if self.eat('/') => depth -= 1,
+                Some(_) => (),
+            }
+        }
+        let text = &self.src[start + 2..self.offset() - 2];
+        if text.starts_with('*') && !text.starts_with("**") && text != "*" && self.documents_decl() {
+            Some(Tok::DocComment(&text[1..]))
+        } else {
+            None
+        }
+    }
+
+    /// lex a string literal after its opening quote
+    fn string(&mut self, start: usize) -> Tok<'input> {
+        // borrow from the source unless there is an escape to process
+        let mut owned: Option<String> = None;
+        let mut error = None;
+        loop {
+            match self.chars.next() {
+                None => return Tok::Error(LexError::UnterminatedString),
+                Some((i, '"')) => {
+                    return match (error, owned) {
+                        (Some(e), _) => Tok::Error(e),
+                        (None, Some(s)) => Tok::Str(Cow::Owned(s)),
+                        (None, None) => Tok::Str(Cow::Borrowed(&self.src[start + 1..i])),
+                    }
+                }
+                Some((i, '\\')) => {
+                    let s = owned.get_or_insert_with(|| self.src[start + 1..i].to_owned());
+                    match self.chars.next() {
+                        None => return Tok::Error(LexError::UnterminatedString),
+                        Some((_, c)) => match escape(c, &mut self.chars) {
+                            Ok(c) => s.push(c),
+                            Err(e) => { error.get_or_insert(e); },
+                        },
+                    }
+                }
+                Some((_, c)) => {
+                    if let Some(ref mut s) = owned {
+                        s.push(c)
+                    }
+                }
+            }
+        }
+    }
+
+    /// lex a character literal after its opening quote, which holds a
+    /// character or an escape like those of strings
+    fn char_literal(&mut self) -> Tok<'input> {
+        let c = match self.chars.next() {
+            None | Some((_, '\n')) => return Tok::Error(LexError::UnterminatedChar),
+            Some((_, '\'')) => return Tok::Error(LexError::InvalidChar),
+            Some((_, '\\')) => match self.chars.next() {
+                None => return Tok::Error(LexError::UnterminatedChar),
+                Some((_, c)) => escape(c, &mut self.chars),
+            },
+            Some((_, c)) => Ok(c),
+        };
+        if !self.eat('\'') {
+            // skip the rest of a literal with several characters
+            self.take_while(|c| c != '\'' && c != '\n');
+            return Tok::Error(if self.eat('\'') { LexError::InvalidChar } else { LexError::UnterminatedChar });
+        }
+        match c {
+            Ok(c) => Tok::Char(c),
+            Err(e) => Tok::Error(e),
+        }
+    }
+}
+
+/// the character an escape sequence stands for, given the char after the backslash
+fn escape(c: char, chars: &mut Peekable<CharIndices>) -> Result<char, LexError> {
+    match c {
+        'n' => Ok('\n'),
+        't' => Ok('\t'),
+        'r' => Ok('\r'),
+        '0' => Ok('\0'),
+        '\\' | '"' | '\'' => Ok(c),
+        'u' => {
+            if chars.next_if(|&(_, c)| c == '{').is_none() {
+                return Err(LexError::InvalidUnicodeEscape);
+            }
+            let mut code = String::new();
+            while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_hexdigit()) {
+                code.push(c);
+            }
+            if chars.next_if(|&(_, c)| c == '}').is_none() || code.is_empty() || code.len() > 6 {
+                return Err(LexError::InvalidUnicodeEscape);
+            }
+            u32::from_str_radix(&code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(LexError::InvalidUnicodeEscape)
+        }
+        _ => Err(LexError::InvalidEscape(c)),
+    }
+}
+
+/// whether a `[` right after tok indexes the operand tok ends
+fn ends_operand(tok: &Tok) -> bool {
+    matches!(
+        tok,
+        Tok::Id(_) | Tok::Int(_) | Tok::Float(_) | Tok::BigInt(_) | Tok::Char(_) | Tok::Str(_)
+            | Tok::True | Tok::False | Tok::Unit | Tok::RParen | Tok::RBracket | Tok::RBrace
+    )
+}
+
+/// whether a declaration starts with tok
+fn starts_decl(tok: &Tok) -> bool {
+    matches!(tok, Tok::Type | Tok::Let | Tok::Rec | Tok::Def | Tok::Trait | Tok::Instance)
+}
+
+/// whether a declaration can come right after tok, which ends an expression, a
+/// type or the declarations of an abstract type, or starts the latter
+fn precedes_decl(tok: &Tok) -> bool {
+    ends_operand(tok)
+        || matches!(
+            tok,
+            Tok::IntType | Tok::FloatType | Tok::BigIntType | Tok::BoolType | Tok::CharType | Tok::StringType
+                | Tok::End | Tok::With
+        )
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '\''
+}
+
+fn keyword(id: &str) -> Option<Tok<'static>> {
+    Some(match id {
+        "type" => Tok::Type,
+        "let" => Tok::Let,
+        "rec" => Tok::Rec,
+        "def" => Tok::Def,
+        "trait" => Tok::Trait,
+        "instance" => Tok::Instance,
+        "in" => Tok::In,
+        "where" => Tok::Where,
+        "when" => Tok::When,
+        "abstract" => Tok::Abstract,
+        "with" => Tok::With,
+        "deriving" => Tok::Deriving,
+        "if" => Tok::If,
+        "then" => Tok::Then,
+        "else" => Tok::Else,
+        "end" => Tok::End,
+        "and" => Tok::And,
+        "or" => Tok::Or,
+        "not" => Tok::Not,
+        "true" => Tok::True,
+        "false" => Tok::False,
+        "int" => Tok::IntType,
+        "float" => Tok::FloatType,
+        "bigint" => Tok::BigIntType,
+        "bool" => Tok::BoolType,
+        "char" => Tok::CharType,
+        "string" => Tok::StringType,
+        "_" => Tok::Underscore,
+        _ => return None,
+    })
+}
+
+impl<'input> Iterator for Lexer<'input> {
+    type Item = Spanned<'input>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, c) = self.chars.next()?;
+            let tok = match c {
+                c if c.is_whitespace() => continue,
+                '/' if self.eat('/') => match self.line_comment(start) {
+                    Some(tok) => tok,
+                    None => continue,
+                },
+                '/' if self.eat('*') => match self.block_comment(start) {
+                    Some(tok) => tok,
+                    None => continue,
+                },
+                '"' => self.string(start),
+                '\'' => self.char_literal(),
+                c if c.is_ascii_digit() => self.number(start),
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let end = self.take_while(is_id_char);
+                    let id = &self.src[start..end];
+                    keyword(id).unwrap_or(Tok::Id(id))
+                }
+                '(' if self.eat(')') => Tok::Unit,
+                '(' => Tok::LParen,
+                ')' => Tok::RParen,
+                '{' => Tok::LBrace,
+                '}' => Tok::RBrace,
+                '[' if self.operand_end == Some(start) => Tok::Index,
+                '[' => Tok::LBracket,
+                ']' => Tok::RBracket,
+                ',' => Tok::Comma,
+                '.' if self.eat('.') => Tok::DotDot,
+                '.' => Tok::Dot,
+                ':' if self.eat(':') => Tok::ColonColon,
+                ':' => Tok::Colon,
+                '|' => Tok::Bar,
+                '@' => Tok::At,
+                '=' if self.eat('>') => Tok::FatArrow,
+                '=' => Tok::Eq,
+                '-' if self.eat('>') => Tok::Arrow,
+                '-' => Tok::Minus,
+                '+' if self.eat('+') => Tok::Concat,
+                '+' => Tok::Plus,
+                '*' => Tok::Star,
+                '/' => Tok::Slash,
+                '%' => Tok::Percent,
+                '>' if self.eat('=') => Tok::GreaterEq,
+                '>' => Tok::Greater,
+                '<' if self.eat('=') => Tok::LessEq,
+                '<' if self.eat('-') => Tok::LArrow,
+                '<' => Tok::Less,
+                '!' if self.eat('=') => Tok::NotEq,
+                c => Tok::Error(LexError::UnexpectedChar(c)),
+            };
+            let end = self.offset();
+            self.operand_end = if ends_operand(&tok) { Some(end) } else { None };
+            if !matches!(tok, Tok::DocComment(_)) {
+                self.decl_can_follow = precedes_decl(&tok);
+            }
+            return Some(Ok((start, tok, end)));
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar(c) => write!(f, "unexpected character `{}`", c.escape_default()),
+            LexError::UnterminatedString => write!(f, "unterminated string literal"),
+            LexError::UnterminatedComment => write!(f, "unterminated block comment"),
+            LexError::InvalidEscape(c) => write!(f, "unknown escape sequence `\\{}`", c.escape_default()),
+            LexError::InvalidUnicodeEscape => write!(f, "invalid unicode escape, expected `\\u{{...}}`"),
+            LexError::UnterminatedChar => write!(f, "unterminated character literal"),
+            LexError::InvalidChar => write!(f, "a character literal must contain exactly one character"),
+            LexError::IntTooLarge => write!(f, "integer literal is too large for `int`, add the suffix `n` for a `bigint`"),
+        }
+    }
+}
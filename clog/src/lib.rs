@@ -1,9 +1,16 @@
 //#![warn(missing_docs)]
+// unit tests are kept at the top of their modules
+#![allow(clippy::items_after_test_module)]
 pub mod ast;
+#[allow(clippy::all)]
 pub mod grammar;
+pub mod lexer;
 pub mod error;
+pub mod diagnostic;
 pub mod types;
+pub mod bigint;
 pub mod type_check;
+mod derive;
 pub mod imper_ast;
 mod unify;
 pub mod dtree;
@@ -1,41 +1,43 @@
 //! A namescope Module. A namescope is a series of nested scopes each having its bound
 //! names. A scope can capture a name from a parent scope.
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::{
-    types::Type,
+    types::{TypeScheme, TypeVar},
     imper_ast::ValPath,
+    unify::Substitution,
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::iter::FromIterator;
+    use crate::types::Type;
     #[test]
     fn test() {
         let ns0 = NameScope { head: Some(Box::new(ScopeList {
             local: HashMap::from_iter(vec![
-                ("foxbar", (ValPath::Constructor(0, 1), Type::Bool)),
-                ("cnnbar", (ValPath::Local(vec![0]), Type::Bool)),
+                ("foxbar", (ValPath::Constructor(0, 1), TypeScheme::mono(Type::Bool))),
+                ("cnnbar", (ValPath::Local(vec![0]), TypeScheme::mono(Type::Bool))),
             ]),
             captures_sz: 0,
             parent: NameScope { head: None },
         }))};
         let ns1 = NameScope { head: Some(Box::new(ScopeList {
             local: HashMap::from_iter(vec![
-                ("bar", (ValPath::Local(vec![0]), Type::Bool)),
-                ("foobar", (ValPath::CaptureLocal(0, vec![0]), Type::Bool)),
+                ("bar", (ValPath::Local(vec![0]), TypeScheme::mono(Type::Bool))),
+                ("foobar", (ValPath::CaptureLocal(0, vec![0]), TypeScheme::mono(Type::Bool))),
             ]),
             captures_sz: 1,
             parent: ns0,
         }))};
         let mut ns = NameScope { head: Some(Box::new(ScopeList {
-            local: HashMap::from_iter(vec![("foo", (ValPath::Local(vec![0]), Type::Bool))]),
+            local: HashMap::from_iter(vec![("foo", (ValPath::Local(vec![0]), TypeScheme::mono(Type::Bool)))]),
             captures_sz: 0,
             parent: ns1,
         }))};
-        assert_eq!(ns.get("foo").unwrap(), &(ValPath::Local(vec![0]), Type::Bool));
+        assert_eq!(ns.get("foo").unwrap(), &(ValPath::Local(vec![0]), TypeScheme::mono(Type::Bool)));
         assert_eq!(ns.head.as_ref().unwrap().captures_sz, 0);
-        assert_eq!(ns.get("bar").unwrap(), &(ValPath::CaptureLocal(0, vec![0]), Type::Bool));
+        assert_eq!(ns.get("bar").unwrap(), &(ValPath::CaptureLocal(0, vec![0]), TypeScheme::mono(Type::Bool)));
         assert_eq!(ns.head.as_ref().unwrap().captures_sz, 1);
         // add test cases
     }
@@ -46,7 +48,7 @@ pub struct NameScope<'input> {
 }
 
 struct ScopeList<'input> {
-    pub local: HashMap<&'input str, (ValPath, Type)>,
+    pub local: HashMap<&'input str, (ValPath, TypeScheme)>,
     captures_sz: u16,
     parent: NameScope<'input>,
 }
@@ -68,16 +70,16 @@ impl<'input> NameScope<'input> {
         }))}
     }
 
-    pub fn local(&mut self) -> &mut HashMap<&'input str, (ValPath, Type)> {
+    pub fn local(&mut self) -> &mut HashMap<&'input str, (ValPath, TypeScheme)> {
         &mut self.head.as_mut().unwrap().local
     }
 
-    fn iter(&self) -> Iter {
-        Iter { next: self.head.as_ref().map(|node| &**node) }
+    fn iter(&self) -> Iter<'_, 'input> {
+        Iter { next: self.head.as_deref() }
     }
 
     fn iter_mut<'a>(&'a mut self) -> IterMut<'a, 'input> {
-        IterMut { next: self.head.as_mut().map(|node| &mut **node) }
+        IterMut { next: self.head.as_deref_mut() }
     }
 
     pub fn push_layer(&mut self) {
@@ -89,7 +91,7 @@ impl<'input> NameScope<'input> {
         self.head = Some(node);
     }
 
-    pub fn pop_layer(&mut self) -> HashMap<&'input str, (ValPath, Type)> {
+    pub fn pop_layer(&mut self) -> HashMap<&'input str, (ValPath, TypeScheme)> {
         self.head.take().map(|node| {
             *self = node.parent;
             node.local
@@ -97,13 +99,26 @@ impl<'input> NameScope<'input> {
     }
 
     pub fn drain_local(&mut self) {
-        self.head.as_mut().unwrap().local.retain(|_, v| if let (ValPath::Local(_), _) = v { false } else { true })
+        self.head.as_mut().unwrap().local.retain(|_, v| !matches!(v, (ValPath::Local(_), _)))
     }
 
-    pub fn extend_local(&mut self, map: HashMap<&'input str, (ValPath, Type)>) {
+    pub fn extend_local(&mut self, map: HashMap<&'input str, (ValPath, TypeScheme)>) {
         self.head.as_mut().unwrap().local.extend(map)
     }
 
+    /// type variables free in any scope after applying the substitution
+    pub fn free_vars(&self, subst: &Substitution) -> HashSet<TypeVar> {
+        let mut vars = HashSet::new();
+        for (local, _) in self.iter() {
+            for (_, scheme) in local.values() {
+                let mut scheme = scheme.clone();
+                scheme.t.substitute_vars(subst);
+                scheme.free_vars(&mut vars);
+            }
+        }
+        vars
+    }
+
     pub fn _exists(&self, key: &str) -> bool {
         for (ns, _) in self.iter() {
             if ns.get(key).is_some() {
@@ -116,7 +131,7 @@ impl<'input> NameScope<'input> {
     /// Get a name from a namescope, doing all captures as necessary
     /// # FUTURE
     /// after nll conditional control flow, remove unsafe
-    pub fn get(&mut self, key: &'input str) -> Option<&(ValPath, Type)> {
+    pub fn get(&mut self, key: &'input str) -> Option<&(ValPath, TypeScheme)> {
         if let Some(val) = self.head.as_mut().unwrap().local.get(key) {
             return unsafe {
                 Some(&*(val as *const _))
@@ -129,7 +144,8 @@ impl<'input> NameScope<'input> {
                 None => (),
                 Some(val @ (ValPath::StaticVal(_), _)) 
                 | Some(val @ (ValPath::Constructor(..), _))
-                | Some(val @ (ValPath::Imported(..), _)) => 
+                | Some(val @ (ValPath::Imported(..), _))
+                | Some(val @ (ValPath::TraitMethod(..), _)) => 
                     return unsafe { Some(&*(val as *const _)) },
                 Some(val) => {
                     result = Some(val);
@@ -138,10 +154,7 @@ impl<'input> NameScope<'input> {
             }
             lengths.push(captures_sz);
         }
-        if result.is_none() {
-            return None
-        }
-        let (path, t) = result.unwrap().clone();
+        let (path, t) = result?.clone();
         let mut namescopes = self.iter_mut();
         for len in lengths {
             let (map, captures_sz) = namescopes.next().unwrap();
@@ -156,14 +169,14 @@ impl<'input> NameScope<'input> {
 
 /// inserts a captured value in self.local given its path in parent and update capture_sz
 fn insert_captured<'input>(
-    map: &mut HashMap<&'input str, (ValPath, Type)>, 
+    map: &mut HashMap<&'input str, (ValPath, TypeScheme)>, 
     captures_sz: &mut u16,
-    key: &'input str, path_up: &ValPath, t: Type
-) -> Option<(ValPath, Type)> {
+    key: &'input str, path_up: &ValPath, t: TypeScheme
+) -> Option<(ValPath, TypeScheme)> {
     let path_down = match path_up {
-        &ValPath::Local(ref v) => ValPath::CaptureLocal(*captures_sz, v.clone()),
-        &ValPath::CaptureLocal(u, _) | &ValPath::CaptureCaptured(u, _) => 
-            ValPath::CaptureCaptured(*captures_sz, u),
+        ValPath::Local(v) => ValPath::CaptureLocal(*captures_sz, v.clone()),
+        ValPath::CaptureLocal(u, _) | ValPath::CaptureCaptured(u, _) =>
+            ValPath::CaptureCaptured(*captures_sz, *u),
         _ => panic!("Capture static not expected"),
         
     };
@@ -172,22 +185,22 @@ fn insert_captured<'input>(
 }
 
 impl<'a, 'input> Iterator for Iter<'a, 'input> {
-    type Item = (&'a HashMap<&'input str, (ValPath, Type)>, u16);
+    type Item = (&'a HashMap<&'input str, (ValPath, TypeScheme)>, u16);
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.take().map(|node| {
-            self.next = node.parent.head.as_ref().map(|node| &**node);
+            self.next = node.parent.head.as_deref();
             (&node.local, node.captures_sz)
         })
     }
 }
 
 impl<'a, 'input> Iterator for IterMut<'a, 'input> {
-    type Item =  (&'a mut HashMap<&'input str, (ValPath, Type)>, &'a mut u16);
+    type Item =  (&'a mut HashMap<&'input str, (ValPath, TypeScheme)>, &'a mut u16);
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.take().map(|node| {
-            self.next = node.parent.head.as_mut().map(|node| &mut **node);
+            self.next = node.parent.head.as_deref_mut();
             (&mut node.local, &mut node.captures_sz)
         })
     }
@@ -1,57 +1,140 @@
-use regex::Regex;
-use lalrpop_util::ParseError;
+use std::convert::Infallible;
+use lalrpop_util::{ErrorRecovery, ParseError};
 use crate::{
-    ast::Binding,
+    ast::{Binding, Span},
+    diagnostic::Diagnostic,
     grammar::ProgramParser,
+    lexer::{Lexer, LexError, Tok},
 };
 
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::{ast::{BindingKind, Expr, ExprKind}, types::Literal};
+
     #[test]
-    fn test() {
-        let s = "";
+    fn test_comments_in_strings() {
+        let src = "// comment\nlet s = \"http://a\\n\" /* block */";
+        let ast = parse(src).unwrap();
+        match &ast[0].kind {
+            BindingKind::Value(_, Expr { kind: ExprKind::Literal(lit), span }, _) => {
+                assert_eq!(*lit, Literal::String("http://a\n".into()));
+                assert_eq!(&src[span.start..span.end], "\"http://a\\n\"");
+            }
+            _ => panic!("expected a string binding"),
+        }
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let ast = parse("/// the answer\n/// to everything\nlet x = 42").unwrap();
+        assert_eq!(ast[0].doc, [" the answer", " to everything"]);
+    }
+
+    #[test]
+    fn test_stray_doc_comments() {
+        // only doc comments before a declaration document it, the others are comments
+        let src = "let f = { x =>\n  /// note\n  x + 1 }\n\
+                   /// g\n\
+                   let g =\n  /// value\n  let y = 2 in y\n\
+                   /// trailing";
+        let ast = parse(src).unwrap();
+        assert_eq!(ast.len(), 2);
+        assert!(ast[0].doc.is_empty());
+        assert_eq!(ast[1].doc, [" g"]);
+        assert!(parse("let x = 1 /** end */").is_ok());
+    }
+
+    #[test]
+    fn test_lex_error() {
+        let src = "let x = \"\\q\"";
+        let errors = parse(src).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "E0002");
+        assert_eq!(&src[errors[0].span.start..errors[0].span.end], "\"\\q\"");
+    }
+
+    #[test]
+    fn test_recovery() {
+        let src = "let x = 1 +\nlet y = { a => a, b c d }\ntype t = | A int int\nrec f = { x => 3 * }\nlet z = (1, 2";
+        let errors = parse(src).unwrap_err();
+        let found: Vec<_> = errors.iter().map(|e| &src[e.span.start..e.span.end]).collect();
+        assert_eq!(found, ["let", "}", "int", "}", ""]);
+        assert!(errors[1].message.contains("`=>`"));
+        assert!(errors[1].message.ends_with("found `}`"));
+        assert!(errors[4].message.ends_with("found end of file"));
+    }
+
+    #[test]
+    fn test_extra_token() {
+        let src = "let x = 1 y";
+        let error = parse_error(ParseError::ExtraToken { token: (10, Tok::Id("y"), 11) }, src);
+        assert_eq!(error.message, "unexpected `y`");
     }
 }
 
-pub fn parse<'input>(input: &'input str) -> Result<Vec<Binding<'input>>, Vec<usize>> {
-    let parser = ProgramParser::new();
+pub fn parse(input: &str) -> Result<Vec<Binding<'_>>, Vec<Diagnostic>> {
     let mut errors = Vec::new();
-    if let Ok(ast) = parser.parse(&mut errors, input) {
-        return Ok(ast)
-    }
-    let newstr = find_replace(input, r#""(\\.|[^"\\])*""#, "\"\"");
-    let re = Regex::new(r"type |fn |rec fn").unwrap();
-    let mut index = 0;
-    while index < newstr.len() {
-        match parser.parse(&mut errors, &newstr[index..]) {
-            Ok(_) => return Err(errors),
-            Err(ParseError::InvalidToken { location }) => {
-                errors.push(index + location);
-                if let Some(m) = re.find_at(&newstr, location) {
-                    index = index + m.start();
-                } else {
-                    break;
-                }
-            }
-            Err(_) => panic!("Parser should have catched this error"),
-        };
+    let result = ProgramParser::new().parse(&mut errors, Lexer::new(input));
+    let mut diagnostics: Vec<_> = errors
+        .into_iter()
+        .map(|ErrorRecovery { error, .. }| parse_error(error, input))
+        .collect();
+    match result {
+        Ok(ast) if diagnostics.is_empty() => Ok(ast),
+        Ok(_) => Err(diagnostics),
+        Err(e) => {
+            diagnostics.push(parse_error(e, input));
+            Err(diagnostics)
+        }
+    }
+}
+
+fn parse_error(e: ParseError<usize, Tok, Infallible>, input: &str) -> Diagnostic {
+    match e {
+        ParseError::UnrecognizedToken { token: (l, Tok::Error(e), r), .. }
+        | ParseError::ExtraToken { token: (l, Tok::Error(e), r) } => lex_error(e, Span::new(l, r)),
+        ParseError::User { error } => match error {},
+        ParseError::UnrecognizedToken { token: (l, _, r), expected } => {
+            let found = format!("`{}`", &input[l..r]);
+            syntax_error(&expected, &found, Span::new(l, r))
+        }
+        ParseError::ExtraToken { token: (l, _, r) } => {
+            syntax_error(&[], &format!("`{}`", &input[l..r]), Span::new(l, r))
+        }
+        ParseError::UnrecognizedEOF { location, expected } => {
+            syntax_error(&expected, "end of file", Span::new(location, location))
+        }
+        ParseError::InvalidToken { location } => syntax_error(&[], "token", Span::new(location, location + 1)),
     }
-    Err(errors)
 }
 
-pub fn uncomment(src: &str) -> String {
-    find_replace(src, r"(//.*)|(/\*(.|\n)*?\*/)", "")
+/// a syntax error listing the tokens the parser expected instead of `found`
+fn syntax_error(expected: &[String], found: &str, span: Span) -> Diagnostic {
+    let expected: Vec<_> = expected.iter().map(|t| describe_terminal(t)).collect();
+    let message = match expected.len() {
+        0 => format!("unexpected {}", found),
+        1 => format!("expected {}, found {}", expected[0], found),
+        _ => format!("expected one of {}, found {}", expected.join(", "), found),
+    };
+    Diagnostic::error("E0001", message, span)
+}
+
+/// name of a terminal in the grammar as shown to the user
+fn describe_terminal(terminal: &str) -> String {
+    match terminal {
+        "ID" => "identifier".to_owned(),
+        "INT" => "integer literal".to_owned(),
+        "FLOAT" => "float literal".to_owned(),
+        "BIGINT" => "bigint literal".to_owned(),
+        "CHAR" => "character literal".to_owned(),
+        "INDEX" => "`[`".to_owned(),
+        "STR" => "string literal".to_owned(),
+        "DOC" => "doc comment".to_owned(),
+        _ => format!("`{}`", terminal.trim_matches('"')),
+    }
 }
 
-fn find_replace(src: &str, re: &str, replace: &str) -> String {
-    let re = Regex::new(re).unwrap();
-    let mut dst = String::new();
-    let mut offset = 0;
-    for mat in re.find_iter(src) {
-        dst += &src[offset..mat.start()];
-        dst += replace;
-        offset = mat.end();
-    }
-    dst += &src[offset..];
-    dst
-}
\ No newline at end of file
+fn lex_error(e: LexError, span: Span) -> Diagnostic {
+    Diagnostic::error("E0002", e.to_string(), span)
+}
@@ -3,16 +3,26 @@
 //! This module contains the logic for transforming a compilation unit from AST
 //! to imperAST.
 
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    convert::TryFrom,
+    sync::Arc,
+};
 
 use crate::{
-    ast::{Binding, Expr, Pattern},
-    dtree::DTree,
+    ast::{self, Binding, BindingKind, Expr, ExprKind, Pattern, PatternKind, Qualifier, Span, TypeDef},
+    derive,
+    diagnostic::Diagnostic,
+    dtree::{DTree, PatternMatchErr},
     error::Error,
-    imper_ast::{Closure, ConstraintValue, Expr as iExpr, Module, ValPath},
+    imper_ast::{Closure, ConstraintValue, Expr as iExpr, ExprKind as iExprKind, Module, ValPath},
     namescope::NameScope,
-    types::{BinOpcode, Literal, ProtoType, Type, UnOpcode,  TypeDecl},
-    unify,
+    parse,
+    types::{
+        BinOpcode, Instance, Literal, ProtoType, TraitDecl, Type, TypeScheme, TypeVar, UnOpcode, TypeDecl, TypeKind,
+        LIST,
+    },
+    unify::{self, Substitution},
 };
 
 #[cfg(test)]
@@ -40,29 +50,33 @@ mod test {
     #[test]
     fn test_get_type_decl() {
         let vars = vec!["T"];
+        let span = Span::default();
         let variants = vec![
-            ("Nil", ProtoType::Unit),
+            ("Nil", ProtoType::Unit, span),
             (
                 "Node",
                 ProtoType::Tuple(vec![
                     ProtoType::Sum(
                         "List",
-                        Box::new(ProtoType::Generic("T")),
+                        Box::new(ProtoType::Generic("T", span)),
+                        span,
                     ),
                     ProtoType::Sum(
                         "List",
                         Box::new(ProtoType::Sum(
                             "BTree",
-                            Box::new(ProtoType::Generic("T")),
+                            Box::new(ProtoType::Generic("T", span)),
+                            span,
                         )),
+                        span,
                     ),
                 ]),
+                span,
             ),
         ];
-        let mut type_map = vec![("List", 0)].into_iter().collect();
-        let mut ns = NameScope::new();
-        let mut errors = Vec::new();
-        let dec = get_type_decl("BTree", vars, variants, &mut type_map, &mut ns, &mut errors);
+        let mut ctx = TypingContext::new();
+        ctx.declare_type("List", 1);
+        let dec = ctx.get_type_decl("BTree", vars, variants);
         assert_eq!(dec.name, "BTree");
         assert_eq!(dec.num_generics, 1);
         assert_eq!(
@@ -72,65 +86,224 @@ mod test {
                 (
                     "Node",
                     Type::Tuple(vec![
-                        Type::Sum(0, vec!(Type::Generic(0))),
-                        Type::Sum(0, vec!(Type::Sum(1, vec!(Type::Generic(0)))))
+                        Type::Sum(1, vec!(Type::Generic(0))),
+                        Type::Sum(1, vec!(Type::Sum(2, vec!(Type::Generic(0)))))
                     ])
                 )
             ]
         );
-        assert_eq!(type_map["BTree"], 1);
+        // the built-in list is the first type
+        assert_eq!(ctx.type_map["BTree"], 2);
         assert_eq!(
-            ns.get("Nil").unwrap(),
+            ctx.namescope.get("Nil").unwrap(),
             &(
-                ValPath::Constructor(1, 1),
-                Type::Constructor {
-                    target: 1,
+                ValPath::Constructor(2, 1),
+                TypeScheme::mono(Type::Constructor {
+                    target: 2,
                     position: 1,
-                }
+                })
             )
         );
         assert_eq!(
-            ns.get("Node").unwrap(),
-            &((<>)
-                ValPath::Constructor(1, 2),
-                Type::Constructor {
-                    target: 1,
+            ctx.namescope.get("Node").unwrap(),
+            &(
+                ValPath::Constructor(2, 2),
+                TypeScheme::mono(Type::Constructor {
+                    target: 2,
                     position: 2,
-                }
+                })
             )
         );
     }
 
+    #[test]
+    fn test_type_decl_errors() {
+        let src = "type Tree t = | Leaf () | Node (Forest t, t)\n\
+                   type Forest t = | Nil () | Cons (Tree t, Forest t)\n\
+                   type Pair (a, b) = | P (a, b)\n\
+                   type One a = | O a\n\
+                   let x = (O (1, 2), P (1, \"a\"), Node (Cons (Leaf (), Nil ()), 3))\n\
+                   type Bad t = | A Missing | B (Tree) | C (t int) | D (Pair int) | Leaf int\n\
+                   type Tree = | T ()";
+        let mut ctx = TypingContext::new();
+        let prelude = ctx.globals.len();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.to_string(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(
+            found,
+            [
+                ("cannot find type `Missing`".to_owned(), "Missing"),
+                ("type `Tree` expects 1 type argument, found 0".to_owned(), "Tree"),
+                ("type `t` expects 0 type arguments, found 1".to_owned(), "t int"),
+                ("type `Pair` expects 2 type arguments, found 1".to_owned(), "Pair int"),
+                ("constructor `Leaf` is defined more than once".to_owned(), "Leaf"),
+                ("type `Tree` is defined more than once".to_owned(), "type Tree = | T ()"),
+            ]
+        );
+        assert_eq!(ctx.type_decls[4].variants[0].1, Type::Generic(0));
+        assert_eq!(
+            ctx.globals[prelude].2.t,
+            Type::Tuple(vec![
+                Type::Sum(4, vec![Type::Tuple(vec![Type::Int, Type::Int])]),
+                Type::Sum(3, vec![Type::Int, Type::String]),
+                Type::Sum(1, vec![Type::Int]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        let src = "type List t = | nil () | cons (t, List t)\n\
+                   type Map (k, v) = List (k, v)\n\
+                   type Ints = Map (int, int)\n\
+                   let m = cons ((1, 2), nil ())\n\
+                   let n = { (cons ((k, v), _)) => k + v, (nil ()) => 0 } m\n\
+                   type Late = Later\n\
+                   type Later = [Pairs]\n\
+                   type Pairs = (int, int)\n\
+                   type Loop = (int, Loop)\n\
+                   type A = B -> int\n\
+                   type B = [C]\n\
+                   type C = (A, int)\n\
+                   type D = Map (A, int)\n\
+                   type Ints = | I int";
+        let mut ctx = TypingContext::new();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.to_string(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(
+            found,
+            [
+                ("type alias `Loop` expands to itself".to_owned(), "Loop"),
+                ("type alias `A` expands to itself through `B`, `C`".to_owned(), "A"),
+                ("type `Ints` is defined more than once".to_owned(), "type Ints = | I int"),
+            ]
+        );
+        let later = Type::Sum(LIST, vec![Type::Tuple(vec![Type::Int, Type::Int])]);
+        assert_eq!(ctx.type_decls[ctx.type_map["Late"] as usize].kind, TypeKind::Alias(later));
+        let pair = Type::Tuple(vec![Type::Int, Type::Int]);
+        assert_eq!(ctx.type_decls[ctx.type_map["Ints"] as usize].kind, TypeKind::Alias(Type::Sum(1, vec![pair])));
+    }
+
+    #[test]
+    fn test_duplicate_mismatches() {
+        let src = "let a = (1, 2)\nlet b = (true, false)\nlet c = { (x, y) => x ++ y } b";
+        let mut ctx = TypingContext::new();
+        for binding in crate::parse::parse(src).unwrap() {
+            ctx.add_binding(binding);
+        }
+        assert_eq!(ctx.errors.len(), 2);
+        let errors = ctx.export().unwrap_err();
+        let found: Vec<_> = errors.iter().map(|e| (e.code, &src[e.span.start..e.span.end])).collect();
+        assert_eq!(found, [("E0100", "b")]);
+    }
+
+    #[test]
+    fn test_record_decl() {
+        let src = "type Box t = { value: t, label: string, value: int }\n\
+                   type Tagged = { label: int }\n\
+                   let b = { value = 1, label = \"a\" }.value\n\
+                   let l = { label = 2 }.label";
+        let mut ctx = TypingContext::new();
+        let prelude = ctx.globals.len();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.to_string(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(found, [("field `value` is specified more than once".to_owned(), "value")]);
+        assert_eq!(ctx.type_decls[1].kind, TypeKind::Record);
+        assert_eq!(ctx.type_decls[1].variants, [("value", Type::Generic(0)), ("label", Type::String)]);
+        // the later record shadows label
+        assert_eq!(ctx.field_map["label"], (2, 0));
+        assert_eq!(ctx.globals[prelude].2.t, Type::Int);
+        assert_eq!(ctx.globals[prelude + 1].2.t, Type::Int);
+    }
+
+    #[test]
+    fn test_shared_fields() {
+        // records are resolved by all their fields, not the first one, and the names
+        // of a pattern of an ambiguous record are still bound
+        let src = "type Point = { x: int, y: int }\n\
+                   type Point3 = { x: int, y: int, z: int }\n\
+                   type Pos = { x: int, y: int }\n\
+                   type Size = { w: int, h: int }\n\
+                   let p = { x = 1, y = 2, z = 3 }\n\
+                   let q = { p with z = 4 }\n\
+                   let f = { { z } => z }\n\
+                   let a = { x = 1, y = 2 }\n\
+                   let g = { { x, y } => x + y }\n\
+                   let b = { x = 1, w = 2 }\n\
+                   let c = { p with y = 1 }";
+        let mut ctx = TypingContext::new();
+        let prelude = ctx.globals.len();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.to_string(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(
+            found,
+            [
+                ("the fields could be of record `Point` or `Pos`".to_owned(), "{ x = 1, y = 2 }"),
+                ("the fields could be of record `Point` or `Point3`".to_owned(), "{ x, y }"),
+                // the last record declaring the first field
+                ("record `Pos` has no field `w`".to_owned(), "w"),
+                ("missing field `y` of record `Pos`".to_owned(), "{ x = 1, w = 2 }"),
+                ("the fields could be of record `Point` or `Point3`".to_owned(), "{ p with y = 1 }"),
+            ]
+        );
+        let point3 = Type::Sum(ctx.type_map["Point3"], vec![]);
+        assert_eq!(ctx.globals[prelude].2.t, point3);
+        assert_eq!(ctx.globals[prelude + 1].2.t, point3);
+        assert_eq!(ctx.globals[prelude + 2].2.t, Type::Function(Box::new(point3), Box::new(Type::Int)));
+    }
+
+    #[test]
+    fn test_constrained_schemes() {
+        use self::Type::*;
+        let src = "let twice = { x => show x ++ show x }\n\
+                   let pair = { x y => (x = y, twice y) }\n\
+                   let ints = { x y => x + y = y }";
+        let mut ctx = TypingContext::new();
+        let prelude = ctx.globals.len();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        assert!(ctx.errors.is_empty());
+        assert_eq!(ctx.trait_map["Show"], SHOW);
+        let schemes: Vec<_> = ctx.globals[prelude..].iter().map(|(_, _, scheme)| scheme).collect();
+        assert_eq!(schemes[0].t, Function(Box::new(Generic(0)), Box::new(String)));
+        assert_eq!(schemes[0].constraints, [(SHOW, 0)]);
+        assert_eq!(schemes[1].constraints, [(EQ, 0), (SHOW, 0)]);
+        // compared by the interpreter, so it takes no dictionaries
+        assert!(schemes[2].constraints.is_empty());
+        assert!(matches!(ctx.globals[prelude + 2].0.kind, iExprKind::Closure(_)));
+        assert_eq!(ctx.namescope.get("twice").unwrap().1.constraints, [(SHOW, 0)]);
+    }
+
     #[test]
     fn test_pattern() {
-        use self::Pattern::*;
-        let pat = Tuple(vec![
-            SumVar(
+        use self::PatternKind::*;
+        let p = |kind| Pattern::new(kind, 0, 0);
+        let pat = p(Tuple(vec![
+            p(SumVar(
                 "cons",
-                Box::new(Tuple(vec![Bind("x"), Bind("L1")])),
-            ),
-            SumVar(
+                Box::new(p(Tuple(vec![p(Bind("x")), p(Bind("L1"))]))),
+            )),
+            p(SumVar(
                 "cons",
-                Box::new(Tuple(vec![Bind("y"), Bind("L2")])),
-            ),
-        ]);
-        let mut ns = NameScope::new();
-        ns.local().insert(
+                Box::new(p(Tuple(vec![p(Bind("y")), p(Bind("L2"))]))),
+            )),
+        ]));
+        let mut ctx = TypingContext::new();
+        ctx.namescope.local().insert(
             "cons",
             (
                 ValPath::Constructor(0, 2),
-                Type::Constructor {
-                    position: 2 as u16,
+                TypeScheme::mono(Type::Constructor {
+                    position: 2,
                     target: 0,
-                },
+                }),
             ),
         );
-        let mut errors = Vec::new();
-        let mut type_consts = Vec::new();
-        let mut val_consts = BTreeMap::new();
-        let mut type_decls = vec![TypeDecl {
+        let mut alts = vec![Alternative::default()];
+        ctx.type_decls = vec![TypeDecl {
             name: "List",
             num_generics: 1,
+            kind: TypeKind::Sum,
+            methods: HashMap::new(),
             variants: vec![
                 ("nil", Type::Unit),
                 (
@@ -139,108 +312,991 @@ mod test {
                 ),
             ],
         }];
-        let mut closures = Vec::new();
         let mut path = vec![1];
-        let mut args = Args {
-            namescope: &mut ns,
-            errors: &mut errors,
-            type_consts: &mut type_consts,
-            type_decls: &mut type_decls,
-            closures: &mut closures,
-        };
         pat.transform(
             10,
             20,
             &mut path,
-            &mut args,
+            &mut ctx,
             ValPath::Local,
-            &mut val_consts,
+            &mut alts,
         );
         assert_eq!(
-            args.namescope.get("x").unwrap(),
-            &(ValPath::Local(vec![1, 0, 2, 0]), Type::Variable(24))
+            ctx.namescope.get("x").unwrap(),
+            &(ValPath::Local(vec![1, 0, 2, 0]), TypeScheme::mono(Type::Variable(24)))
         );
         assert_eq!(
-            args.namescope.get("L1").unwrap(),
-            &(ValPath::Local(vec![1, 0, 2, 1]), Type::Variable(25))
+            ctx.namescope.get("L1").unwrap(),
+            &(ValPath::Local(vec![1, 0, 2, 1]), TypeScheme::mono(Type::Variable(25)))
         );
         assert_eq!(
-            args.namescope.get("y").unwrap(),
-            &(ValPath::Local(vec![1, 1, 2, 0]), Type::Variable(28))
+            ctx.namescope.get("y").unwrap(),
+            &(ValPath::Local(vec![1, 1, 2, 0]), TypeScheme::mono(Type::Variable(28)))
         );
         assert_eq!(
-            args.namescope.get("L2").unwrap(),
-            &(ValPath::Local(vec![1, 1, 2, 1]), Type::Variable(29))
+            ctx.namescope.get("L2").unwrap(),
+            &(ValPath::Local(vec![1, 1, 2, 1]), TypeScheme::mono(Type::Variable(29)))
         );
-        assert!(args.errors.is_empty());
+        assert!(ctx.errors.is_empty());
         assert_eq!(path, &[1]);
     }
+
+    #[test]
+    fn test_generalize() {
+        use self::Type::*;
+        let t = Function(Box::new(Variable(3)), Box::new(Tuple(vec![Variable(5), Variable(3)])));
+        // 5 is free in the environment so it stays a variable
+        let scheme = TypeScheme::generalize(t, &[5].iter().cloned().collect());
+        assert_eq!(scheme.num_generics, 1);
+        assert_eq!(
+            scheme.t,
+            Function(Box::new(Generic(0)), Box::new(Tuple(vec![Variable(5), Generic(0)])))
+        );
+        assert_eq!(
+            scheme.instantiate(7),
+            (Function(Box::new(Variable(7)), Box::new(Tuple(vec![Variable(5), Variable(7)]))), 8)
+        );
+    }
+
+    #[test]
+    fn test_let_polymorphism() {
+        use self::Type::*;
+        let src = "let p = let id = { x => x } in (id 1, id true)\n\
+                   let f = { y => let c = { z => y } in (c 1, c true) }\n\
+                   let g = { y => (w ++ \"\", w) where { w = y } }";
+        let mut ctx = TypingContext::new();
+        let prelude = ctx.globals.len();
+        for binding in crate::parse::parse(src).unwrap() {
+            ctx.add_binding(binding);
+        }
+        assert!(ctx.errors.is_empty());
+        let types: Vec<_> = ctx.globals[prelude..].iter().map(|(_, _, scheme)| scheme.t.clone()).collect();
+        assert_eq!(types[0], Tuple(vec![Int, Bool]));
+        assert_eq!(
+            types[1],
+            Function(Box::new(Generic(0)), Box::new(Tuple(vec![Generic(0), Generic(0)])))
+        );
+        assert_eq!(types[2], Function(Box::new(String), Box::new(Tuple(vec![String, String]))));
+
+        // y is free in the scope of c, so c can't be used at two types
+        let src = "let f = { y => let c = y in (c + 1, c ++ \"a\") }";
+        let mut ctx = TypingContext::new();
+        for binding in crate::parse::parse(src).unwrap() {
+            ctx.add_binding(binding);
+        }
+        assert!(matches!(ctx.errors[..], [Error::TypeMismatch(..)]));
+    }
+
+    #[test]
+    fn test_diagnostic_types() {
+        let src = "let f = { x => x x }\n\
+                   let g = { x => (x, x) ++ \"a\" }\n\
+                   let h = { x y => (x, y) } 1 2 3";
+        let mut ctx = TypingContext::new();
+        for binding in crate::parse::parse(src).unwrap() {
+            ctx.add_binding(binding);
+        }
+        let messages: Vec<_> = ctx.errors.iter().map(|e| e.to_diagnostic(&ctx.type_decls).message).collect();
+        assert_eq!(
+            messages,
+            [
+                "cannot construct the infinite type `a = a -> b`",
+                "mismatched types `string` and `(a, b)`",
+                "mismatched types `a -> b` and `(c, d)`",
+            ]
+        );
+        assert_eq!(format!("{:?}", Type::Tuple(vec![Type::Generic(25), Type::Generic(27)])), "(z, b1)");
+    }
+
+    #[test]
+    fn test_list_errors() {
+        let src = "let a = 1 :: 2\n\
+                   let b = [1, 'a']\n\
+                   let c = { [] => 0, [x] => 1 }\n\
+                   let d = { [x, ..r] => 1, [x] => 2, _ => 3 }\n\
+                   let e = { [x, ..r] => r, 5 => [] }\n\
+                   let f = { [] => 0, [x, ..r] => 1, [] => 2 }";
+        let mut ctx = TypingContext::new();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.code(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(
+            found,
+            [
+                ("E0100", "2"),
+                ("E0100", "'a'"),
+                ("E0110", "{ [] => 0, [x] => 1 }"),
+                ("E0111", "[x]"),
+                ("E0100", "[x, ..r]"),
+                ("E0111", "[]"),
+            ]
+        );
+        let message = ctx.errors[2].to_diagnostic(&ctx.type_decls).message;
+        assert_eq!(message, "non-exhaustive patterns: `(_ :: _ :: _)` not covered");
+    }
+
+    #[test]
+    fn test_long_lists() {
+        // the elements aren't nested, so the checker doesn't recurse once per element
+        let elements: Vec<_> = (0..5000).map(|n| n.to_string()).collect();
+        let src = format!("let a = [{}]\nlet b = {} :: a", elements.join(", "), elements.join(" :: "));
+        let mut ctx = TypingContext::new();
+        ctx.add_bindings(crate::parse::parse(&src).unwrap());
+        assert!(ctx.errors.is_empty());
+        let list = Type::Sum(LIST, vec![Type::Int]);
+        assert_eq!(ctx.namescope.get("a").unwrap().1.t, list);
+        assert_eq!(ctx.namescope.get("b").unwrap().1.t, list);
+    }
+
+    #[test]
+    fn test_errored_patterns() {
+        // a match with a rejected pattern isn't checked for unreachable arms and
+        // missing values, unlike one with errors in the arms' bodies
+        let src = "let a = { 5..1 => 1, 3 => 2, _ => 3 }\n\
+                   let b = { 1.0..2.0 => 1, _ => 2 }\n\
+                   let c = { 1.5 => 1, _ => 2 }\n\
+                   let d = { x y => 1, 2 => 2, _ => 3 }\n\
+                   type R = { x: int }\n\
+                   let e = { { z = 1 } => 1, { x = 1 } => 2, _ => 3 }\n\
+                   let f = { 'a'..1 => 1 }\n\
+                   let g = { 1 => \"a\" ++ 1, 1 => \"b\", _ => \"c\" }";
+        let mut ctx = TypingContext::new();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.code(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(
+            found,
+            [
+                ("E0134", "5..1"),
+                ("E0108", "1.0..2.0"),
+                ("E0108", "1.5"),
+                ("E0107", "_ => 3"),
+                ("E0107", "2 => 2"),
+                ("E0115", "z"),
+                ("E0100", "'a'..1"),
+                ("E0100", "1"),
+                ("E0111", "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mixed_patterns() {
+        // patterns of different types are reported instead of breaking the decision tree
+        let src = "let f = { 5 => 1, true => 2, _ => 3 }\n\
+                   let g = { 'a' => 1, true => 2, _ => 3 }\n\
+                   let h = { 1..3 => 1, true => 2, _ => 3 }\n\
+                   let i = { (1, 2) => 2, [a] => 1, _ => 3 }";
+        let mut ctx = TypingContext::new();
+        ctx.add_bindings(crate::parse::parse(src).unwrap());
+        let found: Vec<_> = ctx.errors.iter().map(|e| (e.code(), &src[e.span().start..e.span().end])).collect();
+        assert_eq!(found, [("E0100", "5"), ("E0100", "'a'"), ("E0100", "1..3"), ("E0100", "(1, 2)")]);
+    }
+}
+
+
+/// A pair of types and the span of the source that requires them to be equal
+type TypeConstraint = (Type, Type, Span);
+
+/// A record and the indices of some of its fields in it with their values
+type ResolvedFields<T> = (u16, Vec<(u16, T)>);
+
+/// Names shadowed by a local binding with their previous values if any
+type Shadowed<'input> = Vec<(&'input str, Option<(ValPath, TypeScheme)>)>;
+
+/// A match that misses some values, reported with a counterexample once the
+/// types of the top-level binding are solved
+struct Inexhaustive<'input> {
+    /// constraints on the matched values that no arm satisfies
+    missing: Vec<(ValPath, ConstraintValue<'input>)>,
+    /// first element of the path and type of each matched value
+    roots: Vec<(u16, Type)>,
+    span: Span,
+}
+
+/// The checks of a match, reported once the types of the top-level binding are
+/// solved unless its patterns have errors. A pattern with an error doesn't match
+/// what it was written to, so the checks would only report its consequences
+struct MatchReport<'input> {
+    /// spans of the patterns of the arms
+    patterns: Vec<Span>,
+    /// the unreachable arms and alternatives
+    redundant: Vec<Span>,
+    inexhaustive: Option<Inexhaustive<'input>>,
+}
+
+/// One of the ways a pattern matches, one for each combination of the alternatives
+/// of its or-patterns
+#[derive(Clone, Default)]
+struct Alternative<'input> {
+    /// constraints on the matched values
+    constraints: BTreeMap<ValPath, ConstraintValue<'input>>,
+    /// paths of the names bound inside or-patterns, which may differ between alternatives
+    bound: BTreeMap<&'input str, ValPath>,
+    /// spans of the alternatives of the or-patterns taken
+    taken: Vec<Span>,
+}
+
+/// What `e.name` refers to
+enum Member {
+    /// the record and index of a field
+    Field(u16, u16),
+    /// the global of a method
+    Method(u16),
+    /// the index and type of a method of a trait type
+    Trait(u16, Type),
+}
+
+/// Definitions written in the language that the checker relies on, e.g. the
+/// instance of Show for lists
+const PRELUDE: &str = include_str!("prelude.mal");
+
+/// The built-in trait of the types compared by `=`
+const EQ: u16 = 0;
+/// The built-in trait of the types with `show`
+const SHOW: u16 = 1;
+/// The built-in trait of the types ordered by `compare`
+const ORD: u16 = 2;
+
+/// The name the dictionaries of a top-level binding are bound to, slot 0 of its
+/// locals. It can't be written in the source
+const DICTS: &str = "#dicts";
+
+/// The prelude functions ranges and comprehensions are desugared into, bound
+/// under names that can't be written in the source so they can't be shadowed
+const RANGE: &str = "#range";
+const FLAT_MAP: &str = "#flat_map";
+
+/// A use of a trait at the type of a variable, resolved to the dictionary of an
+/// instance once the types of the top-level binding are solved
+struct Predicate {
+    trait_: u16,
+    var: TypeVar,
+    span: Span,
+    /// path of the dictionaries of the binding where the trait is used
+    dicts: ValPath,
+}
+
+/// What an `Overloaded` placeholder is filled in with
+enum Overload {
+    /// the dictionary of a predicate
+    Dict(usize),
+    /// an overloaded global applied to the dictionaries of the predicates of its constraints
+    Global(ValPath, Vec<usize>),
+    /// the binding being checked used in itself, applied to the path of its own
+    /// dictionaries if it takes any
+    Recursive(ValPath, ValPath, Span),
+    /// the conversion of a value of the type of the variable to a supertype of it,
+    /// the type expected where the value is used
+    Coercion(TypeVar, Type, Span),
+    /// the closure of a resolved coercion
+    Coerced(u16),
 }
 
+/// The dictionaries a top-level binding takes
+enum Dicts {
+    /// one for each trait used at a type the binding is generalised over
+    Inferred,
+    /// an instance's context, which the binding is checked against
+    Declared {
+        trait_: u16,
+        /// the type of the dictionary, over the variables of the head numbered from 1
+        t: Type,
+        /// number of variables of the head
+        vars: TypeVar,
+        /// (trait, variable) of the dictionaries in the tuple taken
+        given: Vec<(u16, TypeVar)>,
+    },
+}
 
-/// A pair of types. Just more expressive
-type TypeConstraint = (Type, Type);
+/// The dictionaries a top-level binding takes, while its placeholders are filled in
+struct DictScope {
+    /// (trait, variable) of each dictionary in the tuple taken
+    given: Vec<(u16, TypeVar)>,
+    /// the variables the type of the binding is generalised over and their generics
+    generics: HashMap<TypeVar, u16>,
+    /// whether a dictionary is added for any trait used at one of the generics,
+    /// otherwise only the given ones are taken, as by an instance
+    open: bool,
+}
 
 pub struct TypingContext<'input> {
     type_decls: Vec<TypeDecl<'input>>,
     closures: Vec<Closure<'input>>,
-    globals: Vec<(iExpr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, Type)>,
+    globals: Vec<(iExpr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, TypeScheme)>,
     namescope: NameScope<'input>,
     type_consts: Vec<TypeConstraint>,
     type_map: HashMap<&'input str, u16>,
+    /// the record and index of each field name, of the last record declaring it
+    field_map: HashMap<&'input str, (u16, u16)>,
     errors: Vec<Error<'input>>,
-    next: usize,
+    /// solution of the constraints solved so far in the current top-level binding
+    subst: Substitution,
+    /// the local slot of the next let binding, after the args of the closure
+    next_local: u16,
+    /// the unreachable arms and missing values of the matches in the current
+    /// top-level binding
+    match_reports: Vec<MatchReport<'input>>,
+    traits: Vec<TraitDecl<'input>>,
+    trait_map: HashMap<&'input str, u16>,
+    /// uses of traits in the current top-level binding
+    predicates: Vec<Predicate>,
+    /// the placeholders in the current top-level binding
+    overloads: Vec<Overload>,
+    /// the types of the arithmetic in the current top-level binding, which are
+    /// int, float or bigint
+    numeric: Vec<(TypeVar, Span)>,
+    /// the closure comparing values by the interpreter's `=`, the `eq` of the
+    /// types without closures or an instance of Eq
+    prim_eq: Option<u16>,
+    /// the closure returning its argument, the coercion of a value to its own type
+    identity: Option<u16>,
+    /// the closure showing a value of a primitive type
+    prim_show: Option<u16>,
+    /// the closure ordering values by the interpreter, the `compare` of the types
+    /// without closures or an instance of Ord
+    prim_compare: Option<u16>,
+    /// the names of the local bindings in the current top-level binding, their
+    /// types and the variables they aren't generalised over because traits are
+    /// used at them
+    monomorphic: Vec<(&'input str, Type, TypeVar)>,
+    /// the uses of those names as (index in monomorphic, type and variable of the
+    /// use, span), unified with the bindings at the end of the top-level binding
+    monomorphic_uses: Vec<(usize, Type, TypeVar, Span)>,
+}
+
+impl<'input> Default for TypingContext<'input> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'input> TypingContext<'input> {
 
     pub fn new() -> Self {
-        TypingContext {
+        let mut ctx = TypingContext {
             type_decls: vec![],
             closures: vec![],
             globals: vec![], 
             namescope: NameScope::new(),
             type_consts: vec![], 
             type_map: HashMap::new(), 
+            field_map: HashMap::new(),
             errors: vec![],
-            next: 0,
+            subst: Substitution::default(),
+            // slot 0 holds the dictionaries of the top-level binding
+            next_local: 1,
+            match_reports: vec![],
+            traits: vec![],
+            trait_map: HashMap::new(),
+            predicates: vec![],
+            overloads: vec![],
+            numeric: vec![],
+            prim_eq: None,
+            identity: None,
+            prim_show: None,
+            prim_compare: None,
+            monomorphic: vec![],
+            monomorphic_uses: vec![],
+        };
+        // trait Eq a = { eq: a -> a -> bool }
+        // trait Show a = { show: a -> string }
+        // trait Ord a = { compare: a -> a -> int }
+        let a = || Box::new(Type::Generic(0));
+        let binary = |t| Type::Function(a(), Box::new(Type::Function(a(), Box::new(t))));
+        let builtins = vec![
+            (EQ, "Eq", "eq", binary(Type::Bool)),
+            (SHOW, "Show", "show", Type::Function(a(), Box::new(Type::String))),
+            (ORD, "Ord", "compare", binary(Type::Int)),
+        ];
+        for (n, name, method, t) in builtins {
+            ctx.trait_map.insert(name, n);
+            ctx.traits.push(TraitDecl { name, methods: vec![(method, t.clone())], instances: vec![] });
+            let scheme = TypeScheme { num_generics: 1, t, constraints: vec![(n, 0)] };
+            ctx.namescope.local().insert(method, (ValPath::TraitMethod(n, 0), scheme));
+        }
+        for (alias, method) in [(derive::SHOW, "show"), (derive::COMPARE, "compare")] {
+            let binding = ctx.namescope.local()[method].clone();
+            ctx.namescope.local().insert(alias, binding);
+        }
+        // type [a] = | #nil () | #cons (a, [a])
+        let list = Type::Sum(LIST, vec![Type::Generic(0)]);
+        ctx.type_decls.push(TypeDecl {
+            name: "List",
+            num_generics: 1,
+            variants: vec![(ast::NIL, Type::Unit), (ast::CONS, Type::Tuple(vec![Type::Generic(0), list]))],
+            kind: TypeKind::Sum,
+            methods: HashMap::new(),
+        });
+        for (position, name) in [(1, ast::NIL), (2, ast::CONS)] {
+            let t = Type::Constructor { target: LIST, position };
+            ctx.namescope.local().insert(name, (ValPath::Constructor(LIST, position), TypeScheme::mono(t)));
+        }
+        ctx.load_prelude();
+        ctx
+    }
+
+    /// Check the prelude and add the instances of tuples. Its values are removed
+    /// from the scope, so the names are free for the compilation unit, and the
+    /// ones desugaring relies on are kept under their hidden names
+    fn load_prelude(&mut self) {
+        let bindings = parse::parse(PRELUDE).expect("the prelude parses");
+        self.add_bindings(bindings);
+        for trait_ in ["Eq", "Show", "Ord"] {
+            for n in 2..=derive::MAX_TUPLE {
+                let (context, head, methods) = derive::tuple_instance(trait_, n);
+                self.instance_transform(context, head, methods, Span::default());
+            }
+        }
+        debug_assert!(self.errors.is_empty(), "the prelude type checks");
+        let scope = self.namescope.local();
+        for (name, hidden) in [("range", RANGE), ("flat_map", FLAT_MAP)] {
+            let binding = scope[name].clone();
+            scope.insert(hidden, binding);
         }
+        scope.retain(|name, (path, _)| name.starts_with('#') || !matches!(path, ValPath::StaticVal(_)));
     }
 
-    pub fn export(mut self) -> Result<Module<'input>, Error<'input>> {
+    /// Finish checking the compilation unit. Fails with all the errors found
+    /// in the bindings added so far
+    pub fn export(mut self) -> Result<Module<'input>, Vec<Diagnostic>> {
+        // unifying two compound types reports each mismatched part at the same span
+        let mut mismatches = HashSet::new();
+        let diagnostics: Vec<_> = self
+            .errors
+            .iter()
+            .filter(|e| !matches!(e, Error::TypeMismatch(..)) || mismatches.insert(e.span()))
+            .map(|e| e.to_diagnostic(&self.type_decls))
+            .collect();
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return Err(diagnostics);
+        }
+        let type_decls = self.type_decls;
+        // constructors of abstract types aren't exported, nor the names that
+        // can't be written in the source
+        let globals_names = self.namescope
+            .pop_layer()
+            .into_iter()
+            .filter(|(name, (path, _))| match *path {
+                _ if name.starts_with('#') => false,
+                ValPath::Constructor(n, _) => type_decls[n as usize].kind != TypeKind::Abstract,
+                _ => true,
+            })
+            .map(|(s, (path, _))| (s, path))
+            .collect();
         Ok(Module {
+            warnings: diagnostics,
             closures: self.closures,
             globals: self.globals,
-            type_decls: self.type_decls,
-            globals_names: self.namescope
-                .pop_layer()
-                .into_iter()
-                .map(|(s, (path, _))| (s, path))
-                .collect(),
+            type_decls,
+            traits: self.traits,
+            globals_names,
         })
     }
 
-    pub fn add_imports(&mut self, map: HashMap<&'input str, (ValPath, Type)>) {
+    pub fn add_imports(&mut self, map: HashMap<&'input str, (ValPath, TypeScheme)>) {
         self.namescope.extend_local(map);
     }
 
-    pub fn add_constr(&mut self, var: u16, constraint: Type) {
-        self.type_consts.push((Type::Variable(var), constraint));
+    pub fn add_constr(&mut self, var: TypeVar, constraint: Type, span: Span) {
+        self.type_consts.push((Type::Variable(var), constraint, span));
     }
 
-    pub fn add_binding(&mut self, binding: Binding<'input>) -> Result<(), Error<'input>> {
-        match binding {
-            Binding::Type { name, vars, variants } => {
-                let t = self.get_type_decl(name, vars, variants);
-                self.type_decls.push(t);
+    /// Check the bindings of a compilation unit. The names of all the sum and
+    /// abstract types are declared first, and the aliases are defined before the
+    /// other bindings, so type declarations can refer to each other in any order
+    pub fn add_bindings(&mut self, bindings: Vec<Binding<'input>>) {
+        // the alias declared by each binding, a type redefining an earlier alias
+        // is reported as the duplicate
+        let mut aliases = HashMap::new();
+        for (i, binding) in bindings.iter().enumerate() {
+            if let BindingKind::Type { name, ref vars, ref def, .. } = binding.kind {
+                if self.type_map.contains_key(name) || aliases.contains_key(name) {
+                    continue;
+                }
+                if let TypeDef::Alias(_) = def {
+                    aliases.insert(name, i);
+                } else {
+                    self.declare_type(name, vars.len() as u16);
+                }
             }
-            Binding::Value(pat, expr, is_rec) => {
-                let tuple = self.binding_transform(self.globals.len() as u16, pat, expr, is_rec)?;
+        }
+        let mut bindings: Vec<_> = bindings.into_iter().map(Some).collect();
+        let mut order: Vec<_> = aliases.values().copied().collect();
+        order.sort_unstable();
+        for i in order {
+            self.define_alias(i, &mut bindings, &aliases, &mut vec![]);
+        }
+        for binding in bindings.into_iter().flatten() {
+            self.add_binding(binding);
+        }
+    }
+
+    /// Define the alias of the ith binding after the aliases it refers to. The
+    /// stack has the aliases being defined, which it can't refer to
+    /// ### RETURNS
+    /// false if the alias expands to itself or refers to an alias that does
+    fn define_alias(
+        &mut self,
+        i: usize,
+        bindings: &mut [Option<Binding<'input>>],
+        aliases: &HashMap<&'input str, usize>,
+        stack: &mut Vec<&'input str>,
+    ) -> bool {
+        // taken once it's defined
+        let binding = match bindings[i].take() {
+            Some(binding) => binding,
+            None => return true,
+        };
+        let (name, vars, def, deriving) = match binding.kind {
+            BindingKind::Type { name, vars, def, deriving } => (name, vars, def, deriving),
+            _ => unreachable!("aliases are declared by type bindings"),
+        };
+        let mut names = Vec::new();
+        if let TypeDef::Alias(ref t) = def {
+            t.type_names(&mut names);
+        }
+        let mut resolved = true;
+        stack.push(name);
+        for (dep, span) in names {
+            if vars.contains(&dep) || !resolved {
+                continue;
+            }
+            if let Some(start) = stack.iter().position(|&n| n == dep) {
+                self.errors.push(Error::CyclicAlias(stack[start..].to_vec(), span));
+                resolved = false;
+            } else if let Some(&j) = aliases.get(dep) {
+                resolved = self.define_alias(j, bindings, aliases, stack);
+            }
+        }
+        stack.pop();
+        if resolved {
+            self.type_transform(name, vars, def, deriving, binding.span);
+        } else {
+            let n = self.declare_type(name, vars.len() as u16);
+            self.type_decls[n as usize].kind = TypeKind::Alias(Type::Unit);
+        }
+        resolved
+    }
+
+    /// Check a top-level binding and add it to the module. Errors are
+    /// collected and reported by export
+    pub fn add_binding(&mut self, binding: Binding<'input>) {
+        match binding.kind {
+            BindingKind::Type { name, vars, def, deriving } => {
+                self.type_transform(name, vars, def, deriving, binding.span)
+            }
+            BindingKind::Value(pat, expr, is_rec) => {
+                let tuple = self.binding_transform(self.globals.len() as u16, pat, expr, is_rec, Dicts::Inferred);
                 self.globals.push(tuple)
             },
-            Binding::Method(type_name, method_name, body) => panic!("Method Definition"),
+            BindingKind::Method(type_name, name, expr) => self.method_transform(type_name, name, expr, binding.span),
+            BindingKind::Trait { name, var, methods } => self.trait_transform(name, var, methods, binding.span),
+            BindingKind::Instance { context, head, methods } => {
+                self.instance_transform(context, head, methods, binding.span)
+            }
+            // reported by the parser
+            BindingKind::Error => (),
+        }
+    }
+
+    /// Define the type of a type declaration and add the instances it derives. A
+    /// name may be declared in advance by add_bindings, but only defined once
+    fn type_transform(
+        &mut self,
+        name: &'input str,
+        vars: Vec<&'input str>,
+        def: TypeDef<'input>,
+        deriving: Vec<(&'input str, Span)>,
+        span: Span,
+    ) {
+        let defined = match self.type_map.get(name) {
+            Some(&n) => matches!(def, TypeDef::Alias(_)) || self.type_decls[n as usize].kind != TypeKind::Declared,
+            None => false,
+        };
+        if defined {
+            self.errors.push(Error::DuplicateType(name, span));
+            return;
+        }
+        let errors = self.errors.len();
+        let generics = vars.clone();
+        match def {
+            TypeDef::Sum(variants) => {
+                let t = self.get_type_decl(name, vars, variants);
+                self.define_type(t);
+            }
+            TypeDef::Record(fields) => {
+                let t = self.get_record_decl(name, vars, fields);
+                self.define_type(t);
+            }
+            TypeDef::Alias(t) => {
+                let generics_map = generics_map(vars);
+                // the alias isn't declared yet, so it can't refer to itself
+                let t = match t.to_type(&self.type_map, &self.type_decls, &generics_map) {
+                    Ok(t) => t,
+                    Err(e) => { self.errors.push(e); Type::Unit }
+                };
+                let n = self.declare_type(name, generics_map.len() as u16);
+                self.type_decls[n as usize].kind = TypeKind::Alias(t);
+            }
+            TypeDef::Abstract(repr, with) => {
+                let mut t = self.get_type_decl(name, vars, vec![(name, repr, span)]);
+                t.kind = TypeKind::Abstract;
+                let n = self.define_type(t);
+                if let Some(bindings) = with {
+                    for binding in bindings {
+                        self.add_binding(binding);
+                    }
+                    // the representation is only visible to the bindings in the with block
+                    if let Some((ValPath::Constructor(m, _), _)) = self.namescope.local().get(name) {
+                        if *m == n {
+                            self.namescope.local().remove(name);
+                        }
+                    }
+                }
+            }
+        }
+        // a declaration with errors may be missing variants
+        if !deriving.is_empty() && self.errors.len() == errors {
+            self.derive(name, generics, deriving);
+        }
+    }
+
+    /// Add the instances `deriving` declares for the sum or record type name, as if
+    /// they were written after its declaration
+    fn derive(&mut self, name: &'input str, vars: Vec<&'input str>, deriving: Vec<(&'input str, Span)>) {
+        let n = self.type_map[name];
+        for (derived, span) in deriving {
+            let decl = &self.type_decls[n as usize];
+            if !matches!(decl.kind, TypeKind::Sum | TypeKind::Record) {
+                self.errors.push(Error::Unsupported("derived instances of aliases and abstract types", span));
+                return;
+            }
+            match derive::instance(derived, decl, &vars, span) {
+                Some((context, head, methods)) => self.instance_transform(context, head, methods, span),
+                None => self.errors.push(Error::NotDerivable(derived, span)),
+            }
+        }
+    }
+
+    /// Check a method definition and attach it to its type. The method is a global
+    /// closure taking the receiver as its first argument
+    fn method_transform(&mut self, type_name: &'input str, name: &'input str, expr: Expr<'input>, span: Span) {
+        let target = match self.type_map.get(type_name) {
+            None => {
+                self.errors.push(Error::TypeNotDefined(type_name, span));
+                return;
+            }
+            Some(&n) => match self.type_decls[n as usize].kind {
+                TypeKind::Alias(Type::Sum(m, _)) => m,
+                TypeKind::Alias(_) => {
+                    self.errors.push(Error::Unsupported("methods of built-in types", span));
+                    return;
+                }
+                _ => n,
+            },
+        };
+        if self.type_decls[target as usize].methods.contains_key(name) {
+            self.errors.push(Error::DuplicateMethod(name, span));
+            return;
+        }
+        let order = self.globals.len() as u16;
+        // attached before checking the body, so the method can call itself
+        self.type_decls[target as usize].methods.insert(name, order);
+        let pat = Pattern::new(PatternKind::Wild, span.start, span.start);
+        let global = self.binding_transform(order, pat, expr, false, Dicts::Inferred);
+
+        let (t, next) = global.2.instantiate(0);
+        let num_generics = TypeVar::from(self.type_decls[target as usize].num_generics);
+        let receiver = Type::Sum(target, (next..next + num_generics).map(Type::Variable).collect());
+        let method = Type::Function(Box::new(receiver), Box::new(Type::Variable(next + num_generics)));
+        if let Err(errors) = unify::unify(&mut vec![(t, method, span)], &mut Substitution::default()) {
+            self.errors.extend(errors);
+        }
+        self.globals.push(global);
+    }
+
+    /// Declare a trait and bind its methods. A method is overloaded on the type of
+    /// the instance, its uses take the dictionary of the instance
+    fn trait_transform(
+        &mut self,
+        name: &'input str,
+        var: &'input str,
+        methods: Vec<(&'input str, ProtoType<'input>, Span)>,
+        span: Span,
+    ) {
+        if self.trait_map.contains_key(name) {
+            self.errors.push(Error::DuplicateTrait(name, span));
+            return;
+        }
+        let n = self.traits.len() as u16;
+        let generics_map = generics_map(vec![var]);
+        let mut decl_methods: Vec<(&'input str, Type)> = Vec::new();
+        for (method, t, span) in methods {
+            let t = match t.to_type(&self.type_map, &self.type_decls, &generics_map) {
+                Ok(t) => t,
+                Err(e) => { self.errors.push(e); Type::Unit }
+            };
+            if decl_methods.iter().any(|&(m, _)| m == method) {
+                self.errors.push(Error::DuplicateMethod(method, span));
+                continue;
+            }
+            let scheme = TypeScheme { num_generics: 1, t: t.clone(), constraints: vec![(n, 0)] };
+            let path = ValPath::TraitMethod(n, decl_methods.len() as u16);
+            self.namescope.local().insert(method, (path, scheme));
+            decl_methods.push((method, t));
+        }
+        self.trait_map.insert(name, n);
+        self.traits.push(TraitDecl { name, methods: decl_methods, instances: vec![] });
+    }
+
+    /// Check an instance declaration and add its dictionary to the globals. The
+    /// instance is added to its trait before checking the methods, so they can
+    /// use it at the arguments of the head
+    fn instance_transform(
+        &mut self,
+        context: Option<ProtoType<'input>>,
+        head: ProtoType<'input>,
+        methods: Vec<(&'input str, Expr<'input>, Span)>,
+        span: Span,
+    ) {
+        let (name, head, head_span) = match head {
+            ProtoType::Sum(name, t, span) => (name, *t, span),
+            _ => {
+                self.errors.push(Error::Unsupported("instance heads other than a trait applied to a type", span));
+                return;
+            }
+        };
+        let Some(&trait_) = self.trait_map.get(name) else {
+            self.errors.push(Error::TraitNotDefined(name, head_span));
+            return;
+        };
+        let mut vars = Vec::new();
+        head.type_vars(&self.type_map, &mut vars);
+        let generics_map = generics_map(vars);
+        let num_generics = generics_map.len() as u16;
+        let t = match head.to_type(&self.type_map, &self.type_decls, &generics_map) {
+            Ok(t) => t,
+            Err(e) => {
+                self.errors.push(e);
+                return;
+            }
+        };
+        let valid = match t {
+            Type::Unit | Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String => true,
+            Type::Sum(_, ref args) | Type::Tuple(ref args) => {
+                args.len() == num_generics as usize
+                    && args.iter().enumerate().all(|(i, t)| *t == Type::Generic(i as u16))
+            }
+            _ => false,
+        };
+        if !valid {
+            let what = "instances for types other than a declared type applied to, or a tuple of, distinct type variables";
+            self.errors.push(Error::Unsupported(what, head_span));
+            return;
+        }
+        if self.traits[trait_ as usize].instances.iter().any(|i| same_head(&i.head, &t)) {
+            self.errors.push(Error::DuplicateInstance(name, span));
+            return;
+        }
+
+        // e.g. `(Eq a, Show b) =>`
+        let context = match context {
+            None => vec![],
+            Some(ProtoType::Tuple(v)) => v,
+            Some(t) => vec![t],
+        };
+        let mut required = Vec::new();
+        for t in context {
+            match t {
+                ProtoType::Sum(name, var, span) => match (self.trait_map.get(name), *var) {
+                    (None, _) => self.errors.push(Error::TraitNotDefined(name, span)),
+                    (Some(&n), ProtoType::Generic(var, span)) => match generics_map.get(var) {
+                        Some(&g) => required.push((n, g)),
+                        None => self.errors.push(Error::TypeNotDefined(var, span)),
+                    },
+                    (Some(_), _) => {
+                        self.errors.push(Error::Unsupported("contexts other than traits of type variables", span))
+                    }
+                },
+                _ => self.errors.push(Error::Unsupported("contexts other than traits of type variables", span)),
+            }
+        }
+
+        // the methods in the order of the trait, missing ones are reported and left as errors
+        let decl = &self.traits[trait_ as usize];
+        let mut impls: Vec<Option<Expr<'input>>> = decl.methods.iter().map(|_| None).collect();
+        for (method, e, span) in methods {
+            match decl.methods.iter().position(|&(m, _)| m == method) {
+                None => self.errors.push(Error::UnknownMethod(name, method, span)),
+                Some(i) if impls[i].is_some() => self.errors.push(Error::DuplicateMethod(method, span)),
+                Some(i) => impls[i] = Some(e),
+            }
+        }
+        let errors = &mut self.errors;
+        let impls = impls
+            .into_iter()
+            .zip(&decl.methods)
+            .map(|(e, &(method, _))| {
+                e.unwrap_or_else(|| {
+                    errors.push(Error::MissingMethod(name, method, span));
+                    Expr::new(ExprKind::Error, span.start, span.end)
+                })
+            })
+            .collect();
+        // the dictionary is checked against the methods at the head, with its generics
+        // as the variables from 1
+        let (head_type, _) = t.instantiate(1);
+        let dict = self.dict_type(trait_, &head_type);
+        let given = required.iter().map(|&(n, g)| (n, 1 + TypeVar::from(g))).collect();
+
+        let order = self.globals.len() as u16;
+        self.traits[trait_ as usize].instances.push(Instance { head: t, context: required, global: order });
+        let pat = Pattern::new(PatternKind::Wild, span.start, span.start);
+        let expr = Expr::new(ExprKind::Tuple(impls), span.start, span.end);
+        let dicts = Dicts::Declared { trait_, t: dict, vars: TypeVar::from(num_generics), given };
+        let global = self.binding_transform(order, pat, expr, false, dicts);
+        self.globals.push(global);
+    }
+
+    /// Resolve `e.name` by the type of e inferred so far, to a field of its record
+    /// or a method of its type. If the type isn't known yet, the name is looked up
+    /// in the fields of all records, then in the methods of all types
+    /// # Arguments
+    /// - recv: the type variable of e
+    fn resolve_member(&mut self, recv: TypeVar, name: &'input str, span: Span) -> Option<Member> {
+        self.solve();
+        let mut t = Type::Variable(recv);
+        t.substitute_vars(&self.subst);
+        match t {
+            Type::Sum(n, _) => {
+                let decl = &self.type_decls[n as usize];
+                let field = match decl.kind {
+                    TypeKind::Record => decl.variants.iter().position(|&(field, _)| field == name),
+                    _ => None,
+                };
+                match (field, decl.methods.get(name)) {
+                    (Some(i), _) => Some(Member::Field(n, i as u16)),
+                    (None, Some(&order)) => Some(Member::Method(order)),
+                    (None, None) => {
+                        self.errors.push(Error::MemberNotFound(t, name, span));
+                        None
+                    }
+                }
+            }
+            Type::Trait(ref methods) => match methods.iter().position(|(method, _)| **method == *name) {
+                Some(i) => Some(Member::Trait(i as u16, methods[i].1.clone())),
+                None => {
+                    self.errors.push(Error::MemberNotFound(t.clone(), name, span));
+                    None
+                }
+            },
+            Type::Variable(_) => {
+                if let Some(&(n, i)) = self.field_map.get(name) {
+                    return Some(Member::Field(n, i));
+                }
+                let mut methods = self.type_decls.iter().filter_map(|decl| decl.methods.get(name));
+                match (methods.next(), methods.next()) {
+                    (Some(&order), None) => Some(Member::Method(order)),
+                    _ => {
+                        self.errors.push(Error::UnknownReceiver(name, span));
+                        None
+                    }
+                }
+            }
+            t => {
+                self.errors.push(Error::MemberNotFound(t, name, span));
+                None
+            }
+        }
+    }
+
+    /// The type scheme of the global at order. The binding being checked has the
+    /// type of its expression
+    fn global_scheme(&self, order: u16) -> TypeScheme {
+        match self.globals.get(order as usize) {
+            Some((_, _, scheme)) => scheme.clone(),
+            None => TypeScheme::mono(Type::Variable(0)),
+        }
+    }
+
+    /// A use of a global or a trait method, instantiated from next. A trait method
+    /// is taken from the dictionary of its instance, an overloaded global is applied
+    /// to the dictionaries of its constraints, and the binding being checked to its
+    /// own dictionaries if it turns out to take any
+    /// ### RETURNS
+    /// the value, its type and the next free variable
+    fn use_global(&mut self, path: ValPath, scheme: &TypeScheme, next: TypeVar, span: Span) -> (iExprKind<'input>, Type, TypeVar) {
+        let (t, nnext) = scheme.instantiate(next);
+        let kind = match path {
+            ValPath::TraitMethod(trait_, i) => {
+                let p = self.predicate(trait_, next, span);
+                let dict = iExpr::new(self.overload(Overload::Dict(p)), span);
+                iExprKind::Field(Box::new(dict), i)
+            }
+            ValPath::StaticVal(ref v) if v[0] as usize == self.globals.len() => {
+                let dicts = self.dicts_path();
+                self.overload(Overload::Recursive(path, dicts, span))
+            }
+            _ if !scheme.constraints.is_empty() => {
+                let predicates = scheme
+                    .constraints
+                    .iter()
+                    .map(|&(trait_, g)| self.predicate(trait_, next + TypeVar::from(g), span))
+                    .collect();
+                self.overload(Overload::Global(path, predicates))
+            }
+            _ => iExprKind::Bound(path),
+        };
+        (kind, t, nnext)
+    }
+
+    /// `e1 op e2` for `=`, `!=` or an ordering at the type of var. Values of primitive
+    /// types are compared by the interpreter, the others by the `eq` of their instance
+    /// of Eq, or by the `compare` of their instance of Ord as `compare e1 e2 op 0`
+    fn comparison(&mut self, e1: iExpr<'input>, op: BinOpcode, e2: iExpr<'input>, var: TypeVar, span: Span) -> iExprKind<'input> {
+        self.solve();
+        let mut t = Type::Variable(var);
+        t.substitute_vars(&self.subst);
+        if has_primitive_eq(&t) {
+            return iExprKind::BinOp(Box::new(e1), op, Box::new(e2));
         }
-        Ok(())
+        let trait_ = match op {
+            BinOpcode::Equal | BinOpcode::NotEq => EQ,
+            _ => ORD,
+        };
+        let p = self.predicate(trait_, var, span);
+        let dict = iExpr::new(self.overload(Overload::Dict(p)), span);
+        let method = iExpr::new(iExprKind::Field(Box::new(dict), 0), span);
+        let method = iExpr::new(iExprKind::Application(Box::new(method), Box::new(e1)), span);
+        let applied = iExprKind::Application(Box::new(method), Box::new(e2));
+        match op {
+            BinOpcode::Equal => applied,
+            BinOpcode::NotEq => iExprKind::UnOp(UnOpcode::Not, Box::new(iExpr::new(applied, span))),
+            _ => {
+                let zero = iExpr::new(iExprKind::Literal(Literal::Int(0)), span);
+                iExprKind::BinOp(Box::new(iExpr::new(applied, span)), op, Box::new(zero))
+            }
+        }
+    }
+
+    /// Record a use of a trait at the type of var
+    /// ### RETURNS
+    /// index of the predicate
+    fn predicate(&mut self, trait_: u16, var: TypeVar, span: Span) -> usize {
+        let dicts = self.dicts_path();
+        self.predicates.push(Predicate { trait_, var, span, dicts });
+        self.predicates.len() - 1
+    }
+
+    /// A placeholder for an overloaded value
+    fn overload(&mut self, overload: Overload) -> iExprKind<'input> {
+        self.overloads.push(overload);
+        iExprKind::Overloaded((self.overloads.len() - 1) as u32)
+    }
+
+    /// the path of the dictionaries of the top-level binding, captured by the
+    /// closures in between
+    fn dicts_path(&mut self) -> ValPath {
+        self.namescope.get(DICTS).expect("dictionaries are bound in every top-level binding").0.clone()
     }
 
     /// Transform a top-level binding
@@ -249,415 +1305,2117 @@ impl<'input> TypingContext<'input> {
     /// - pattern
     /// - expression
     /// - is_rec: is recursive? if recursive, pattern added to scope before the expression
+    /// - dicts: the dictionaries the binding takes. A binding that takes any is a closure
+    ///   taking a tuple of them, bound to slot 0 of its locals
     /// 
     /// # Returns
-    /// Result(tranformed expression, constraints on the expression by the pattern, type of expression)
-    /// 
-    /// # Future
-    /// when non-top-level bindings are allowed, shouldn't generalize types here
-
+    /// (tranformed expression, constraints on the expression by the pattern, type of expression)
     fn binding_transform(
         &mut self,
         order: u16,
         pat: Pattern<'input>,
         expr: Expr<'input>,
         is_rec: bool,
-    ) -> Result<(iExpr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, Type), Error<'input>> {
+        dicts: Dicts,
+    ) -> (iExpr<'input>, BTreeMap<ValPath, ConstraintValue<'input>>, TypeScheme) {
         let mut path = vec![order];
-        let mut val_consts = BTreeMap::new();
+        let mut alts = vec![Alternative::default()];
+        let errors = self.errors.len();
         // remember how many closures was already there. Closures are added to global closures vector
         // as the expression is processed, i.e. before type unification. This means we have to change
         // their types inside the global vector
         let closures_num = self.closures.len();
+        let pat_span = pat.span;
+        let span = expr.span;
+        let single_name = matches!(pat.kind, PatternKind::Bind(_) | PatternKind::Wild);
+        let (first, declared) = match dicts {
+            Dicts::Inferred => (1, None),
+            Dicts::Declared { trait_, t, vars, given } => {
+                self.add_constr(0, t, span);
+                (1 + vars, Some((trait_, vars, given)))
+            }
+        };
+        let dicts_layer = |ctx: &mut Self| {
+            ctx.namescope.push_layer();
+            ctx.namescope.local().insert(DICTS, (ValPath::Local(vec![0]), TypeScheme::mono(Type::Unit)));
+        };
         // we don't insert directly into the scope because we want to do type unification
         // before inserting finally
-        let expr = if is_rec {
+        let (mut expr, next) = if is_rec {
             self.namescope.push_layer();
-            let next = pat.transform(0, 1, &mut path, self, ValPath::StaticVal, &mut val_consts);
-            expr.transform(0, next, self).0
+            let next = pat.transform(0, first, &mut path, self, ValPath::StaticVal, &mut alts);
+            dicts_layer(self);
+            let (e, next) = expr.transform(0, next, self);
+            self.namescope.pop_layer();
+            (e, next)
         } else {
-            let (e, next) = expr.transform(0, 1, self);
+            dicts_layer(self);
+            let (e, next) = expr.transform(0, first, self);
+            self.namescope.pop_layer();
             self.namescope.push_layer();
-            pat.transform(0, next, &mut path, self, ValPath::StaticVal, &mut val_consts);
-            e
+            let next = pat.transform(0, next, &mut path, self, ValPath::StaticVal, &mut alts);
+            (e, next)
         };
-        let mut type_consts = self.type_consts.drain(0..).collect();
-        let mut map = unify::unify(&mut type_consts)?;
-        let mut local = self.namescope.pop_layer();
-        for (_, (_, t)) in local.iter_mut() {
-            t.substitute_vars(&map);
-            t.generalize_type();
-        }
+        // the constraints all the alternatives have
+        let val_consts = alts[0]
+            .constraints
+            .iter()
+            .filter(|&(path, c)| alts.iter().all(|alt| alt.constraints.get(path) == Some(c)))
+            .map(|(path, c)| (path.clone(), c.clone()))
+            .collect();
+        let mut dtree = DTree::new();
+        let taken = self.add_alternatives(&mut dtree, alts, 0, false, false, pat_span);
+        self.check_match(&dtree, &[(pat_span, taken)], vec![(order, Type::Variable(0))], pat_span);
+        // on failure, the names are still bound (to their partially solved types) to
+        // avoid reporting every use of them as not found
+        self.solve();
+        self.solve_monomorphic();
+        self.resolve_numeric();
+        self.resolve_coercions(next);
+        let subst = std::mem::take(&mut self.subst);
+        let local = self.namescope.pop_layer();
+        // the environment is the global scope which is normally closed, unless the
+        // context is kept alive between bindings with unsolved variables
+        let env = self.namescope.free_vars(&subst);
+        let mut t = Type::Variable(0);
+        t.substitute_vars(&subst);
+        let (mut scheme, generics) = TypeScheme::generalize_vars(t.clone(), &env);
+
+        // an instance's methods must work for every type of its head, whose variables
+        // are only given the dictionaries of the context
+        let mut scope = DictScope { given: vec![], generics, open: declared.is_none() };
+        if let Some((trait_, vars, given)) = declared {
+            let heads: Vec<_> = (1..=vars)
+                .map(|v| {
+                    let mut t = Type::Variable(v);
+                    t.substitute_vars(&subst);
+                    t
+                })
+                .collect();
+            let distinct = heads.iter().enumerate().all(|(i, t)| {
+                matches!(t, Type::Variable(_)) && !heads[..i].contains(t)
+            });
+            if !distinct {
+                self.errors.push(Error::InstanceNotGeneral(self.traits[trait_ as usize].name, span));
+            }
+            scope.given = given
+                .into_iter()
+                .map(|(trait_, v)| match heads[v as usize - 1] {
+                    Type::Variable(root) => (trait_, root),
+                    _ => (trait_, v),
+                })
+                .collect();
+        }
+        self.fill_overloads(&mut expr, closures_num, &subst, &mut scope);
+        scheme.constraints = scope
+            .given
+            .iter()
+            .filter_map(|&(trait_, v)| scope.generics.get(&v).map(|&g| (trait_, g)))
+            .collect();
+
+        let local = local
+            .into_iter()
+            .map(|(name, (path, scheme))| {
+                let mut t = scheme.t;
+                t.substitute_vars(&subst);
+                let mut scheme = TypeScheme::generalize(t, &env);
+                // the name is the whole value
+                scheme.constraints = scope
+                    .given
+                    .iter()
+                    .filter_map(|&(trait_, v)| scope.generics.get(&v).map(|&g| (trait_, g)))
+                    .collect();
+                (name, (path, scheme))
+            })
+            .collect();
         self.namescope.extend_local(local);
 
         // chnage types of closures added for this binding
         for closure in self.closures.iter_mut().skip(closures_num) {
-            closure.substitute_types(&map);
+            closure.substitute_types(&subst);
         }
 
-        let mut t = Type::Variable(0);
-        t.substitute_vars(&mut map);
-        t.generalize_type();
-        // let mut pretty = String::new();
-        // t.pretty_format(&mut pretty, args.type_decls);
-        // println!("{}",pretty);
-        Ok((expr, val_consts, t))
+        // the errors of a pattern start in it, even those of the whole arm
+        let errored: Vec<_> = self.errors[errors..].iter().map(|e| e.span().start).collect();
+        for report in std::mem::take(&mut self.match_reports) {
+            if errored.iter().any(|&e| report.patterns.iter().any(|p| p.start <= e && e < p.end)) {
+                continue;
+            }
+            self.errors.extend(report.redundant.into_iter().map(Error::RedundantArm));
+            if let Some(inexhaustive) = report.inexhaustive {
+                let span = inexhaustive.span;
+                let pat = inexhaustive.witness(&subst, &self.type_decls);
+                self.errors.push(Error::NonExhaustive(pat, span));
+            }
+        }
+
+        if !scope.given.is_empty() {
+            // the names of a pattern are parts of the one value, which would each need
+            // the dictionaries of its own generics, e.g. `let (a, b) = ({ x => show x }, 2)`
+            if !single_name {
+                self.errors.push(Error::Unsupported("overloaded values bound to patterns", pat_span));
+            }
+            let dict_types = scope
+                .given
+                .iter()
+                .map(|&(trait_, v)| self.dict_type(trait_, &Type::Variable(v)))
+                .collect();
+            expr = self.dicts_closure(expr, dict_types, t);
+        }
+        (expr, val_consts, scheme)
     }
 
-    
+    /// Fill in the placeholders of the current top-level binding in expr and the
+    /// closures from closures_num, now that its types are solved. The dictionaries
+    /// the binding takes are added to the scope
+    fn fill_overloads(&mut self, expr: &mut iExpr<'input>, closures_num: usize, subst: &Substitution, scope: &mut DictScope) {
+        let predicates = std::mem::take(&mut self.predicates);
+        let predicates_spans: Vec<_> = predicates.iter().map(|p| p.span).collect();
+        let mut dicts: Vec<_> = predicates
+            .iter()
+            .map(|p| {
+                let mut t = Type::Variable(p.var);
+                t.substitute_vars(subst);
+                Some(self.dict_expr(p.trait_, &t, scope, &p.dicts, p.span))
+            })
+            .collect();
+        let mut take = |p: usize| dicts[p].take().expect("a predicate is used once");
+        let mut filled: Vec<_> = std::mem::take(&mut self.overloads)
+            .into_iter()
+            .map(|overload| {
+                let (f, arg) = match overload {
+                    Overload::Dict(p) => return Some(take(p).kind),
+                    Overload::Coerced(closure) => return Some(iExprKind::Closure(closure)),
+                    Overload::Coercion(..) => unreachable!("coercions are resolved before generalising"),
+                    Overload::Recursive(path, _, _) if scope.given.is_empty() => return Some(iExprKind::Bound(path)),
+                    Overload::Global(path, predicates) => {
+                        let span = predicates_spans[predicates[0]];
+                        let dicts = predicates.into_iter().map(&mut take).collect();
+                        (iExpr::new(iExprKind::Bound(path), span), iExpr::new(iExprKind::Tuple(dicts), span))
+                    }
+                    Overload::Recursive(path, dicts, span) => {
+                        (iExpr::new(iExprKind::Bound(path), span), iExpr::new(iExprKind::Bound(dicts), span))
+                    }
+                };
+                Some(iExprKind::Application(Box::new(f), Box::new(arg)))
+            })
+            .collect();
+        fill_placeholders(expr, &mut filled);
+        for closure in self.closures.iter_mut().skip(closures_num) {
+            for branch in closure.branches.iter_mut().chain(closure.guards.iter_mut().flatten()) {
+                fill_placeholders(branch, &mut filled);
+            }
+        }
+    }
+
+    /// The dictionary of the instance of a trait for t, a solved type. At a variable
+    /// the binding is generalised over, it's taken from the binding's own
+    /// dictionaries at the path dicts
+    fn dict_expr(&mut self, trait_: u16, t: &Type, scope: &mut DictScope, dicts: &ValPath, span: Span) -> iExpr<'input> {
+        let name = self.traits[trait_ as usize].name;
+        let kind = match *t {
+            Type::Variable(v) => {
+                let given = scope.given.iter().position(|&d| d == (trait_, v));
+                let k = match (given, scope.generics.get(&v)) {
+                    (Some(k), _) => Some(k),
+                    (None, Some(_)) if scope.open => {
+                        scope.given.push((trait_, v));
+                        Some(scope.given.len() - 1)
+                    }
+                    (None, Some(&g)) => {
+                        self.errors.push(Error::NoInstance(name, Type::Generic(g), span));
+                        None
+                    }
+                    (None, None) => {
+                        self.errors.push(Error::AmbiguousInstance(name, span));
+                        None
+                    }
+                };
+                match k {
+                    Some(k) => iExprKind::Field(Box::new(iExpr::new(iExprKind::Bound(dicts.clone()), span)), k as u16),
+                    None => iExprKind::Error,
+                }
+            }
+            _ if trait_ == EQ && has_primitive_eq(t) => {
+                iExprKind::Tuple(vec![iExpr::new(iExprKind::Closure(self.prim_eq()), span)])
+            }
+            _ => {
+                let instance = self.traits[trait_ as usize]
+                    .instances
+                    .iter()
+                    .find(|i| same_head(&i.head, t))
+                    .map(|i| (i.context.clone(), i.global));
+                let comparison = trait_ == EQ || trait_ == ORD;
+                // reported at the whole tuple rather than at its elements' instances
+                if comparison && (instance.is_none() || matches!(t, Type::Tuple(_))) && self.has_function(t) {
+                    let t = TypeScheme::generalize(t.clone(), &HashSet::new()).t;
+                    self.errors.push(Error::Incomparable(t, span));
+                    return iExpr::new(iExprKind::Error, span);
+                }
+                match instance {
+                    // unless the module declares its own
+                    None if trait_ == SHOW && is_primitive(t) => {
+                        iExprKind::Tuple(vec![iExpr::new(iExprKind::Closure(self.prim_show()), span)])
+                    }
+                    // values without closures are compared structurally by the interpreter,
+                    // as long as the values of the generics in t are too
+                    None if comparison => {
+                        let mut vars = HashSet::new();
+                        t.free_vars(&mut vars);
+                        let mut vars: Vec<_> = vars.into_iter().filter(|v| scope.generics.contains_key(v)).collect();
+                        vars.sort_unstable();
+                        for v in vars {
+                            self.dict_expr(trait_, &Type::Variable(v), scope, dicts, span);
+                        }
+                        let closure = if trait_ == EQ { self.prim_eq() } else { self.prim_compare() };
+                        iExprKind::Tuple(vec![iExpr::new(iExprKind::Closure(closure), span)])
+                    }
+                    None => {
+                        let t = TypeScheme::generalize(t.clone(), &HashSet::new()).t;
+                        self.errors.push(Error::NoInstance(name, t, span));
+                        iExprKind::Error
+                    }
+                    Some((context, global)) if context.is_empty() => iExprKind::Bound(ValPath::StaticVal(vec![global])),
+                    // applied to the dictionaries of its context at the arguments of t
+                    Some((context, global)) => {
+                        let args = match *t {
+                            Type::Sum(_, ref args) | Type::Tuple(ref args) => &args[..],
+                            _ => &[],
+                        };
+                        let context = context
+                            .into_iter()
+                            .map(|(trait_, g)| self.dict_expr(trait_, &args[g as usize], scope, dicts, span))
+                            .collect();
+                        let global = iExpr::new(iExprKind::Bound(ValPath::StaticVal(vec![global])), span);
+                        iExprKind::Application(Box::new(global), Box::new(iExpr::new(iExprKind::Tuple(context), span)))
+                    }
+                }
+            }
+        };
+        iExpr::new(kind, span)
+    }
+
+    /// whether values of t may have closures in them, which can't be compared. The
+    /// generics of a declared type are checked at its arguments
+    fn has_function(&self, t: &Type) -> bool {
+        fn has_function(t: &Type, types: &[TypeDecl], seen: &mut HashSet<u16>) -> bool {
+            match t {
+                Type::Function(..) | Type::Trait(_) => true,
+                Type::Tuple(v) => v.iter().any(|t| has_function(t, types, seen)),
+                Type::Sum(n, args) => {
+                    args.iter().any(|t| has_function(t, types, seen))
+                        || seen.insert(*n) && types[*n as usize].variants.iter().any(|(_, t)| has_function(t, types, seen))
+                }
+                _ => false,
+            }
+        }
+        has_function(t, &self.type_decls, &mut HashSet::new())
+    }
+
+    /// the type of the dictionary of a trait's instance for t
+    fn dict_type(&self, trait_: u16, t: &Type) -> Type {
+        let methods = &self.traits[trait_ as usize].methods;
+        Type::Tuple(methods.iter().map(|(_, m)| m.substitute_generics(std::slice::from_ref(t))).collect())
+    }
+
+    /// A closure taking a tuple of dictionaries to body, the value of a top-level
+    /// binding that takes them
+    fn dicts_closure(&mut self, body: iExpr<'input>, dict_types: Vec<Type>, return_type: Type) -> iExpr<'input> {
+        let span = body.span;
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure {
+            captures: vec![],
+            args: vec![Type::Tuple(dict_types)],
+            return_type,
+            dtree,
+            branches: vec![body],
+            guards: vec![None],
+            span,
+        });
+        iExpr::new(iExprKind::Closure((self.closures.len() - 1) as u16), span)
+    }
+
+    /// The closure comparing two values with the interpreter's `=`, added once
+    fn prim_eq(&mut self) -> u16 {
+        if let Some(n) = self.prim_eq {
+            return n;
+        }
+        let span = Span::default();
+        let arg = |i| Box::new(iExpr::new(iExprKind::Bound(ValPath::Local(vec![i])), span));
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure {
+            captures: vec![],
+            args: vec![Type::Generic(0), Type::Generic(0)],
+            return_type: Type::Bool,
+            dtree,
+            branches: vec![iExpr::new(iExprKind::BinOp(arg(0), BinOpcode::Equal, arg(1)), span)],
+            guards: vec![None],
+            span,
+        });
+        let n = (self.closures.len() - 1) as u16;
+        self.prim_eq = Some(n);
+        n
+    }
+
+    /// The closure showing a value of a primitive type, added once
+    fn prim_show(&mut self) -> u16 {
+        if let Some(n) = self.prim_show {
+            return n;
+        }
+        let span = Span::default();
+        let arg = Box::new(iExpr::new(iExprKind::Bound(ValPath::Local(vec![0])), span));
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure {
+            captures: vec![],
+            args: vec![Type::Generic(0)],
+            return_type: Type::String,
+            dtree,
+            branches: vec![iExpr::new(iExprKind::UnOp(UnOpcode::Show, arg), span)],
+            guards: vec![None],
+            span,
+        });
+        let n = (self.closures.len() - 1) as u16;
+        self.prim_show = Some(n);
+        n
+    }
+
+    /// The closure comparing two values with the interpreter's `<` and `=`, added once
+    fn prim_compare(&mut self) -> u16 {
+        if let Some(n) = self.prim_compare {
+            return n;
+        }
+        let span = Span::default();
+        let e = |kind| Box::new(iExpr::new(kind, span));
+        let arg = |i| e(iExprKind::Bound(ValPath::Local(vec![i])));
+        let int = |n| e(iExprKind::Literal(Literal::Int(n)));
+        // if x < y then -1 else if x = y then 0 else 1
+        let equal = iExprKind::Conditional(e(iExprKind::BinOp(arg(0), BinOpcode::Equal, arg(1))), int(0), int(1));
+        let body = iExprKind::Conditional(e(iExprKind::BinOp(arg(0), BinOpcode::Less, arg(1))), int(-1), e(equal));
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure {
+            captures: vec![],
+            args: vec![Type::Generic(0), Type::Generic(0)],
+            return_type: Type::Int,
+            dtree,
+            branches: vec![iExpr::new(body, span)],
+            guards: vec![None],
+            span,
+        });
+        let n = (self.closures.len() - 1) as u16;
+        self.prim_compare = Some(n);
+        n
+    }
+
+    /// The closure returning its argument, the coercion of a value to its own type,
+    /// added once
+    fn identity(&mut self) -> u16 {
+        if let Some(n) = self.identity {
+            return n;
+        }
+        let span = Span::default();
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure {
+            captures: vec![],
+            args: vec![Type::Generic(0)],
+            return_type: Type::Generic(0),
+            dtree,
+            branches: vec![iExpr::new(iExprKind::Bound(ValPath::Local(vec![0])), span)],
+            guards: vec![None],
+            span,
+        });
+        let n = (self.closures.len() - 1) as u16;
+        self.identity = Some(n);
+        n
+    }
+
+    /// The type written in an annotation, which can't have type variables
+    fn annotation(&mut self, t: ProtoType<'input>) -> Option<Type> {
+        match t.to_type(&self.type_map, &self.type_decls, &HashMap::new()) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                self.errors.push(e);
+                None
+            }
+        }
+    }
+
+    /// Transform e where a value of the type of var is expected. If a trait type
+    /// occurs in the type inferred so far, e may have any subtype of it and is
+    /// converted once the types of the top-level binding are solved. Otherwise the
+    /// types are unified as usual
+    fn check(&mut self, e: Expr<'input>, var: TypeVar, next: TypeVar) -> (iExpr<'input>, TypeVar) {
+        self.solve();
+        let mut t = Type::Variable(var);
+        t.substitute_vars(&self.subst);
+        if !t.has_trait() {
+            return e.transform(var, next, self);
+        }
+        let span = e.span;
+        let (e, nnext) = e.transform(next, next + 1, self);
+        let coercion = iExpr::new(self.overload(Overload::Coercion(next, Type::Variable(var), span)), span);
+        (iExpr::new(iExprKind::Application(Box::new(coercion), Box::new(e)), span), nnext)
+    }
+
+    /// Check the arithmetic of the current top-level binding is on numbers,
+    /// before its type is generalised. A type that isn't known by then is int
+    fn resolve_numeric(&mut self) {
+        for (var, span) in std::mem::take(&mut self.numeric) {
+            let mut t = Type::Variable(var);
+            t.substitute_vars(&self.subst);
+            match t {
+                Type::Int | Type::Float | Type::BigInt => (),
+                Type::Variable(_) => {
+                    self.add_constr(var, Type::Int, span);
+                    self.solve();
+                }
+                t => self.errors.push(Error::TypeMismatch(Type::Int, t, span)),
+            }
+        }
+    }
+
+    /// Resolve the coercions of the current top-level binding to the closures converting
+    /// the values, before its type is generalised. A type that isn't known by then is
+    /// the same as the one expected
+    /// # Arguments
+    /// - next: the next free variable in the binding
+    fn resolve_coercions(&mut self, mut next: TypeVar) {
+        // the closures converting sum types, which can be recursive
+        let mut sums = Vec::new();
+        for i in 0..self.overloads.len() {
+            if let Overload::Coercion(from, ref to, span) = self.overloads[i] {
+                let to = to.clone();
+                let closure = self.coercion(&Type::Variable(from), &to, span, &mut next, &mut sums);
+                self.overloads[i] = Overload::Coerced(closure.unwrap_or_else(|| self.identity()));
+            }
+        }
+        self.solve();
+    }
+
+    /// The closure converting a value of type from to its supertype to, None if it's
+    /// the same value
+    fn coercion(
+        &mut self,
+        from: &Type,
+        to: &Type,
+        span: Span,
+        next: &mut TypeVar,
+        sums: &mut Vec<(Type, Type, u16)>,
+    ) -> Option<u16> {
+        let body = self.coerce(&mut vec![0], from, to, span, next, sums)?;
+        Some(self.coercion_closure(vec![from.clone()], to.clone(), body))
+    }
+
+    /// The value at the local path of type from converted to its supertype to, None
+    /// if it's the same value. Subtyping is structural: tuples and the arguments of
+    /// declared types are covariant, functions are contravariant in their argument,
+    /// and a trait type is a supertype of the types with all of its methods, including
+    /// other trait types with more methods. Other types must be equal
+    /// # Arguments
+    /// - sums: the closures converting sum types made so far, with their types
+    fn coerce(
+        &mut self,
+        path: &mut Vec<u16>,
+        from: &Type,
+        to: &Type,
+        span: Span,
+        next: &mut TypeVar,
+        sums: &mut Vec<(Type, Type, u16)>,
+    ) -> Option<iExpr<'input>> {
+        self.solve();
+        let (mut from, mut to) = (from.clone(), to.clone());
+        from.substitute_vars(&self.subst);
+        to.substitute_vars(&self.subst);
+        let value = |path: &[u16]| iExpr::new(iExprKind::Bound(ValPath::Local(path.to_vec())), span);
+        match (&from, &to) {
+            (Type::Variable(_), _) | (_, Type::Variable(_)) => {
+                self.type_consts.push((from, to, span));
+                None
+            }
+            (Type::Trait(have), Type::Trait(want)) => {
+                let mut methods = Vec::new();
+                for (name, t) in want {
+                    let Some(i) = have.iter().position(|(method, _)| method == name) else {
+                        self.errors.push(Error::NotSubtype(from.clone(), to.clone(), name.clone(), span));
+                        return None;
+                    };
+                    self.type_consts.push((have[i].1.clone(), t.clone(), span));
+                    path.push(i as u16);
+                    methods.push(value(path));
+                    path.pop();
+                }
+                match have.len() == want.len() {
+                    true => None,
+                    false => Some(iExpr::new(iExprKind::Tuple(methods), span)),
+                }
+            }
+            (_, Type::Trait(want)) => {
+                let mut methods = Vec::new();
+                for (name, t) in want {
+                    methods.push(self.trait_method(path, &from, &to, name, t, span, next)?);
+                }
+                Some(iExpr::new(iExprKind::Tuple(methods), span))
+            }
+            (Type::Tuple(v), Type::Tuple(u)) if v.len() == u.len() => {
+                let mut values = Vec::new();
+                let mut converted = false;
+                for (i, (x, y)) in v.iter().zip(u).enumerate() {
+                    path.push(i as u16);
+                    let e = self.coerce(path, x, y, span, next, sums);
+                    converted |= e.is_some();
+                    values.push(e.unwrap_or_else(|| value(path)));
+                    path.pop();
+                }
+                converted.then(|| iExpr::new(iExprKind::Tuple(values), span))
+            }
+            (Type::Function(from_arg, from_ret), Type::Function(to_arg, to_ret)) => {
+                // a closure taking the function and its argument, applied to the function
+                let arg = self.coerce(&mut vec![1], to_arg, from_arg, span, next, sums);
+                let ret = self.coercion(from_ret, to_ret, span, next, sums);
+                if arg.is_none() && ret.is_none() {
+                    return None;
+                }
+                let arg = arg.unwrap_or_else(|| value(&[1]));
+                let mut body = iExpr::new(iExprKind::Application(Box::new(value(&[0])), Box::new(arg)), span);
+                if let Some(ret) = ret {
+                    let ret = iExpr::new(iExprKind::Closure(ret), span);
+                    body = iExpr::new(iExprKind::Application(Box::new(ret), Box::new(body)), span);
+                }
+                let args = vec![from.clone(), (**to_arg).clone()];
+                let closure = self.coercion_closure(args, (**to_ret).clone(), body);
+                let closure = iExpr::new(iExprKind::Closure(closure), span);
+                Some(iExpr::new(iExprKind::Application(Box::new(closure), Box::new(value(path))), span))
+            }
+            (&Type::Sum(n, ref v), &Type::Sum(m, ref u)) if n == m && v != u => {
+                let kind = &self.type_decls[n as usize].kind;
+                if *kind != TypeKind::Sum && *kind != TypeKind::Record {
+                    self.type_consts.push((from, to, span));
+                    return None;
+                }
+                let variants: Vec<_> = self.type_decls[n as usize]
+                    .variants
+                    .iter()
+                    .map(|(_, t)| (t.substitute_generics(v), t.substitute_generics(u)))
+                    .collect();
+                if *kind == TypeKind::Record {
+                    let mut fields = Vec::new();
+                    let mut converted = false;
+                    for (i, (x, y)) in variants.iter().enumerate() {
+                        path.push(i as u16);
+                        let e = self.coerce(path, x, y, span, next, sums);
+                        converted |= e.is_some();
+                        fields.push(e.unwrap_or_else(|| value(path)));
+                        path.pop();
+                    }
+                    return converted.then(|| iExpr::new(iExprKind::Record(n, fields), span));
+                }
+                // a closure rebuilding the value with the argument of its variant converted,
+                // shared by the recursive occurrences of the type
+                let closure = match sums.iter().find(|(x, y, _)| *x == from && *y == to) {
+                    Some(&(_, _, closure)) => closure,
+                    None => {
+                        let closure = self.coercion_closure(vec![from.clone()], to.clone(), iExpr::new(iExprKind::Error, span));
+                        sums.push((from.clone(), to.clone(), closure));
+                        let len = variants.len() as u16;
+                        let mut dtree = DTree::new();
+                        let mut branches = Vec::new();
+                        for (i, (x, y)) in variants.iter().enumerate() {
+                            let position = i as u16 + 1;
+                            let mut path = vec![0, position];
+                            let value = self.coerce(&mut path, x, y, span, next, sums).unwrap_or_else(|| value(&path));
+                            let tag = ValPath::Local(vec![0, 0]);
+                            dtree.add_pattern(BTreeMap::from([(tag, ConstraintValue::Finite(i as u16, len))]), i as u16);
+                            branches.push(iExpr::new(iExprKind::SumVal { target: n, position, value: Box::new(value) }, span));
+                        }
+                        let closure_ref = &mut self.closures[closure as usize];
+                        closure_ref.dtree = dtree;
+                        closure_ref.guards = branches.iter().map(|_| None).collect();
+                        closure_ref.branches = branches;
+                        closure
+                    }
+                };
+                let closure = iExpr::new(iExprKind::Closure(closure), span);
+                Some(iExpr::new(iExprKind::Application(Box::new(closure), Box::new(value(path))), span))
+            }
+            _ => {
+                self.type_consts.push((from, to, span));
+                None
+            }
+        }
+    }
+
+    /// The method `name` of the value at the local path of type from, a field of its
+    /// record or a method of its type, required at method_type by the trait type to.
+    /// A method is applied to the value when it's converted
+    #[allow(clippy::too_many_arguments)]
+    fn trait_method(
+        &mut self,
+        path: &[u16],
+        from: &Type,
+        to: &Type,
+        name: &Arc<str>,
+        method_type: &Type,
+        span: Span,
+        next: &mut TypeVar,
+    ) -> Option<iExpr<'input>> {
+        let value = iExpr::new(iExprKind::Bound(ValPath::Local(path.to_vec())), span);
+        let not_subtype = Error::NotSubtype(from.clone(), to.clone(), name.clone(), span);
+        let Type::Sum(n, ref args) = *from else {
+            self.errors.push(not_subtype);
+            return None;
+        };
+        let decl = &self.type_decls[n as usize];
+        let field = match decl.kind {
+            TypeKind::Record => decl.variants.iter().position(|&(field, _)| field == &**name),
+            _ => None,
+        };
+        match (field, decl.methods.get(&**name)) {
+            (Some(i), _) => {
+                let t = decl.variants[i].1.substitute_generics(args);
+                self.type_consts.push((t, method_type.clone(), span));
+                Some(iExpr::new(iExprKind::Field(Box::new(value), i as u16), span))
+            }
+            (None, Some(&order)) => {
+                let scheme = self.global_scheme(order);
+                if !scheme.constraints.is_empty() {
+                    self.errors.push(Error::Unsupported("overloaded methods of trait types", span));
+                    return None;
+                }
+                let (t, nnext) = scheme.instantiate(*next);
+                *next = nnext;
+                let applied = Type::Function(Box::new(from.clone()), Box::new(method_type.clone()));
+                self.type_consts.push((t, applied, span));
+                let method = iExpr::new(iExprKind::Bound(ValPath::StaticVal(vec![order])), span);
+                Some(iExpr::new(iExprKind::Application(Box::new(method), Box::new(value)), span))
+            }
+            (None, None) => {
+                self.errors.push(not_subtype);
+                None
+            }
+        }
+    }
+
+    /// A closure without captures converting its arguments with body
+    fn coercion_closure(&mut self, args: Vec<Type>, return_type: Type, body: iExpr<'input>) -> u16 {
+        let span = body.span;
+        let mut dtree = DTree::new();
+        dtree.add_pattern(BTreeMap::new(), 0);
+        self.closures.push(Closure { captures: vec![], args, return_type, dtree, branches: vec![body], guards: vec![None], span });
+        (self.closures.len() - 1) as u16
+    }
+
+    /// Check that a match is exhaustive and has no redundant arms or alternatives.
+    /// Redundant ones are reported right away, missing values when the types of the
+    /// binding are known
+    /// # Arguments
+    /// - arms: span of the patterns of each arm, and the spans of the alternatives of
+    ///   or-patterns taken by each of its alternatives
+    /// - roots: first element of the path and type of each matched value
+    fn check_match(
+        &mut self,
+        dtree: &DTree<'input>,
+        arms: &[(Span, Vec<Vec<Span>>)],
+        roots: Vec<(u16, Type)>,
+        span: Span,
+    ) {
+        let alternatives: Vec<_> = arms.iter().map(|(_, taken)| taken.len() as u16).collect();
+        let patterns = arms.iter().map(|&(span, _)| span).collect();
+        let mut report = MatchReport { patterns, redundant: vec![], inexhaustive: None };
+        let mut unreached = BTreeMap::new();
+        for err in dtree.is_sound_complete(&alternatives).err().unwrap_or_default() {
+            match err {
+                PatternMatchErr::Redundant(i) => report.redundant.push(arms[i as usize].0),
+                PatternMatchErr::RedundantAlternative(i, j) => {
+                    unreached.entry(i).or_insert_with(HashSet::new).insert(j);
+                }
+                PatternMatchErr::NonExhaustive(missing) => {
+                    report.inexhaustive = Some(Inexhaustive { missing, roots: roots.clone(), span })
+                }
+            }
+        }
+        // an alternative of an or-pattern is redundant if it's only taken by the
+        // unreached combinations of alternatives, report the outermost ones
+        for (i, unreached) in unreached {
+            let taken = &arms[i as usize].1;
+            let mut reached = HashSet::new();
+            let mut redundant = BTreeSet::new();
+            for (j, spans) in taken.iter().enumerate() {
+                if unreached.contains(&(j as u16)) {
+                    redundant.extend(spans.iter().copied());
+                } else {
+                    reached.extend(spans.iter().copied());
+                }
+            }
+            redundant.retain(|span| !reached.contains(span));
+            for &span in &redundant {
+                if !redundant.iter().any(|&outer| outer != span && outer.to(span) == outer) {
+                    report.redundant.push(span);
+                }
+            }
+        }
+        self.match_reports.push(report);
+    }
+
+    /// Add the alternatives of the patterns of an arm to the decision tree. The names
+    /// they bind at different paths are moved to the next local slots, which the
+    /// matched alternative fills, if movable
+    /// # Returns
+    /// the spans of the alternatives of or-patterns taken by each alternative
+    fn add_alternatives(
+        &mut self,
+        dtree: &mut DTree<'input>,
+        mut alts: Vec<Alternative<'input>>,
+        arm: u16,
+        guarded: bool,
+        movable: bool,
+        span: Span,
+    ) -> Vec<Vec<Span>> {
+        let taken = alts.iter().map(|alt| alt.taken.clone()).collect();
+        if alts.len() == 1 {
+            let alt = alts.pop().unwrap();
+            if guarded {
+                dtree.add_guarded_pattern(alt.constraints, arm);
+            } else {
+                dtree.add_pattern(alt.constraints, arm);
+            }
+            return taken;
+        }
+        let first = &alts[0].bound;
+        let mut moved: Vec<_> = first
+            .iter()
+            .filter(|&(name, path)| alts.iter().any(|alt| alt.bound.get(name).is_some_and(|p| p != path)))
+            .map(|(&name, path)| (name, path.clone()))
+            .collect();
+        if !moved.is_empty() && !movable {
+            let what = "or-patterns binding names at different positions outside of closures";
+            self.errors.push(Error::Unsupported(what, span));
+            moved.clear();
+        }
+        for &(name, _) in &moved {
+            let slot = ValPath::Local(vec![self.next_local]);
+            self.next_local += 1;
+            if let Some(entry) = self.namescope.local().get_mut(name) {
+                entry.0 = slot;
+            }
+        }
+        for (j, alt) in alts.into_iter().enumerate().rev() {
+            let bound = moved.iter().map(|(name, path)| alt.bound.get(name).unwrap_or(path).clone()).collect();
+            dtree.add_alternative(alt.constraints, arm, j as u16, bound, guarded);
+        }
+        taken
+    }
+
+    /// Solve the constraints collected so far, extending the substitution of the
+    /// current top-level binding
+    fn solve(&mut self) {
+        if let Err(errors) = unify::unify(&mut self.type_consts, &mut self.subst) {
+            self.errors.extend(errors);
+        }
+    }
+
+    /// Bind the names in the pattern of a local binding to the next local slot. Their types
+    /// are generalised over the variables that are not free in the enclosing scopes
+    /// # Arguments
+    /// - var: the type variable of the bound value
+    ///
+    /// # Returns
+    /// (decision tree checking the pattern, shadowed names to restore by unbind_local,
+    /// next free variable)
+    fn let_transform(
+        &mut self,
+        pat: Pattern<'input>,
+        var: TypeVar,
+        next: TypeVar,
+    ) -> (DTree<'input>, Shadowed<'input>, TypeVar) {
+        let slot = self.next_local;
+        let mut path = vec![slot];
+        self.next_local += 1;
+        let pat_span = pat.span;
+        let mut alts = vec![Alternative::default()];
+        // a fresh layer only to collect the bound names, they don't capture anything
+        self.namescope.push_layer();
+        let next = pat.transform(var, next, &mut path, self, ValPath::Local, &mut alts);
+        let mut dtree = DTree::new();
+        let taken = self.add_alternatives(&mut dtree, alts, 0, false, false, pat_span);
+        let bound = self.namescope.pop_layer();
+
+        self.solve();
+        // the variables of pending arithmetic aren't generalised, it's defaulted to int
+        let mut env = self.namescope.free_vars(&self.subst);
+        for &(var, _) in &self.numeric {
+            let mut t = Type::Variable(var);
+            t.substitute_vars(&self.subst);
+            t.free_vars(&mut env);
+        }
+        // nor those of pending coercions, whose types may still be unified
+        for overload in &self.overloads {
+            if let Overload::Coercion(from, ref to, _) = *overload {
+                for mut t in [Type::Variable(from), to.clone()] {
+                    t.substitute_vars(&self.subst);
+                    t.free_vars(&mut env);
+                }
+            }
+        }
+        // nor those of the uses of names that aren't generalised, until they're unified
+        for &(binding, _, var, _) in &self.monomorphic_uses {
+            for mut t in [Type::Variable(self.monomorphic[binding].2), Type::Variable(var)] {
+                t.substitute_vars(&self.subst);
+                t.free_vars(&mut env);
+            }
+        }
+        // nor those of pending uses of traits, only top-level bindings take dictionaries
+        let mut traits_env = HashSet::new();
+        for p in &self.predicates {
+            let mut t = Type::Variable(p.var);
+            t.substitute_vars(&self.subst);
+            t.free_vars(&mut traits_env);
+        }
+        let mut shadowed = Vec::new();
+        for (name, (path, scheme)) in bound {
+            let mut t = scheme.t;
+            t.substitute_vars(&self.subst);
+            let mut vars = HashSet::new();
+            t.free_vars(&mut vars);
+            let mut monomorphic: Vec<_> = vars.intersection(&traits_env).filter(|v| !env.contains(v)).collect();
+            monomorphic.sort_unstable();
+            self.monomorphic.extend(monomorphic.into_iter().map(|&v| (name, t.clone(), v)));
+            let scheme = TypeScheme::generalize(t, &env.union(&traits_env).copied().collect());
+            shadowed.push((name, self.namescope.local().insert(name, (path, scheme))));
+        }
+        self.check_match(&dtree, &[(pat_span, taken)], vec![(slot, Type::Variable(var))], pat_span);
+        (dtree, shadowed, next)
+    }
+
+    /// The type t of a use of a local name with each variable the name isn't
+    /// generalised over replaced by a fresh one, so that uses at different types
+    /// are reported together rather than as the mismatch of the last one
+    fn monomorphic_use(&mut self, name: &'input str, t: Type, next: TypeVar, span: Span) -> (Type, TypeVar) {
+        let mut vars = HashSet::new();
+        t.free_vars(&mut vars);
+        let mut used = Vec::new();
+        for (i, &(binding, _, var)) in self.monomorphic.iter().enumerate() {
+            let mut root = Type::Variable(var);
+            root.substitute_vars(&self.subst);
+            match root {
+                Type::Variable(v) if binding == name && vars.remove(&v) => used.push((i, v)),
+                _ => (),
+            }
+        }
+        let (scheme, generics) = TypeScheme::generalize_vars(t, &vars);
+        let (t, nnext) = scheme.instantiate(next);
+        for (i, v) in used {
+            let var = next + TypeVar::from(generics[&v]);
+            self.monomorphic_uses.push((i, t.clone(), var, span));
+        }
+        (t, nnext)
+    }
+
+    /// Unify the uses of the local names that aren't generalised with their
+    /// bindings, once all of the uses are known. A use at another type than the
+    /// first is reported at the use
+    fn solve_monomorphic(&mut self) {
+        for (binding, mut used, var, span) in std::mem::take(&mut self.monomorphic_uses) {
+            let (name, ref t, bound) = self.monomorphic[binding];
+            let mut t = t.clone();
+            let mut consts = vec![(Type::Variable(bound), Type::Variable(var), span)];
+            if unify::unify(&mut consts, &mut self.subst).is_err() {
+                t.substitute_vars(&self.subst);
+                used.substitute_vars(&self.subst);
+                self.errors.push(Error::NotGeneralised(name, t, used, span));
+            }
+        }
+        self.monomorphic.clear();
+    }
+
+    /// Remove the names of a local binding from scope after its body
+    fn unbind_local(&mut self, shadowed: Shadowed<'input>) {
+        self.next_local -= 1;
+        for (name, old) in shadowed {
+            match old {
+                Some(old) => self.namescope.local().insert(name, old),
+                None => self.namescope.local().remove(name),
+            };
+        }
+    }
+
+    /// Replace the declaration of a type with its definition, keeping the methods
+    /// defined before it
+    fn define_type(&mut self, mut t: TypeDecl<'input>) -> u16 {
+        let n = self.type_map[t.name];
+        t.methods = std::mem::take(&mut self.type_decls[n as usize].methods);
+        self.type_decls[n as usize] = t;
+        n
+    }
+
+    /// Bind a type name to a type taking num_generics arguments that isn't
+    /// defined yet. The definition is filled in by add_binding
+    fn declare_type(&mut self, name: &'input str, num_generics: u16) -> u16 {
+        let len = self.type_decls.len() as u16;
+        self.type_map.insert(name, len);
+        self.type_decls.push(TypeDecl {
+            name,
+            num_generics,
+            variants: vec![],
+            kind: TypeKind::Declared,
+            methods: HashMap::new(),
+        });
+        len
+    }
+
+    /// Resolve the variants of a type declaration and bind its constructors. The
+    /// type is declared first, if it wasn't already, so it can be recursive
     fn get_type_decl(&mut self,
         name: &'input str,
         vars: Vec<&'input str>,
-        variants: Vec<(&'input str, ProtoType<'input>)>,
+        variants: Vec<(&'input str, ProtoType<'input>, Span)>,
     ) -> TypeDecl<'input> {
-        let generics_map: HashMap<&'input str, u16> = vars
-            .into_iter()
-            .enumerate()
-            .map(|(i, s)| (s, i as u16))
-            .collect();
-        let len = self.type_map.len() as u16;
-        self.type_map.insert(name, len);
+        let generics_map = generics_map(vars);
+        let len = match self.type_map.get(name) {
+            Some(&n) => n,
+            None => self.declare_type(name, generics_map.len() as u16),
+        };
         TypeDecl {
             name,
             num_generics: generics_map.len() as u16,
+            kind: TypeKind::Sum,
+            methods: HashMap::new(),
             variants: variants
                 .into_iter()
                 .enumerate()
-                .map(|(i, (s, t))| {
-                    let t = match t.to_type(&self.type_map, &generics_map) {
+                .map(|(i, (s, t, span))| {
+                    let t = match t.to_type(&self.type_map, &self.type_decls, &generics_map) {
                         Ok(t) => t,
                         Err(e) => { self.errors.push(e); Type::Unit }
                     };
-                    self.namescope.local().insert(
-                        s,
-                        (
-                            ValPath::Constructor(len, (i+1) as u16),
-                            Type::Constructor {
-                                target: len,
-                                position: (i + 1) as u16,
-                            },
-                        ),
-                    );
+                    if let Some((ValPath::Constructor(..), _)) = self.namescope.local().get(s) {
+                        self.errors.push(Error::DuplicateConstructor(s, span));
+                    } else {
+                        self.namescope.local().insert(
+                            s,
+                            (
+                                ValPath::Constructor(len, (i+1) as u16),
+                                TypeScheme::mono(Type::Constructor {
+                                    target: len,
+                                    position: (i + 1) as u16,
+                                }),
+                            ),
+                        );
+                    }
                     (s, t)
                 })
                 .collect(),
         }
     }
 
+    /// Resolve the fields of a record declaration and bind their names to it. In
+    /// `e.name` where the type of e isn't known, the fields of the records declared
+    /// before with the same names are shadowed
+    fn get_record_decl(&mut self,
+        name: &'input str,
+        vars: Vec<&'input str>,
+        fields: Vec<(&'input str, ProtoType<'input>, Span)>,
+    ) -> TypeDecl<'input> {
+        let generics_map = generics_map(vars);
+        let len = match self.type_map.get(name) {
+            Some(&n) => n,
+            None => self.declare_type(name, generics_map.len() as u16),
+        };
+        let mut variants: Vec<(&'input str, Type)> = Vec::new();
+        for (s, t, span) in fields {
+            let t = match t.to_type(&self.type_map, &self.type_decls, &generics_map) {
+                Ok(t) => t,
+                Err(e) => { self.errors.push(e); Type::Unit }
+            };
+            if variants.iter().any(|&(field, _)| field == s) {
+                self.errors.push(Error::DuplicateField(s, span));
+                continue;
+            }
+            self.field_map.insert(s, (len, variants.len() as u16));
+            variants.push((s, t));
+        }
+        TypeDecl {
+            name,
+            num_generics: generics_map.len() as u16,
+            kind: TypeKind::Record,
+            methods: HashMap::new(),
+            variants,
+        }
+    }
+
+    /// Resolve the fields of a record literal, update or pattern to their indices
+    /// in the record declaring all of them, and no others if complete, as the fields
+    /// of a literal are. Without such a record, they're resolved in the one declaring
+    /// the most of them, and fields that aren't in it or are repeated are reported
+    /// and left out
+    /// # Returns
+    /// the record and the remaining fields, or the values of the fields if the first
+    /// field isn't found or more than one record has the fields
+    fn record_fields<T>(
+        &mut self,
+        fields: Vec<(&'input str, T, Span)>,
+        complete: bool,
+        span: Span,
+    ) -> Result<ResolvedFields<T>, Vec<T>> {
+        let values = |fields: Vec<(&'input str, T, Span)>| fields.into_iter().map(|(_, value, _)| value).collect();
+        let (first, _, first_span) = fields[0];
+        let Some(&(first_record, _)) = self.field_map.get(first) else {
+            self.errors.push(Error::FieldNotFound(first, first_span));
+            return Err(values(fields));
+        };
+        let mut names: Vec<_> = fields.iter().map(|&(name, _, _)| name).collect();
+        names.sort_unstable();
+        names.dedup();
+        let decls = &self.type_decls;
+        let declared = |n: u16| {
+            let variants = &decls[n as usize].variants;
+            names.iter().filter(|&&name| variants.iter().any(|&(field, _)| field == name)).count()
+        };
+        let records: Vec<_> = (0..decls.len() as u16).filter(|&n| decls[n as usize].kind == TypeKind::Record).collect();
+        let full: Vec<_> = records.iter().copied().filter(|&n| declared(n) == names.len()).collect();
+        let exact: Vec<_> = full.iter().copied().filter(|&n| decls[n as usize].variants.len() == names.len()).collect();
+        let candidates = if complete && !exact.is_empty() { exact } else { full };
+        let target = match candidates[..] {
+            [n] => n,
+            [n, m, ..] => {
+                self.errors.push(Error::AmbiguousRecord(decls[n as usize].name, decls[m as usize].name, span));
+                return Err(values(fields));
+            }
+            // the record of the first field among those declaring the most
+            [] => records.into_iter().max_by_key(|&n| (declared(n), n == first_record)).unwrap_or(first_record),
+        };
+        let decl = &self.type_decls[target as usize];
+        let mut resolved: Vec<(u16, T)> = Vec::new();
+        for (name, value, span) in fields {
+            match decl.variants.iter().position(|&(field, _)| field == name) {
+                None => self.errors.push(Error::UnknownField(decl.name, name, span)),
+                Some(i) if resolved.iter().any(|&(j, _)| j == i as u16) => {
+                    self.errors.push(Error::DuplicateField(name, span))
+                }
+                Some(i) => resolved.push((i as u16, value)),
+            }
+        }
+        Ok((target, resolved))
+    }
+
+    /// instantiate a record type with variables starting from next
+    /// ### RETURNS
+    /// the type of the record, the types of its fields and the next free variable
+    fn instantiate_record(&self, target: u16, next: TypeVar) -> (Type, Vec<Type>, TypeVar) {
+        let decl = &self.type_decls[target as usize];
+        let num_generics = TypeVar::from(decl.num_generics);
+        let t = Type::Sum(target, (0..num_generics).map(|n| Type::Variable(next + n)).collect());
+        let fields = decl.variants.iter().map(|(_, t)| t.instantiate(next).0).collect();
+        (t, fields, next + num_generics)
+    }
+
     fn fn_transform(&mut self,
-        fn_branches: Vec<(Vec<Pattern<'input>>, Expr<'input>)>,
-        var: u16,
-        next: u16,
-    ) -> (u16, u16) {
+        fn_branches: Vec<ast::Arm<'input>>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+    ) -> (u16, TypeVar) {
         // patterns per branch
         let len = fn_branches[0].0.len() as u16;
         debug_assert!(len > 0);
-        self.type_consts
-            .push((Type::Variable(var), mk_curried_type(next, len + 1)));
-        let mut nnext = next + len + 1;
+        let vars = TypeVar::from(len);
+        self.add_constr(var, mk_curried_type(next, vars + 1), span);
+        let mut nnext = next + vars + 1;
         let mut dtree = DTree::new();
         let mut branches = Vec::new();
+        let mut guards = Vec::new();
+        // let bindings in the arms are stored after the args
+        let mut arms: Vec<_> = fn_branches
+            .iter()
+            .map(|(pats, _, e)| (pats.iter().map(|p| p.span).reduce(Span::to).unwrap_or(e.span), vec![]))
+            .collect();
+        let outer_locals = std::mem::replace(&mut self.next_local, len);
         self.namescope.push_layer();
-        for (i, (pats, e)) in fn_branches.into_iter().enumerate().rev() {
+        for (i, (pats, guard, e)) in fn_branches.into_iter().enumerate().rev() {
             if pats.len() as u16 != len {
-                self.errors.push(Error::VariablePatsNum);
+                let arm_span = pats.iter().fold(e.span, |acc, p| acc.to(p.span));
+                self.errors.push(Error::VariablePatsNum(arm_span));
             }
     
             let mut path = vec![];
-            let mut val_consts = BTreeMap::new();
+            let mut alts = vec![Alternative::default()];
             for (j, pat) in pats.into_iter().enumerate() {
                 path.push(j as u16);
                 nnext = pat.transform(
-                    next + j as u16,
+                    next + j as TypeVar,
                     nnext,
                     &mut path,
                     self,
                     ValPath::Local,
-                    &mut val_consts,
+                    &mut alts,
                 );
                 path.pop();
             }
-            dtree.add_pattern(val_consts, i as u16);
-            let (e, tmp) = e.transform(next + len, nnext, self);
+            // a guarded arm falls through to the arms after it
+            arms[i].1 = self.add_alternatives(&mut dtree, alts, i as u16, guard.is_some(), true, span);
+            match guard {
+                Some(guard) => {
+                    self.add_constr(nnext, Type::Bool, guard.span);
+                    let (guard, tmp) = guard.transform(nnext, nnext + 1, self);
+                    guards.push(Some(guard));
+                    nnext = tmp;
+                }
+                None => guards.push(None),
+            }
+            let (e, tmp) = e.transform(next + vars, nnext, self);
             branches.push(e);
             nnext = tmp;
             self.namescope.drain_local();
+            self.next_local = len;
         }
+        self.next_local = outer_locals;
+        let roots = (0..len).map(|j| (j, Type::Variable(next + TypeVar::from(j)))).collect();
+        self.check_match(&dtree, &arms, roots, span);
         let map = self.namescope.pop_layer();
         let mut captures = Vec::new();
         for (_, (val, t)) in map.into_iter() {
             match val {
                 ValPath::CaptureCaptured(n, _) | ValPath::CaptureLocal(n, _) => {
-                    captures.push((n, (val, t)))
+                    captures.push((n, (val, t.t)))
                 }
                 _ => panic!("non capture value path not expected here"),
             }
         }
-        captures.sort_unstable_by(|(ord1, _), (ord2, _)| ord1.cmp(ord2));
+        captures.sort_unstable_by_key(|(ord, _)| *ord);
         let captures: Vec<(ValPath, Type)> = captures.into_iter().map(|(_, v)| v).collect();
-        let is_static = captures.is_empty();
         self.closures.push(Closure {
             captures,
             dtree,
             branches: branches.into_iter().rev().collect(),
-            args: (next..(next + len)).map(|n| Type::Variable(n)).collect(),
-            return_type: Type::Variable(next + len),
+            guards: guards.into_iter().rev().collect(),
+            args: (next..(next + vars)).map(Type::Variable).collect(),
+            return_type: Type::Variable(next + vars),
+            span,
         });
-        if is_static {}
-    
+
         ((self.closures.len() - 1) as u16, nnext)
     }
 }
 
 impl<'input> Pattern<'input> {
-    /// parse a pattern and fill local with the name bindings, and val_consts with
-    /// value bindings.
+    /// parse a pattern and fill local with the name bindings, and the alternatives
+    /// with value bindings. Names bound in or-patterns are bound to their paths in
+    /// the first alternative
     /// ### RETURNS
     /// next free variable
-    fn transform<'b, T: Fn(Vec<u16>) -> ValPath + Copy>(
+    fn transform<T: Fn(Vec<u16>) -> ValPath + Copy>(
         self,
-        var: u16,
-        next: u16,
+        var: TypeVar,
+        next: TypeVar,
         path: &mut Vec<u16>,
         ctx: &mut TypingContext<'input>,
         valpath_constructor: T,
-        val_consts: &mut BTreeMap<ValPath, ConstraintValue<'input>>,
-    ) -> u16 {
-        match self {
-            Pattern::Error(..) => panic!("Parse Error not supposed to be propagated"),
-            Pattern::Wild => next,
-            Pattern::Literal(l) => {
-                ctx.add_constr(var, l.get_type());
-                if let Literal::Unit = l {
-                    ()
-                } else {
-                    val_consts.insert(valpath_constructor(path.clone()), l.get_constraint());
+        alts: &mut Vec<Alternative<'input>>,
+    ) -> TypeVar {
+        let span = self.span;
+        match self.kind {
+            // already reported by the parser
+            PatternKind::Error => next,
+            PatternKind::Wild => next,
+            PatternKind::Literal(l @ (Literal::Float(_) | Literal::BigInt(_))) => {
+                ctx.add_constr(var, l.get_type(), span);
+                let what = if l.get_type() == Type::Float { "float literal patterns" } else { "bigint literal patterns" };
+                ctx.errors.push(Error::Unsupported(what, span));
+                next
+            }
+            PatternKind::Literal(l) => {
+                ctx.add_constr(var, l.get_type(), span);
+                if l != Literal::Unit {
+                    let constraint = l.get_constraint();
+                    for alt in alts.iter_mut() {
+                        alt.constraints.insert(valpath_constructor(path.clone()), constraint.clone());
+                    }
                 }
                 next
             }
-            Pattern::Bind(s) => match ctx.namescope.local().get(&s) {
+            PatternKind::Bind(s) => match ctx.namescope.local().get(&s) {
                 Some(_) => {
-                    ctx.errors.push(Error::MultBindPattern(s));
+                    ctx.errors.push(Error::MultBindPattern(s, span));
                     next
                 }
                 None => {
                     ctx.namescope
                         .local()
-                        .insert(s, (valpath_constructor(path.clone()), Type::Variable(var)));
+                        .insert(s, (valpath_constructor(path.clone()), TypeScheme::mono(Type::Variable(var))));
                     next
                 }
             },
-            Pattern::Tuple(v) => {
-                let len = v.len() as u16;
-                let mut nnext = next + len;
-                ctx.add_constr(var, Type::Tuple((next..nnext).map(|i| Type::Variable(i)).collect()));
+            PatternKind::Tuple(v) => {
+                let mut nnext = next + v.len() as TypeVar;
+                ctx.add_constr(var, Type::Tuple((next..nnext).map(Type::Variable).collect()), span);
                 for (i, pat) in v.into_iter().enumerate() {
-                    let i = i as u16;
-                    path.push(i);
+                    path.push(i as u16);
                     nnext =
-                        pat.transform(next + i, nnext, path, ctx, valpath_constructor, val_consts);
+                        pat.transform(next + i as TypeVar, nnext, path, ctx, valpath_constructor, alts);
                     path.pop();
                 }
                 nnext
             }
-            Pattern::SumVar(constructor, pat) => match ctx.namescope.get(&constructor) {
+            PatternKind::SumVar(constructor, pat) => match ctx.namescope.get(constructor) {
                 None => {
-                    ctx.errors.push(Error::ConstructorNotFound(constructor));
+                    ctx.errors.push(Error::ConstructorNotFound(constructor, span));
                     next
                 }
                 Some(ni) => {
-                    if let Type::Constructor { target, position } = ni.1 {
+                    if let Type::Constructor { target, position } = ni.1.t {
                         let t = &ctx.type_decls[target as usize];
                         // The value constraint for the tag
-                        val_consts.insert(
-                            valpath_constructor({
-                                let mut p = path.clone();
-                                p.push(0);
-                                p
-                            }),
+                        let tag = valpath_constructor({
+                            let mut p = path.clone();
+                            p.push(0);
+                            p
+                        });
+                        for alt in alts.iter_mut() {
                             // position starts from 1
-                            ConstraintValue::Finite(position - 1, t.variants.len() as u16),
-                        );
+                            let constraint = ConstraintValue::Finite(position - 1, t.variants.len() as u16);
+                            alt.constraints.insert(tag.clone(), constraint);
+                        }
 
                         let (from, n1) = t.variants[position as usize - 1].1.instantiate(next + 1);
+                        let num_generics = TypeVar::from(t.num_generics);
                         let (to, n2) = (
                             Type::Sum(
                                 target,
-                                (0..t.num_generics)
+                                (0..num_generics)
                                     .map(|n| Type::Variable(next + 1 + n))
                                     .collect(),
                             ),
-                            next + 1 + t.num_generics,
+                            next + 1 + num_generics,
                         );
-                        ctx.add_constr(var, to);
-                        ctx.add_constr(next, from);
+                        ctx.add_constr(var, to, span);
+                        ctx.add_constr(next, from, pat.span);
                         path.push(position);
                         debug_assert!(n2 >= n1);
                         let next =
-                            pat.transform(next, n2, path, ctx, valpath_constructor, val_consts);
+                            pat.transform(next, n2, path, ctx, valpath_constructor, alts);
                         path.pop();
                         next
                     } else {
-                        ctx.errors.push(Error::NonConstAppPattern(constructor));
+                        ctx.errors.push(Error::NonConstAppPattern(constructor, span));
                         next
                     }
                 }
             },
+            // fields left out match anything
+            PatternKind::Record(fields) => match ctx.record_fields(fields, false, span) {
+                // the names are still bound to avoid reporting every use of them
+                Err(pats) => {
+                    let mut nnext = next + pats.len() as TypeVar;
+                    for (j, pat) in pats.into_iter().enumerate() {
+                        path.push(j as u16);
+                        nnext = pat.transform(next + j as TypeVar, nnext, path, ctx, valpath_constructor, alts);
+                        path.pop();
+                    }
+                    nnext
+                }
+                Ok((target, fields)) => {
+                    let (t, field_types, next) = ctx.instantiate_record(target, next);
+                    ctx.add_constr(var, t, span);
+                    let mut nnext = next + fields.len() as TypeVar;
+                    for (j, (i, pat)) in fields.into_iter().enumerate() {
+                        let field_var = next + j as TypeVar;
+                        ctx.add_constr(field_var, field_types[i as usize].clone(), pat.span);
+                        path.push(i);
+                        nnext = pat.transform(field_var, nnext, path, ctx, valpath_constructor, alts);
+                        path.pop();
+                    }
+                    nnext
+                }
+            },
+            PatternKind::Annotated(pat, t) => {
+                if let Some(t) = ctx.annotation(t) {
+                    ctx.add_constr(var, t, span);
+                }
+                pat.transform(var, next, path, ctx, valpath_constructor, alts)
+            }
+            PatternKind::Range(lo, hi) => {
+                let (lo_type, hi_type) = (lo.get_type(), hi.get_type());
+                // bounds of the same type are only reported once if it isn't the matched type
+                ctx.add_constr(var, lo_type.clone(), span);
+                if hi_type != lo_type {
+                    ctx.add_constr(var, hi_type.clone(), span);
+                }
+                let constraint = match (lo, hi) {
+                    (Literal::Int(lo), Literal::Int(hi)) if lo <= hi => {
+                        Some(if lo == hi { ConstraintValue::Int(lo) } else { ConstraintValue::IntRange(lo, hi) })
+                    }
+                    (Literal::Char(lo), Literal::Char(hi)) if lo <= hi => {
+                        Some(if lo == hi { ConstraintValue::Char(lo) } else { ConstraintValue::CharRange(lo, hi) })
+                    }
+                    (Literal::Int(_), Literal::Int(_)) | (Literal::Char(_), Literal::Char(_)) => {
+                        ctx.errors.push(Error::EmptyRange(span));
+                        None
+                    }
+                    // bounds of different types are a type mismatch
+                    _ => {
+                        if lo_type == hi_type {
+                            ctx.errors.push(Error::Unsupported("range patterns of types other than int and char", span));
+                        }
+                        None
+                    }
+                };
+                if let Some(constraint) = constraint {
+                    for alt in alts.iter_mut() {
+                        alt.constraints.insert(valpath_constructor(path.clone()), constraint.clone());
+                    }
+                }
+                next
+            }
+            PatternKind::Prefix(prefix, rest) => {
+                ctx.add_constr(var, Type::String, span);
+                // the strings and prefixes the rest is matched against are joined to the
+                // prefix, so only the whole string is tested
+                let rest = *rest;
+                let joined = |kind| Pattern { kind, span };
+                let prefixed = |p: Pattern<'input>| Pattern { span: p.span, kind: PatternKind::Prefix(prefix.clone(), Box::new(p)) };
+                match rest.kind {
+                    PatternKind::Literal(Literal::String(s)) => {
+                        let whole = joined(PatternKind::Literal(Literal::String(format!("{}{}", prefix, s).into())));
+                        whole.transform(var, next, path, ctx, valpath_constructor, alts)
+                    }
+                    PatternKind::Prefix(s, rest) => {
+                        let whole = joined(PatternKind::Prefix(format!("{}{}", prefix, s).into(), rest));
+                        whole.transform(var, next, path, ctx, valpath_constructor, alts)
+                    }
+                    PatternKind::Or(pats) => {
+                        let whole = joined(PatternKind::Or(pats.into_iter().map(prefixed).collect()));
+                        whole.transform(var, next, path, ctx, valpath_constructor, alts)
+                    }
+                    PatternKind::Annotated(pat, t) => {
+                        if let Some(t) = ctx.annotation(t) {
+                            ctx.add_constr(next, t, rest.span);
+                            ctx.add_constr(next, Type::String, rest.span);
+                        }
+                        prefixed(*pat).transform(var, next + 1, path, ctx, valpath_constructor, alts)
+                    }
+                    PatternKind::As(name, pat) => {
+                        let bound = Pattern { kind: PatternKind::Bind(name), span: rest.span };
+                        let next = prefixed(bound).transform(var, next, path, ctx, valpath_constructor, alts);
+                        prefixed(*pat).transform(var, next, path, ctx, valpath_constructor, alts)
+                    }
+                    kind => {
+                        // the rest is at the length of the prefix in bytes
+                        let len = match u16::try_from(prefix.len()) {
+                            Ok(len) => len,
+                            Err(_) => {
+                                ctx.errors.push(Error::Unsupported("string prefixes longer than 65535 bytes", span));
+                                return next;
+                            }
+                        };
+                        for alt in alts.iter_mut() {
+                            alt.constraints.insert(valpath_constructor(path.clone()), ConstraintValue::StrPrefix(prefix.clone()));
+                        }
+                        ctx.add_constr(next, Type::String, rest.span);
+                        path.push(len);
+                        let rest = Pattern { kind, span: rest.span };
+                        let next = rest.transform(next, next + 1, path, ctx, valpath_constructor, alts);
+                        path.pop();
+                        next
+                    }
+                }
+            }
+            PatternKind::As(name, pat) => {
+                let bound = Pattern { kind: PatternKind::Bind(name), span };
+                let next = bound.transform(var, next, path, ctx, valpath_constructor, alts);
+                pat.transform(var, next, path, ctx, valpath_constructor, alts)
+            }
+            PatternKind::Or(pats) => {
+                let before: HashSet<_> = ctx.namescope.local().keys().copied().collect();
+                let base = std::mem::take(alts);
+                let mut first: Option<(BTreeMap<_, _>, Span)> = None;
+                let mut next = next;
+                for pat in pats {
+                    let pat_span = pat.span;
+                    let mut taken = base.clone();
+                    for alt in &mut taken {
+                        alt.taken.push(pat_span);
+                    }
+                    next = pat.transform(var, next, path, ctx, valpath_constructor, &mut taken);
+                    // the names this alternative bound, which the next ones bind again
+                    let names: Vec<_> = ctx.namescope.local().keys().copied().filter(|k| !before.contains(k)).collect();
+                    let names: BTreeMap<_, _> =
+                        names.into_iter().map(|name| (name, ctx.namescope.local().remove(name).unwrap())).collect();
+                    for alt in &mut taken {
+                        for (&name, (path, _)) in &names {
+                            alt.bound.entry(name).or_insert_with(|| path.clone());
+                        }
+                    }
+                    alts.extend(taken);
+                    match first {
+                        None => first = Some((names, pat_span)),
+                        Some((ref first, first_span)) => {
+                            for (&name, (_, scheme)) in &names {
+                                match first.get(name) {
+                                    Some((_, TypeScheme { t: Type::Variable(v), .. })) => {
+                                        ctx.add_constr(*v, scheme.t.clone(), pat_span)
+                                    }
+                                    Some(_) => (),
+                                    None => ctx.errors.push(Error::UnboundInAlternative(name, first_span)),
+                                }
+                            }
+                            for &name in first.keys().filter(|name| !names.contains_key(*name)) {
+                                ctx.errors.push(Error::UnboundInAlternative(name, pat_span));
+                            }
+                        }
+                    }
+                }
+                if let Some((names, _)) = first {
+                    ctx.namescope.local().extend(names);
+                }
+                next
+            }
         }
     }
 }
 
+// the subexpressions stay boxed when passed to the helpers of transform, so
+// that they aren't moved through its frame
+#[allow(clippy::boxed_local)]
 impl<'input> Expr<'input> {
-    fn transform(self, var: u16, next: u16, ctx: &mut TypingContext<'input>) -> (iExpr<'input>, u16) {
-        let sequence = |e1: Expr<'input>, e2: Expr<'input>, var1, var2, next, ctx: &mut TypingContext<'input>| {
+    /// All but the simplest kinds of expressions are handled by separate
+    /// functions, which keeps the frame of each level of the recursion small
+    /// enough for deeply nested expressions
+    fn transform(self, var: TypeVar, next: TypeVar, ctx: &mut TypingContext<'input>) -> (iExpr<'input>, TypeVar) {
+        let span = self.span;
+        let (kind, next) = match self.kind {
+            // already reported by the parser
+            ExprKind::Error => (iExprKind::Error, next),
+            ExprKind::Literal(l) => {
+                ctx.add_constr(var, l.get_type(), span);
+                (iExprKind::Literal(l), next)
+            }
+            ExprKind::Bound(s) => Expr::bound(s, var, next, span, ctx),
+            ExprKind::Slice(e1, e2, e3) => Expr::slice(e1, e2, e3, var, next, span, ctx),
+            ExprKind::BinOp(e1, op, e2) => return Expr::binop(e1, op, e2, var, next, span, ctx),
+            ExprKind::UnOp(op, e) => Expr::unop(op, e, var, next, span, ctx),
+            ExprKind::Tuple(v) => Expr::tuple(v, var, next, span, ctx),
+            ExprKind::Application(e1, e2) => Expr::application(e1, e2, var, next, span, ctx),
+            ExprKind::MethodCall(e, name) => Expr::method_call(e, name, var, next, span, ctx),
+            ExprKind::Record(fields) => Expr::record(fields, var, next, span, ctx),
+            ExprKind::Update(record, fields) => Expr::update(record, fields, var, next, span, ctx),
+            ExprKind::List(elements, tl) => Expr::list_of(elements, tl, var, next, span, ctx),
+            ExprKind::Conditional(cond, e1, e2) => Expr::conditional(cond, e1, e2, var, next, ctx),
+            ExprKind::Closure(v) => {
+                let (idx, next) = ctx.fn_transform(v, var, next, span);
+                (iExprKind::Closure(idx), next)
+            }
+            ExprKind::Let(pat, e1, e2) => Expr::let_in(pat, e1, e2, var, next, ctx),
+            ExprKind::Where(body, bindings) => {
+                return Expr::where_lets(body, bindings, span).transform(var, next, ctx)
+            }
+            ExprKind::Annotated(e, t) => return Expr::annotated(e, t, var, next, span, ctx),
+            ExprKind::Range(a, b) => return Expr::range(a, b, span).transform(var, next, ctx),
+            ExprKind::Comprehension(e, qualifiers) => {
+                return Expr::comprehension(e, qualifiers, span).transform(var, next, ctx)
+            }
+        };
+        (iExpr::new(kind, span), next)
+    }
+
+    #[inline(never)]
+    fn bound(
+        s: &'input str,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        match ctx.namescope.get(s).cloned() {
+            Some((path, scheme)) => {
+                let (kind, t, next) = if let Type::Constructor { target, position } = scheme.t {
+                    let ttype = &ctx.type_decls[target as usize];
+                    let (from, n1) = ttype.variants[position as usize - 1].1.instantiate(next);
+                    let num_generics = TypeVar::from(ttype.num_generics);
+                    let (to, n2) = (
+                        Type::Sum(
+                            target,
+                            (0..num_generics)
+                                .map(|n| Type::Variable(next + n))
+                                .collect(),
+                        ),
+                        next + num_generics,
+                    );
+                    debug_assert!(n2 >= n1);
+                    (iExprKind::Bound(path), Type::Function(Box::new(from), Box::new(to)), n2)
+                } else {
+                    ctx.use_global(path, &scheme, next, span)
+                };
+                let (t, next) = match kind {
+                    iExprKind::Bound(ValPath::Local(_) | ValPath::CaptureLocal(..) | ValPath::CaptureCaptured(..))
+                        if !ctx.monomorphic.is_empty() => ctx.monomorphic_use(s, t, next, span),
+                    _ => (t, next),
+                };
+                ctx.add_constr(var, t, span);
+                (kind, next)
+            }
+            None => {
+                ctx.errors.push(Error::NameNotFound(s, span));
+                (iExprKind::Error, next)
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn slice(
+        e1: Box<Self>,
+        e2: Box<Self>,
+        e3: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        ctx.add_constr(var, Type::String, span);
+        ctx.add_constr(next, Type::Int, span);
+        let (e1, nnext) = e1.transform(var, next+1, ctx);
+        let (e2, nnext) = e2.transform(next, nnext, ctx);
+        let (e3, nnext) = e3.transform(next, nnext, ctx);
+        (iExprKind::Slice(Box::new(e1), Box::new(e2), Box::new(e3)), nnext)
+    }
+
+    #[inline(never)]
+    fn binop(
+        e1: Box<Self>,
+        op: BinOpcode,
+        e2: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExpr<'input>, TypeVar) {
+        use self::BinOpcode::*;
+        let sequence = |e1: Box<Expr<'input>>, e2: Box<Expr<'input>>, var1, var2, next, ctx: &mut TypingContext<'input>| {
             let (e1, next) = e1.transform(var1, next, ctx);
             let (e2, next) = e2.transform(var2, next, ctx);
             (e1, e2, next)
         };
-        match self {
-            Expr::Error(..) => panic!("Parse Error not supposed to be propagated"),
-            Expr::Literal(l) => {
-                ctx.add_constr(var, l.get_type());
-                (iExpr::Literal(l), next)
+        let (e1, e2, next) = match op {
+            Index => {
+                ctx.add_constr(var, Type::Char, span);
+                ctx.add_constr(next, Type::String, e1.span);
+                ctx.add_constr(next + 1, Type::Int, e2.span);
+                sequence(e1, e2, next, next + 1, next + 2, ctx)
             }
-            Expr::Bound(s) => match ctx.namescope.get(&s) {
-                Some(ni) => {
-                    let (path, t) = &*ni;
-                    let (t, next) = if let Type::Constructor { target, position } = t {
-                        let ttype = &ctx.type_decls[*target as usize];
-                        let (from, n1) = ttype.variants[*position as usize - 1].1.instantiate(next);
-                        let (to, n2) = (
-                            Type::Sum(
-                                *target,
-                                (0..ttype.num_generics)
-                                    .map(|n| Type::Variable(next + n))
-                                    .collect(),
-                            ),
-                            next + ttype.num_generics,
-                        );
-                        debug_assert!(n2 >= n1);
-                        (Type::Function(Box::new(from), Box::new(to)), n2)
-                    } else {
-                        t.instantiate(next)
-                    };
-                    // borrow checker doesn't accept ctx.add_constr here
-                    ctx.type_consts.push((Type::Variable(var), t));
-                    (iExpr::Bound(path.clone()), next)
-                }
-                None => {
-                    ctx.errors.push(Error::NameNotFound(s));
-                    (iExpr::Error, next)
+            Add | Sub | Mul | Div | Mod => {
+                ctx.numeric.push((var, span));
+                sequence(e1, e2, var, var, next, ctx)
+            }
+            Concat => {
+                ctx.add_constr(var, Type::String, span);
+                sequence(e1, e2, var, var, next, ctx)
+            }
+            Equal | NotEq | Greater | Less | GreaterEq | LessEq => {
+                ctx.add_constr(var, Type::Bool, span);
+                let (e1, e2, nnext) = sequence(e1, e2, next, next, next + 1, ctx);
+                return (iExpr::new(ctx.comparison(e1, op, e2, next, span), span), nnext);
+            }
+            And | Or => {
+                ctx.add_constr(var, Type::Bool, span);
+                sequence(e1, e2, var, var, next, ctx)
+            }
+        };
+        (iExpr::new(iExprKind::BinOp(Box::new(e1), op, Box::new(e2)), span), next)
+    }
+
+    #[inline(never)]
+    fn unop(
+        op: UnOpcode,
+        e: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        let (e, next) = match op {
+            UnOpcode::Minus => {
+                ctx.numeric.push((var, span));
+                e.transform(var, next, ctx)
+            }
+            UnOpcode::Not => {
+                ctx.add_constr(var, Type::Bool, span);
+                e.transform(var, next, ctx)
+            }
+            UnOpcode::Show => {
+                ctx.add_constr(var, Type::String, span);
+                e.transform(next, next + 1, ctx)
+            }
+        };
+        (iExprKind::UnOp(op, Box::new(e)), next)
+    }
+
+    #[inline(never)]
+    fn tuple(
+        v: Vec<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        let mut nnext = next + v.len() as TypeVar;
+        ctx.add_constr(
+            var,
+            Type::Tuple(
+                (0..v.len())
+                    .map(|i| Type::Variable(next + i as TypeVar))
+                    .collect(),
+            ),
+            span,
+        );
+        let mut v2 = Vec::new();
+        for (i, e) in v.into_iter().enumerate() {
+            // the rhs next is not the outer next, otherwise cannot update mutable nnext
+            let (e, next) = e.transform(next + i as TypeVar, nnext, ctx);
+            v2.push(e);
+            nnext = next;
+        }
+        (iExprKind::Tuple(v2), nnext)
+    }
+
+    #[inline(never)]
+    fn application(
+        e1: Box<Self>,
+        e2: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        // TODO : if e1 is constructor ...
+        ctx.add_constr(
+            next,
+            Type::Function(
+                Box::new(Type::Variable(next + 1)),
+                Box::new(Type::Variable(var)),
+            ),
+            span,
+        );
+        let (e1, nnext) = e1.transform(next, next + 2, ctx);
+        let (e2, nnext) = ctx.check(*e2, next + 1, nnext);
+        (iExprKind::Application(Box::new(e1), Box::new(e2)), nnext)
+    }
+
+    #[inline(never)]
+    fn conditional(
+        cond: Box<Self>,
+        e1: Box<Self>,
+        e2: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        ctx.add_constr(next, Type::Bool, cond.span);
+        let (cond, next) = cond.transform(next, next + 1, ctx);
+        let (e1, next) = e1.transform(var, next, ctx);
+        let (e2, next) = e2.transform(var, next, ctx);
+        (
+            iExprKind::Conditional(Box::new(cond), Box::new(e1), Box::new(e2)),
+            next,
+        )
+    }
+
+    #[inline(never)]
+    fn let_in(
+        pat: Pattern<'input>,
+        e1: Box<Self>,
+        e2: Box<Self>,
+        var: TypeVar,
+        next: TypeVar,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        let (e1, nnext) = e1.transform(next, next + 1, ctx);
+        let (dtree, shadowed, nnext) = ctx.let_transform(pat, next, nnext);
+        let (e2, nnext) = e2.transform(var, nnext, ctx);
+        ctx.unbind_local(shadowed);
+        (iExprKind::Let(Box::new(e1), dtree, Box::new(e2)), nnext)
+    }
+
+    /// The elements are checked in a loop rather than as nested applications of
+    /// the constructors, so long lists don't recurse as deep as they are long
+    #[inline(never)]
+    fn list_of(
+        elements: Vec<Self>,
+        tl: Option<Box<Self>>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        ctx.add_constr(var, Type::Sum(LIST, vec![Type::Variable(next)]), span);
+        let mut nnext = next + 1;
+        let mut v = Vec::with_capacity(elements.len());
+        for e in elements {
+            let (e, n) = ctx.check(e, next, nnext);
+            v.push(e);
+            nnext = n;
+        }
+        let tl = tl.map(|tl| {
+            let (tl, n) = ctx.check(*tl, var, nnext);
+            nnext = n;
+            Box::new(tl)
+        });
+        (iExprKind::List(v, tl), nnext)
+    }
+
+    #[inline(never)]
+    fn method_call(
+        e: Box<Self>,
+        name: &'input str,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        let (recv, recv_span) = (next, e.span);
+        let (e, next) = e.transform(recv, next + 1, ctx);
+        match ctx.resolve_member(recv, name, span) {
+            Some(Member::Field(target, i)) => {
+                let (t, field_types, next) = ctx.instantiate_record(target, next);
+                ctx.add_constr(var, field_types[i as usize].clone(), span);
+                ctx.add_constr(recv, t, recv_span);
+                (iExprKind::Field(Box::new(e), i), next)
+            }
+            // e.name is (name e)
+            Some(Member::Method(order)) => {
+                let scheme = ctx.global_scheme(order);
+                let (method, t, next) = ctx.use_global(ValPath::StaticVal(vec![order]), &scheme, next, span);
+                let applied = Type::Function(Box::new(Type::Variable(recv)), Box::new(Type::Variable(var)));
+                ctx.type_consts.push((t, applied, span));
+                let method = iExpr::new(method, span);
+                (iExprKind::Application(Box::new(method), Box::new(e)), next)
+            }
+            Some(Member::Trait(i, t)) => {
+                ctx.add_constr(var, t, span);
+                (iExprKind::Field(Box::new(e), i), next)
+            }
+            None => (iExprKind::Error, next),
+        }
+    }
+
+    #[inline(never)]
+    fn record(
+        fields: Vec<(&'input str, Self, Span)>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        match ctx.record_fields(fields, true, span) {
+            Err(_) => (iExprKind::Error, next),
+            Ok((target, fields)) => {
+                let (t, field_types, mut next) = ctx.instantiate_record(target, next);
+                ctx.add_constr(var, t, span);
+                let mut values: Vec<_> = field_types.iter().map(|_| None).collect();
+                for (i, e) in fields {
+                    ctx.add_constr(next, field_types[i as usize].clone(), e.span);
+                    let (e, nnext) = ctx.check(e, next, next + 1);
+                    values[i as usize] = Some(e);
+                    next = nnext;
                 }
-            },
-            Expr::Slice(e1, e2, e3) => {
-                ctx.add_constr(var, Type::String);
-                ctx.add_constr(next, Type::Int);
-                let (e1, nnext) = e1.transform(var, next+1, ctx);
-                let (e2, nnext) = e2.transform(next, nnext, ctx);
-                let (e3, nnext) = e3.transform(next, nnext, ctx);
-                (iExpr::Slice(Box::new(e1), Box::new(e2), Box::new(e3)), nnext)
-            },
-            Expr::BinOp(e1, op, e2) => {
-                use self::BinOpcode::*;
-                let (e1, e2, next) = match op {
-                    Index => {
-                        ctx.add_constr(var, Type::Int);
-                        ctx.add_constr(next, Type::String);
-                        sequence(*e1, *e2, next, var, next+1, ctx)
-                    }
-                    Add | Sub | Mul | Div | Mod => {
-                        ctx.add_constr(var, Type::Int);
-                        sequence(*e1, *e2, var, var, next, ctx)
-                    }
-                    Greater | Less | GreaterEq | LessEq => {
-                        ctx.add_constr(var, Type::Bool);
-                        ctx.add_constr(next, Type::Int);
-                        sequence(*e1, *e2, next, next, next + 1, ctx)
-                    }
-                    Concat => {
-                        ctx.add_constr(var, Type::String);
-                        sequence(*e1, *e2, var, var, next, ctx)
-                    }
-                    Equal | NotEq => {
-                        ctx.add_constr(var, Type::Bool);
-                        sequence(*e1, *e2, next, next, next + 1, ctx)
-                    }
-                    And | Or => {
-                        ctx.add_constr(var, Type::Bool);
-                        sequence(*e1, *e2, var, var, next, ctx)
+                let decl = &ctx.type_decls[target as usize];
+                for (value, (field, _)) in values.iter().zip(&decl.variants) {
+                    if value.is_none() {
+                        ctx.errors.push(Error::MissingField(decl.name, field, span));
                     }
-                };
-                (iExpr::BinOp(Box::new(e1), op, Box::new(e2)), next)
-            }
-            Expr::UnOp(UnOpcode::Minus, e) => {
-                ctx.add_constr(var, Type::Int);
-                let (e, next) = e.transform(var, next, ctx);
-                (iExpr::UnOp(UnOpcode::Minus, Box::new(e)), next)
-            }
-            Expr::UnOp(UnOpcode::Not, e) => {
-                ctx.add_constr(var, Type::Bool);
-                let (e, next) = e.transform(var, next, ctx);
-                (iExpr::UnOp(UnOpcode::Not, Box::new(e)), next)
-            }
-            Expr::Tuple(v) => {
-                let mut nnext = next + v.len() as u16;
-                ctx.type_consts.push((
-                    Type::Variable(var),
-                    Type::Tuple(
-                        (0..v.len())
-                            .map(|i| Type::Variable(next + i as u16))
-                            .collect(),
-                    ),
-                ));
-                let mut v2 = Vec::new();
-                for (i, e) in v.into_iter().enumerate() {
-                    // the rhs next is not the outer next, otherwise cannot update mutable nnext
-                    let (e, next) = e.transform(next + i as u16, nnext, ctx);
-                    v2.push(e);
-                    nnext = next;
-                }
-                (iExpr::Tuple(v2), nnext)
-            }
-            Expr::Application(e1, e2) => {
-                // TODO : if e1 is constructor ...
-                if let Expr::Bound(s) = *e1 {
-                    match ctx.namescope.get(s) {
-                        Some((_, Type::Constructor { .. })) => (), // unimplemented!(),
-                        Some(_) => (), // unimplemented!(),
-                        None => (), // unimplemented!(),
-                    }
-                }
-                ctx.type_consts.push((
-                    Type::Variable(next),
-                    Type::Function(
-                        Box::new(Type::Variable(next + 1)),
-                        Box::new(Type::Variable(var)),
-                    ),
-                ));
-                let (e1, e2, next) = sequence(*e1, *e2, next, next + 1, next + 2, ctx);
-                (iExpr::Application(Box::new(e1), Box::new(e2)), next)
-            }
-            Expr::MethodCall(object, method) => panic!("Method Call"),
-            Expr::Conditional(cond, e1, e2) => {
-                ctx.add_constr(next, Type::Bool);
-                let (cond, next) = cond.transform(next, next + 1, ctx);
-                let (e1, e2, next) = sequence(*e1, *e2, var, var, next, ctx);
-                (
-                    iExpr::Conditional(Box::new(cond), Box::new(e1), Box::new(e2)),
-                    next,
-                )
+                }
+                match values.into_iter().collect() {
+                    Some(values) => (iExprKind::Record(target, values), next),
+                    None => (iExprKind::Error, next),
+                }
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn update(
+        record: Box<Self>,
+        fields: Vec<(&'input str, Self, Span)>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExprKind<'input>, TypeVar) {
+        match ctx.record_fields(fields, false, span) {
+            Err(_) => (iExprKind::Error, next),
+            Ok((target, fields)) => {
+                let (t, field_types, next) = ctx.instantiate_record(target, next);
+                ctx.add_constr(var, t, span);
+                let (record, mut next) = record.transform(var, next, ctx);
+                let mut updates = Vec::new();
+                for (i, e) in fields {
+                    ctx.add_constr(next, field_types[i as usize].clone(), e.span);
+                    let (e, nnext) = ctx.check(e, next, next + 1);
+                    updates.push((i, e));
+                    next = nnext;
+                }
+                (iExprKind::Update(Box::new(record), updates), next)
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn annotated(
+        e: Box<Self>,
+        t: ProtoType<'input>,
+        var: TypeVar,
+        next: TypeVar,
+        span: Span,
+        ctx: &mut TypingContext<'input>,
+    ) -> (iExpr<'input>, TypeVar) {
+        if let Some(t) = ctx.annotation(t) {
+            ctx.add_constr(var, t, span);
+        }
+        let (e, next) = ctx.check(*e, var, next);
+        (iExpr::new(e.kind, span), next)
+    }
+
+    /// `body where p1 = e1 ... pn = en`, the same as nesting a let for each
+    /// binding around the body
+    #[inline(never)]
+    fn where_lets(body: Box<Self>, bindings: Vec<(Pattern<'input>, Self)>, span: Span) -> Self {
+        bindings.into_iter().rev().fold(*body, |e2, (pat, e1)| Expr {
+            kind: ExprKind::Let(pat, Box::new(e1), Box::new(e2)),
+            span,
+        })
+    }
+
+    /// `[a..b]` spanning span, which is `#range a b`
+    #[inline(never)]
+    fn range(a: Box<Self>, b: Box<Self>, span: Span) -> Self {
+        let range = Expr { kind: ExprKind::Bound(RANGE), span };
+        let applied = Expr { kind: ExprKind::Application(Box::new(range), a), span };
+        Expr { kind: ExprKind::Application(Box::new(applied), b), span }
+    }
+
+    /// `[e | q1, ..., qn]` spanning span, with each qualifier wrapped around the
+    /// ones after it: a generator `p <- l` is `#flat_map l { p => rest }` and a
+    /// guard `c` is `if c then rest else []`, spanning the qualifier. The list is
+    /// checked before the qualifiers using its elements, and the pattern of a
+    /// generator must be exhaustive
+    #[inline(never)]
+    fn comprehension(e: Box<Self>, qualifiers: Vec<Qualifier<'input>>, span: Span) -> Self {
+        let single = Expr::list(vec![*e], span.start, span.end);
+        qualifiers.into_iter().rev().fold(single, |rest, qualifier| match qualifier {
+            Qualifier::Generator(p, l) => {
+                let span = p.span.to(l.span);
+                let f = Expr { kind: ExprKind::Closure(vec![(vec![p], None, rest)]), span };
+                let flat_map = Expr { kind: ExprKind::Bound(FLAT_MAP), span };
+                let applied = Expr { kind: ExprKind::Application(Box::new(flat_map), Box::new(l)), span };
+                Expr { kind: ExprKind::Application(Box::new(applied), Box::new(f)), span }
             }
-            Expr::Closure(v) => {
-                let (idx, next) = ctx.fn_transform(v, var, next);
-                (iExpr::Closure(idx), next)
+            Qualifier::Guard(cond) => {
+                let span = cond.span;
+                let empty = Expr::list(vec![], span.start, span.end);
+                Expr { kind: ExprKind::Conditional(Box::new(cond), Box::new(rest), Box::new(empty)), span }
             }
+        })
+    }
+}
+
+/// Type check a whole compilation unit
+pub fn ast2imper_ast(bindings: Vec<Binding<'_>>) -> Result<Module<'_>, Vec<Diagnostic>> {
+    let mut ctx = TypingContext::new();
+    ctx.add_bindings(bindings);
+    ctx.export()
+}
+
+impl<'input> Inexhaustive<'input> {
+    /// the matched values no arm matches as a sequence of patterns, e.g. `(Cons _) 0`
+    fn witness(self, subst: &Substitution, types: &[TypeDecl]) -> String {
+        let missing: BTreeMap<_, _> = self
+            .missing
+            .into_iter()
+            .map(|(path, constraint)| match path {
+                ValPath::Local(v) | ValPath::StaticVal(v) => (v, constraint),
+                _ => unreachable!("patterns only constrain locals and statics"),
+            })
+            .collect();
+        self.roots
+            .into_iter()
+            .map(|(root, mut t)| {
+                t.substitute_vars(subst);
+                witness_pattern(&t, &mut vec![root], &missing, types, true)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A pattern of type t matching the value at path that meets the constraints in
+/// missing. Atomic patterns are parenthesised if they apply a constructor
+fn witness_pattern(
+    t: &Type,
+    path: &mut Vec<u16>,
+    missing: &BTreeMap<Vec<u16>, ConstraintValue>,
+    types: &[TypeDecl],
+    atomic: bool,
+) -> String {
+    let constrained = missing
+        .range(path.clone()..)
+        .next()
+        .is_some_and(|(p, _)| p.starts_with(path));
+    match (t, missing.get(path)) {
+        (Type::Unit, _) => "()".to_owned(),
+        _ if !constrained => "_".to_owned(),
+        (Type::Bool, Some(&ConstraintValue::Finite(n, _))) => (n == 1).to_string(),
+        (_, Some(ConstraintValue::Int(n))) => n.to_string(),
+        (_, Some(ConstraintValue::Char(c))) => format!("{:?}", c),
+        (_, Some(ConstraintValue::Str(s))) => format!("{:?}", s),
+        (_, Some(ConstraintValue::IntRange(lo, _))) => lo.to_string(),
+        (_, Some(ConstraintValue::CharRange(lo, _))) => format!("{:?}", lo),
+        (_, Some(ConstraintValue::StrPrefix(prefix))) => {
+            path.push(prefix.len() as u16);
+            let rest = witness_pattern(t, path, missing, types, false);
+            path.pop();
+            if atomic { format!("({:?} ++ {})", prefix, rest) } else { format!("{:?} ++ {}", prefix, rest) }
+        }
+        (Type::Tuple(v), _) => {
+            let fields: Vec<_> = v
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    path.push(i as u16);
+                    let pat = witness_pattern(t, path, missing, types, false);
+                    path.pop();
+                    pat
+                })
+                .collect();
+            format!("({})", fields.join(", "))
         }
+        (Type::Sum(target, args), _) if types[*target as usize].kind == TypeKind::Record => {
+            let fields: Vec<_> = types[*target as usize]
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, (name, t))| {
+                    path.push(i as u16);
+                    let pat = witness_pattern(&t.substitute_generics(args), path, missing, types, false);
+                    path.pop();
+                    format!("{} = {}", name, pat)
+                })
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        (Type::Sum(LIST, args), _) => {
+            path.push(0);
+            let tag = missing.get(path);
+            path.pop();
+            match tag {
+                Some(&ConstraintValue::Finite(0, _)) => "[]".to_owned(),
+                Some(&ConstraintValue::Finite(_, _)) => {
+                    // the head and the tail are at the position of #cons
+                    path.extend([2, 0]);
+                    let hd = witness_pattern(&args[0], path, missing, types, true);
+                    *path.last_mut().unwrap() = 1;
+                    let tl = witness_pattern(t, path, missing, types, false);
+                    path.truncate(path.len() - 2);
+                    if atomic { format!("({} :: {})", hd, tl) } else { format!("{} :: {}", hd, tl) }
+                }
+                _ => "_".to_owned(),
+            }
+        }
+        (Type::Sum(target, args), _) => {
+            path.push(0);
+            let tag = missing.get(path);
+            path.pop();
+            match tag {
+                Some(&ConstraintValue::Finite(n, _)) => {
+                    let (name, arg) = &types[*target as usize].variants[n as usize];
+                    // the argument is at the 1-based position of the constructor
+                    path.push(n + 1);
+                    let arg = witness_pattern(&arg.substitute_generics(args), path, missing, types, true);
+                    path.pop();
+                    if atomic {
+                        format!("({} {})", name, arg)
+                    } else {
+                        format!("{} {}", name, arg)
+                    }
+                }
+                _ => "_".to_owned(),
+            }
+        }
+        _ => "_".to_owned(),
+    }
+}
+
+/// Replace the placeholders in e by their values in filled
+fn fill_placeholders<'input>(e: &mut iExpr<'input>, filled: &mut [Option<iExprKind<'input>>]) {
+    match e.kind {
+        iExprKind::Overloaded(n) => {
+            e.kind = filled[n as usize].take().expect("a placeholder is filled once");
+        }
+        iExprKind::Literal(_) | iExprKind::Bound(_) | iExprKind::Closure(_) | iExprKind::Error => (),
+        iExprKind::Tuple(ref mut v) | iExprKind::Record(_, ref mut v) => {
+            v.iter_mut().for_each(|e| fill_placeholders(e, filled))
+        }
+        iExprKind::Field(ref mut e, _) | iExprKind::UnOp(_, ref mut e) | iExprKind::SumVal { value: ref mut e, .. } => {
+            fill_placeholders(e, filled)
+        }
+        iExprKind::Update(ref mut e, ref mut v) => {
+            fill_placeholders(e, filled);
+            v.iter_mut().for_each(|(_, e)| fill_placeholders(e, filled))
+        }
+        iExprKind::List(ref mut v, ref mut tl) => {
+            v.iter_mut().for_each(|e| fill_placeholders(e, filled));
+            tl.iter_mut().for_each(|e| fill_placeholders(e, filled))
+        }
+        iExprKind::BinOp(ref mut e1, _, ref mut e2)
+        | iExprKind::Application(ref mut e1, ref mut e2)
+        | iExprKind::Let(ref mut e1, _, ref mut e2) => {
+            fill_placeholders(e1, filled);
+            fill_placeholders(e2, filled);
+        }
+        iExprKind::Slice(ref mut e1, ref mut e2, ref mut e3) | iExprKind::Conditional(ref mut e1, ref mut e2, ref mut e3) => {
+            fill_placeholders(e1, filled);
+            fill_placeholders(e2, filled);
+            fill_placeholders(e3, filled);
+        }
+    }
+}
+
+/// whether an instance's head is for the type t
+fn same_head(head: &Type, t: &Type) -> bool {
+    match (head, t) {
+        (Type::Sum(n, _), Type::Sum(m, _)) => n == m,
+        (Type::Tuple(v), Type::Tuple(w)) => v.len() == w.len(),
+        _ => head == t,
     }
 }
 
+/// whether values of t are compared by the interpreter's `=` and orderings
+/// without a dictionary, which is true of the types without closures or
+/// declared types
+fn has_primitive_eq(t: &Type) -> bool {
+    match t {
+        Type::Unit | Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String => true,
+        Type::Tuple(v) => v.iter().all(has_primitive_eq),
+        _ => false,
+    }
+}
+
+/// whether t is one of the types with built-in instances of Show and Ord
+fn is_primitive(t: &Type) -> bool {
+    matches!(t, Type::Unit | Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String)
+}
+
+/// map of the names of a type's generics to their indices
+fn generics_map(vars: Vec<&str>) -> HashMap<&str, u16> {
+    vars.into_iter()
+        .enumerate()
+        .map(|(i, s)| (s, i as u16))
+        .collect()
+}
+
 /// ### REQUIRES
 /// count > 0
-fn mk_curried_type(from: u16, count: u16) -> Type {
+fn mk_curried_type(from: TypeVar, count: TypeVar) -> Type {
     let mut t = Type::Variable(from + count - 1);
     for i in (from..(from + count - 1)).rev() {
         t = Type::Function(Box::new(Type::Variable(i)), Box::new(t));
@@ -670,21 +3428,22 @@ impl<'input> Literal<'input> {
         match self {
             Literal::Unit => panic!("trying to get constraint from unit"),
             Literal::Int(n) => ConstraintValue::Int(n),
-            Literal::Bool(true) => ConstraintValue::Finite(0, 2),
-            Literal::Bool(false) => ConstraintValue::Finite(1, 2),
+            Literal::Char(c) => ConstraintValue::Char(c),
+            Literal::Float(_) | Literal::BigInt(_) => unreachable!("float and bigint patterns are rejected"),
+            Literal::Bool(b) => ConstraintValue::Finite(b as u16, 2),
             Literal::String(s) => ConstraintValue::Str(s),
         }
     }
 }
 
 impl<'input> Closure<'input> {
-    fn substitute_types(&mut self, map: &HashMap<u16, Type>) {
+    fn substitute_types(&mut self, subst: &Substitution) {
         for (_, t) in &mut self.captures {
-            t.substitute_vars(&map);
+            t.substitute_vars(subst);
         }
         for t in &mut self.args {
-            t.substitute_vars(&map);
+            t.substitute_vars(subst);
         }
-        self.return_type.substitute_vars(&map);
+        self.return_type.substitute_vars(subst);
     }
 }
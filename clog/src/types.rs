@@ -1,14 +1,22 @@
 use std::{
+    borrow::Cow,
     cmp::max,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
+    sync::Arc,
 };
 use crate::{
+    ast::Span,
+    bigint::BigInt,
     error::Error,
 };
 
 
-/// Representation of a sum type
+/// The index of the built-in list type in the type declarations. Its
+/// constructors are `ast::NIL` and `ast::CONS`
+pub const LIST: u16 = 0;
+
+/// Representation of a declared type
 #[derive(Debug)]
 pub struct TypeDecl<'input> {
     pub name: &'input str,
@@ -16,21 +24,66 @@ pub struct TypeDecl<'input> {
     /// number of generics
     pub num_generics: u16,
 
-    #[cfg(debug_assertions)]
+    /// constructors' names and argument types, or fields' names and types of a
+    /// record. A type that is declared but not defined yet has no variants
     pub variants: Vec<(&'input str, Type)>,
 
-    #[cfg(not(debug_assertions))]
-    pub variants: Vec<Type>
+    pub kind: TypeKind,
+
+    /// methods' names and the indices of their closures in the module's globals
+    pub methods: HashMap<&'input str, u16>,
+}
+
+/// How a declared type is defined
+#[derive(PartialEq, Debug)]
+pub enum TypeKind {
+    /// declared but not defined yet
+    Declared,
+    Sum,
+    /// another name for the type, with generics substituted by the arguments
+    Alias(Type),
+    /// a single constructor that is hidden outside the type's definition
+    Abstract,
+    /// the variants are the fields
+    Record,
+}
+
+/// A trait, a set of methods over a type that its instances define
+#[derive(Debug)]
+pub struct TraitDecl<'input> {
+    pub name: &'input str,
+    /// methods' names and types, with the type of the instance as Generic(0)
+    pub methods: Vec<(&'input str, Type)>,
+    pub instances: Vec<Instance>,
+}
+
+/// An instance of a trait for a type. Its dictionary is a global tuple of the
+/// methods in the order of the trait
+#[derive(Debug)]
+pub struct Instance {
+    /// a primitive type, or a declared type applied to distinct generics
+    pub head: Type,
+    /// the traits required of the generics of the head as (trait, generic). If
+    /// any, the global is a closure taking a tuple of their dictionaries
+    pub context: Vec<(u16, u16)>,
+    /// index of the dictionary in the module's globals
+    pub global: u16,
 }
 
 #[derive(Debug)]
 pub enum ProtoType<'input> {
     Unit,
-    Int, Bool, String,
+    Int, Float, BigInt, Bool, Char, String,
     Function(Box<ProtoType<'input>>, Box<ProtoType<'input>>),
     Tuple(Vec<ProtoType<'input>>),
-    Sum(&'input str, Box<ProtoType<'input>>),
-    Generic(&'input str),
+    /// a named type applied to arguments, a tuple if more than one
+    Sum(&'input str, Box<ProtoType<'input>>, Span),
+    /// a type variable, or a named type without arguments
+    Generic(&'input str, Span),
+    /// trait{name: type, ...}, the methods' names, types and the spans of the names
+    Trait(Vec<(&'input str, ProtoType<'input>, Span)>),
+    /// [t], the built-in list of t
+    List(Box<ProtoType<'input>>),
     /// Parse error
     Error(usize, usize),
 }
@@ -38,7 +91,7 @@ pub enum ProtoType<'input> {
 #[derive(Clone, PartialEq, Eq)]
 pub enum Type {
     Unit,
-    Int, Bool, String,
+    Int, Float, BigInt, Bool, Char, String,
     Constructor {
         /// target type in a global types vector
         target: u16,
@@ -48,18 +101,21 @@ pub enum Type {
     },
     Function(Box<Type>, Box<Type>),
     Tuple(Vec<Type>),
-    /// Sum type
+    /// Sum or record type
     // a vector is used instead of a box type, because sum is frequently on a tuple type,
     // this optimizes the common case to 1 level of indirection instead of 2.
     Sum(u16, Vec<Type>),
+    /// the values that have the methods, with the names sorted. A value is a tuple
+    /// of its methods applied to it, in the same order
+    Trait(Vec<(Arc<str>, Type)>),
     Generic(u16),
-    Variable(u16),    // type variable only used for type-checking
+    Variable(TypeVar),    // type variable only used for type-checking
 }
 
 impl Type {
     pub fn pretty_format(
         &self,result: &mut String,
-        types: &Vec<TypeDecl>
+        types: &[TypeDecl]
     ){
         let call_self = |t: &Self, dst: &mut String| t.pretty_format(dst, types); 
         match *self {
@@ -67,30 +123,55 @@ impl Type {
                 *result += types[target as usize].name;
                 *result += types[target as usize].variants[position as usize - 1].0
             },
+            Type::Sum(LIST, ref v) => {
+                result.push('[');
+                v[0].pretty_format(result, types);
+                result.push(']');
+            }
             Type::Sum(n, ref v) => {
                 *result += types[n as usize].name;
-                result.push_str("(");
-                v[0].to_string_base(result, call_self);
-                for t in v.iter().skip(1) {
-                    result.push_str(", ");
-                    t.to_string_base(result, call_self);
+                if let Some((first, rest)) = v.split_first() {
+                    result.push('(');
+                    first.pretty_format(result, types);
+                    for t in rest {
+                        result.push_str(", ");
+                        t.pretty_format(result, types);
+                    }
+                    result.push(')')
                 }
-                result.push_str(")")
             },
             _ => self.to_string_base(result, call_self),
         }
     }
 
+    /// replace the variables of the types, which are only numbered, and their generics
+    /// with generics in order of appearance, so that they're printed as `a`, `b`, ...
+    pub fn name_vars(types: &mut [Type]) {
+        let mut vars = HashSet::new();
+        for t in types.iter() {
+            t.free_vars(&mut vars);
+        }
+        let fresh = vars.into_iter().max().map_or(0, |n| n + 1);
+        let mut map = HashMap::new();
+        for t in types {
+            *t = t.instantiate(fresh).0;
+            t.generalize(&mut map, &HashSet::new());
+        }
+    }
+
     fn to_string_base<F: Fn(&Self, &mut String)>(&self, dst: &mut String, f: F) {
         match *self {
             Type::Unit => dst.push_str("()"),
             Type::Int => dst.push_str("int"),
+            Type::Float => dst.push_str("float"),
+            Type::BigInt => dst.push_str("bigint"),
+            Type::Char => dst.push_str("char"),
             Type::Bool => dst.push_str("bool"),
             Type::String => dst.push_str("string"),
             Type::Constructor { target, position } => *dst += &format!("~{}::{}", target, position),
             Type::Function(ref from, ref to) => {
                 if let Type::Function (..) = **from {
-                    dst.push_str("(");
+                    dst.push('(');
                     f(from.as_ref(), dst);
                     dst.push_str(") -> ");
                 } else {
@@ -100,29 +181,103 @@ impl Type {
                 f(to.as_ref(), dst)
             }
             Type::Tuple(ref v) => {
-                dst.push_str("(");
+                dst.push('(');
                 f(&v[0], dst);
                 for t in v.iter().skip(1) {
                     dst.push_str(", ");
                     f(t, dst);
                 }
-                dst.push_str(")")
+                dst.push(')')
             }
             Type::Sum(n, ref v) => {
-                *dst += &format!("~{}(", n);
-                f(&v[0], dst);
-                for t in v.iter().skip(1) {
-                    dst.push_str(", ");
-                    f(t, dst);
+                *dst += &format!("~{}", n);
+                if let Some((first, rest)) = v.split_first() {
+                    dst.push('(');
+                    f(first, dst);
+                    for t in rest {
+                        dst.push_str(", ");
+                        f(t, dst);
+                    }
+                    dst.push(')')
                 }
-                dst.push_str(")")
             },
-            Type::Generic(n) => *dst += &format!("{}", ('a' as u16 + n) as u8 as char),
+            Type::Trait(ref methods) => {
+                dst.push_str("trait{");
+                for (i, (name, t)) in methods.iter().enumerate() {
+                    if i > 0 {
+                        dst.push_str(", ");
+                    }
+                    *dst += name;
+                    dst.push_str(": ");
+                    f(t, dst);
+                }
+                dst.push('}')
+            }
+            Type::Generic(n) => *dst += &generic_name(n),
             Type::Variable(n) => *dst += &format!("{}", n),
         }
     }
 }
 
+/// the name of the nth generic, `a` to `z`, then `a1` to `z1` and so on
+fn generic_name(n: u16) -> String {
+    let letter = char::from(b'a' + (n % 26) as u8);
+    match n / 26 {
+        0 => letter.to_string(),
+        round => format!("{}{}", letter, round),
+    }
+}
+
+/// A type variable. Variables are numbered from 0 in each top-level binding,
+/// which can need more than `u16` allows in generated code
+pub type TypeVar = u32;
+
+/// A type generalised over some of its variables. The quantified variables are
+/// replaced by `Generic(0..num_generics)`, the remaining `Variable`s are free in
+/// the environment the scheme was generalised in.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TypeScheme {
+    pub num_generics: u16,
+    pub t: Type,
+    /// the traits required of the generics as (trait, generic), e.g. `Eq a` in
+    /// `Eq a => a -> a -> bool`. A value of a scheme with constraints takes a
+    /// tuple of the dictionaries of their instances
+    pub constraints: Vec<(u16, u16)>,
+}
+
+impl TypeScheme {
+    /// a scheme that isn't polymorphic
+    pub fn mono(t: Type) -> Self {
+        TypeScheme { num_generics: 0, t, constraints: vec![] }
+    }
+
+    /// quantify the variables of t that are not free in the environment
+    pub fn generalize(t: Type, env: &HashSet<TypeVar>) -> Self {
+        Self::generalize_vars(t, env).0
+    }
+
+    /// quantify the variables of t that are not free in the environment
+    /// ### RETURNS
+    /// the scheme and the generic each quantified variable is replaced by
+    pub fn generalize_vars(mut t: Type, env: &HashSet<TypeVar>) -> (Self, HashMap<TypeVar, u16>) {
+        let mut map = HashMap::new();
+        t.generalize(&mut map, env);
+        (TypeScheme { num_generics: map.len() as u16, t, constraints: vec![] }, map)
+    }
+
+    /// substitute the quantified variables with fresh variables starting from var
+    /// ### RETURNS
+    /// instantiated type and next free variable
+    pub fn instantiate(&self, var: TypeVar) -> (Type, TypeVar) {
+        (self.t.instantiate(var).0, var + TypeVar::from(self.num_generics))
+    }
+
+    /// add the variables of the scheme that are not quantified to vars
+    pub fn free_vars(&self, vars: &mut HashSet<TypeVar>) {
+        self.t.free_vars(vars)
+    }
+}
+
 impl fmt::Debug for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> fmt::Result {
         let mut s = String::new();
@@ -136,8 +291,11 @@ impl fmt::Debug for Type {
 pub enum Literal<'input> {
     Unit,
     Int(isize),
+    Float(f64),
+    BigInt(BigInt),
     Bool(bool),
-    String(&'input str),
+    Char(char),
+    String(Cow<'input, str>),
 }
 
 impl<'input> Literal<'input> {
@@ -145,7 +303,10 @@ impl<'input> Literal<'input> {
         match *self {
             Literal::Unit      => Type::Unit,
             Literal::Int(_)    => Type::Int,
+            Literal::Float(_)  => Type::Float,
+            Literal::BigInt(_) => Type::BigInt,
             Literal::Bool(_)   => Type::Bool,
+            Literal::Char(_)   => Type::Char,
             Literal::String(_) => Type::String,
         }
     }
@@ -180,12 +341,16 @@ pub enum BinOpcode {
 pub enum UnOpcode {
     Minus,
     Not,
+    /// the string of a value of a primitive type as written in the source. It has
+    /// no syntax, it's the `show` of the built-in instances of Show
+    Show,
 }
 
 impl<'input> ProtoType<'input> {
     pub fn to_type(
         self,
         type_map: &HashMap<&'input str, u16>, // map of type names -> index in types vector
+        types: &[TypeDecl<'input>],
         generics_map: &HashMap<&'input str, u16>,
     ) -> Result<Type,Error<'input>> {
         use self::ProtoType as P;
@@ -193,39 +358,112 @@ impl<'input> ProtoType<'input> {
         match self {
             P::Unit => Ok(T::Unit),
             P::Int => Ok(T::Int),
+            P::Float => Ok(T::Float),
+            P::BigInt => Ok(T::BigInt),
+            P::Char => Ok(T::Char),
             P::Bool => Ok(T::Bool),
             P::String => Ok(T::String),
             P::Tuple(v) => Ok(T::Tuple({
                 let mut u = Vec::new();
                 for t in v.into_iter() {
-                    u.push(t.to_type(type_map, generics_map)?);
+                    u.push(t.to_type(type_map, types, generics_map)?);
                 }
                 u            })),
             P::Function(from, to) => Ok(T::Function(
-                Box::new(from.to_type(type_map, generics_map)?),
-                Box::new(to.to_type(type_map, generics_map)?),
+                Box::new(from.to_type(type_map, types, generics_map)?),
+                Box::new(to.to_type(type_map, types, generics_map)?),
             )),
-            P::Generic(name) => match generics_map.get(&name) {
+            P::Generic(name, span) => match generics_map.get(&name) {
                 Some(&n) => Ok(T::Generic(n)),
-                None => Err(Error::NameNotFound(name)),
+                None => P::apply(name, vec![], span, type_map, types, generics_map),
             },
-            P::Sum(name, t) => match type_map.get(&name) {
-                Some(&n) => if let P::Tuple(v) = *t {
-                    Ok(T::Sum(n, {
-                        let mut u = Vec::new();
-                        for t in v.into_iter() {
-                            u.push(t.to_type(type_map, generics_map)?);
-                        }
-                        u
-                    }))
-                } else {
-                    Ok(T::Sum(n, vec![t.to_type(type_map, generics_map)?]))
+            P::Sum(name, t, span) => {
+                // a tuple is a single argument of a type taking one argument
+                let args = match *t {
+                    P::Tuple(v) if type_map.get(&name).is_none_or(|&n| types[n as usize].num_generics != 1) => v,
+                    t => vec![t],
+                };
+                P::apply(name, args, span, type_map, types, generics_map)
+            }
+            P::List(t) => Ok(T::Sum(LIST, vec![t.to_type(type_map, types, generics_map)?])),
+            P::Trait(methods) => {
+                let mut u: Vec<(Arc<str>, Type)> = Vec::new();
+                for (name, t, span) in methods {
+                    if u.iter().any(|(m, _)| **m == *name) {
+                        return Err(Error::DuplicateMethod(name, span));
+                    }
+                    u.push((name.into(), t.to_type(type_map, types, generics_map)?));
                 }
-                None => panic!("should be error type not defined"),
-            },
+                u.sort_by(|(m, _), (n, _)| m.cmp(n));
+                Ok(T::Trait(u))
+            }
             P::Error(..) => panic!("Parse Error not supposed to be propagated"),
         }
     }
+
+    /// add the names self refers to, which are named types or type variables, and
+    /// their spans to names
+    pub fn type_names(&self, names: &mut Vec<(&'input str, Span)>) {
+        match self {
+            ProtoType::Generic(name, span) => names.push((name, *span)),
+            ProtoType::Sum(name, t, span) => {
+                names.push((name, *span));
+                t.type_names(names)
+            }
+            ProtoType::List(t) => t.type_names(names),
+            ProtoType::Tuple(v) => v.iter().for_each(|t| t.type_names(names)),
+            ProtoType::Trait(methods) => methods.iter().for_each(|(_, t, _)| t.type_names(names)),
+            ProtoType::Function(from, to) => {
+                from.type_names(names);
+                to.type_names(names);
+            }
+            _ => (),
+        }
+    }
+
+    /// add the names in self that aren't declared types to vars in the order they
+    /// first appear, e.g. the type variables of the head of an instance
+    pub fn type_vars(&self, type_map: &HashMap<&'input str, u16>, vars: &mut Vec<&'input str>) {
+        match self {
+            ProtoType::Generic(name, _) if !type_map.contains_key(name) && !vars.contains(name) => vars.push(name),
+            ProtoType::Sum(_, t, _) | ProtoType::List(t) => t.type_vars(type_map, vars),
+            ProtoType::Tuple(v) => v.iter().for_each(|t| t.type_vars(type_map, vars)),
+            ProtoType::Trait(methods) => methods.iter().for_each(|(_, t, _)| t.type_vars(type_map, vars)),
+            ProtoType::Function(from, to) => {
+                from.type_vars(type_map, vars);
+                to.type_vars(type_map, vars);
+            }
+            _ => (),
+        }
+    }
+
+    /// the named type applied to args, checking it's applied to as many arguments
+    /// as it's declared with. Type variables take no arguments
+    fn apply(
+        name: &'input str,
+        args: Vec<ProtoType<'input>>,
+        span: Span,
+        type_map: &HashMap<&'input str, u16>,
+        types: &[TypeDecl<'input>],
+        generics_map: &HashMap<&'input str, u16>,
+    ) -> Result<Type, Error<'input>> {
+        if generics_map.contains_key(&name) {
+            return Err(Error::TypeArity(name, 0, args.len(), span));
+        }
+        let n = *type_map.get(&name).ok_or(Error::TypeNotDefined(name, span))?;
+        let decl = &types[n as usize];
+        if args.len() != decl.num_generics as usize {
+            return Err(Error::TypeArity(name, decl.num_generics, args.len(), span));
+        }
+        let mut u = Vec::new();
+        for t in args {
+            u.push(t.to_type(type_map, types, generics_map)?);
+        }
+        match decl.kind {
+            TypeKind::Alias(ref t) => Ok(t.substitute_generics(&u)),
+            _ => Ok(Type::Sum(n, u)),
+        }
+    }
 }
 
 impl Type {
@@ -234,9 +472,9 @@ impl Type {
     /// Generic(n) => Variable(var + n)
     /// ### RETURNS
     /// instantiated type and next free variable
-    pub fn instantiate(&self, var: u16) -> (Type, u16) {
+    pub fn instantiate(&self, var: TypeVar) -> (Type, TypeVar) {
         match *self {
-            Type::Unit | Type::Int | Type::Bool | Type::String | Type::Constructor {..} | Type::Variable(_) => (self.clone(), var),
+            Type::Unit | Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String | Type::Constructor {..} | Type::Variable(_) => (self.clone(), var),
             Type::Function(ref from, ref to) => {
                 let (from, next) = from.instantiate(var);
                 let (to, nnext) = to.instantiate(var);
@@ -245,24 +483,39 @@ impl Type {
                     max(next, nnext),
                 )
             },
-            Type::Generic(n) => (Type::Variable(var + n), var + n + 1),
+            Type::Generic(n) => {
+                let n = var + TypeVar::from(n);
+                (Type::Variable(n), n + 1)
+            }
             Type::Sum(n, ref v) => {
-                let (v, next): (Vec<Type>, Vec<u16>) = v.iter().map(|t| t.instantiate(var)).unzip();
-                let next = next.into_iter().fold(var, |acc, elem| max(acc, elem));
+                let (v, next): (Vec<Type>, Vec<TypeVar>) = v.iter().map(|t| t.instantiate(var)).unzip();
+                let next = next.into_iter().fold(var, max);
                 (Type::Sum(n, v), next)
             }
             Type::Tuple(ref v) => {
-                let (v, next): (Vec<Type>, Vec<u16>) = v.iter().map(|t| t.instantiate(var)).unzip();
-                let next = next.into_iter().fold(var, |acc, elem| max(acc, elem));
+                let (v, next): (Vec<Type>, Vec<TypeVar>) = v.iter().map(|t| t.instantiate(var)).unzip();
+                let next = next.into_iter().fold(var, max);
                 (Type::Tuple(v), next)
             }
+            Type::Trait(ref methods) => {
+                let (v, next): (Vec<_>, Vec<TypeVar>) = methods
+                    .iter()
+                    .map(|(name, t)| {
+                        let (t, next) = t.instantiate(var);
+                        ((name.clone(), t), next)
+                    })
+                    .unzip();
+                let next = next.into_iter().fold(var, max);
+                (Type::Trait(v), next)
+            }
         }
     }
 
-    // convert variables to generics
-    fn generalize(&mut self, map: &mut HashMap<u16, u16>) {
+    // convert variables except those in env to generics
+    fn generalize(&mut self, map: &mut HashMap<TypeVar, u16>, env: &HashSet<TypeVar>) {
         match *self {
-            Type::Int | Type::Bool | Type::String | Type::Unit | Type::Constructor {..} => (),
+            Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String | Type::Unit | Type::Constructor {..} => (),
+            Type::Variable(n) if env.contains(&n) => (),
             Type::Variable(n) => {
                 match map.get(&n) {
                     Some(&m) => *self = Type::Generic(m),
@@ -274,19 +527,70 @@ impl Type {
                 }
             }
             Type::Function(ref mut from, ref mut to) => {
-                from.generalize(map);
-                to.generalize(map);
+                from.generalize(map, env);
+                to.generalize(map, env);
             }
             Type::Tuple(ref mut v) |  Type::Sum(_, ref mut v) => {
                 for t in v {
-                    t.generalize(map);
+                    t.generalize(map, env);
+                }
+            }
+            Type::Trait(ref mut methods) => {
+                for (_, t) in methods {
+                    t.generalize(map, env);
                 }
             }
             Type::Generic(_) => panic!("Generic not expected in generalize"), // maybe remove
         }
     }
 
-    pub fn generalize_type(&mut self) {
-        self.generalize(&mut HashMap::new())
+    /// substitute Generic(n) with args[n], e.g. in the argument type of a constructor
+    /// of a generic sum applied to args
+    pub fn substitute_generics(&self, args: &[Type]) -> Type {
+        match *self {
+            Type::Generic(n) => args[n as usize].clone(),
+            Type::Function(ref from, ref to) => Type::Function(
+                Box::new(from.substitute_generics(args)),
+                Box::new(to.substitute_generics(args)),
+            ),
+            Type::Tuple(ref v) => Type::Tuple(v.iter().map(|t| t.substitute_generics(args)).collect()),
+            Type::Sum(n, ref v) => Type::Sum(n, v.iter().map(|t| t.substitute_generics(args)).collect()),
+            Type::Trait(ref methods) => Type::Trait(
+                methods.iter().map(|(name, t)| (name.clone(), t.substitute_generics(args))).collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// add the type variables in self to vars
+    pub fn free_vars(&self, vars: &mut HashSet<TypeVar>) {
+        match *self {
+            Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String | Type::Unit | Type::Constructor {..} | Type::Generic(_) => (),
+            Type::Variable(n) => { vars.insert(n); }
+            Type::Function(ref from, ref to) => {
+                from.free_vars(vars);
+                to.free_vars(vars);
+            }
+            Type::Tuple(ref v) | Type::Sum(_, ref v) => {
+                for t in v {
+                    t.free_vars(vars);
+                }
+            }
+            Type::Trait(ref methods) => {
+                for (_, t) in methods {
+                    t.free_vars(vars);
+                }
+            }
+        }
+    }
+
+    /// whether a trait type occurs in self, where a value is checked by subtyping
+    pub fn has_trait(&self) -> bool {
+        match *self {
+            Type::Trait(_) => true,
+            Type::Function(ref from, ref to) => from.has_trait() || to.has_trait(),
+            Type::Tuple(ref v) | Type::Sum(_, ref v) => v.iter().any(Type::has_trait),
+            _ => false,
+        }
     }
 }
\ No newline at end of file
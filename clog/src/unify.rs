@@ -1,9 +1,10 @@
 //! Hindly-Milner type inference
 //! This module implements the unification algorithm for Hindly-Milner type inference
 
-use std::collections::HashMap;
+use std::rc::Rc;
 use crate::{
-    types::Type,
+    ast::Span,
+    types::{Type, TypeVar},
     error::Error
 };
 
@@ -11,11 +12,13 @@ use crate::{
 mod test {
     use self::Type::*;
     use super::*;
+    use std::collections::HashSet;
+    use crate::types::TypeScheme;
 
     #[test]
     fn test_unify_fold() {
         // see consts.txt for derivation
-        let mut consts = vec![
+        let consts = vec![
             (
                 Variable(0),
                 Function(
@@ -53,13 +56,16 @@ mod test {
             ),
             (Variable(13), Variable(0)),
         ];
-        let map = unify(&mut consts).unwrap();
+        let mut consts = consts.into_iter().map(|(l, r)| (l, r, Span::default())).collect();
+        let mut subst = Substitution::default();
+        unify(&mut consts, &mut subst).unwrap();
 
         let mut f_type = Variable(1);
-        f_type.substitute_vars(&map);
-        f_type.generalize_type();
+        f_type.substitute_vars(&subst);
+        let f_type = TypeScheme::generalize(f_type, &HashSet::new());
+        assert_eq!(f_type.num_generics, 2);
         assert_eq!(
-            f_type,
+            f_type.t,
             Function(
                 Box::new(Tuple(vec![Generic(0), Generic(1)])),
                 Box::new(Generic(1))
@@ -67,10 +73,10 @@ mod test {
         );
 
         let mut fold_type = Variable(0);
-        fold_type.substitute_vars(&map);
-        fold_type.generalize_type();
+        fold_type.substitute_vars(&subst);
+        let fold_type = TypeScheme::generalize(fold_type, &HashSet::new());
         assert_eq!(
-            fold_type,
+            fold_type.t,
             Function(
                 Box::new(Function(
                     Box::new(Tuple(vec![Generic(0), Generic(1)])),
@@ -86,70 +92,246 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_occurs_check() {
+        // x x: x = a -> b
+        let mut consts = vec![(
+            Variable(0),
+            Function(Box::new(Variable(0)), Box::new(Variable(1))),
+            Span::new(3, 6),
+        )];
+        let mut subst = Substitution::default();
+        let errors = unify(&mut consts, &mut subst).unwrap_err();
+        assert!(matches!(errors[..], [Error::InfiniteType(Variable(0), Function(..), Span { start: 3, end: 6 })]));
+        assert_eq!(subst.get(0), None);
+
+        // through a chain of variables: 0 = 1 -> int, 1 = 2, 2 = 0
+        let mut consts = vec![
+            (Variable(0), Function(Box::new(Variable(1)), Box::new(Int)), Span::default()),
+            (Variable(1), Variable(2), Span::default()),
+            (Variable(2), Variable(0), Span::new(1, 2)),
+        ];
+        let errors = unify(&mut consts, &mut Substitution::default()).unwrap_err();
+        assert!(matches!(errors[..], [Error::InfiniteType(_, _, Span { start: 1, end: 2 })]));
+    }
+
+    #[test]
+    fn test_keep_unifying() {
+        let mut consts = vec![
+            (Tuple(vec![Int, Variable(0)]), Tuple(vec![Bool, String]), Span::new(0, 1)),
+            (Variable(1), Tuple(vec![Int]), Span::new(1, 2)),
+            (Variable(1), Tuple(vec![Int, Int]), Span::new(2, 3)),
+            (Variable(0), Int, Span::new(3, 4)),
+        ];
+        let mut subst = Substitution::default();
+        let errors = unify(&mut consts, &mut subst).unwrap_err();
+        let spans: Vec<_> = errors.iter().map(|e| e.span().start).collect();
+        assert_eq!(spans, [0, 2, 3]);
+        assert_eq!(subst.get(0), Some(&String));
+        assert!(consts.is_empty());
+
+        // a failed constraint between two variables doesn't join them
+        let mut consts = vec![
+            (Variable(0), Int, Span::default()),
+            (Variable(1), Bool, Span::default()),
+            (Variable(0), Variable(1), Span::default()),
+        ];
+        let mut subst = Substitution::default();
+        assert_eq!(unify(&mut consts, &mut subst).unwrap_err().len(), 1);
+        assert_eq!((subst.get(0), subst.get(1)), (Some(&Int), Some(&Bool)));
+    }
+
+    #[test]
+    fn test_wide_vars() {
+        // variables past u16::MAX, joined in a long chain
+        let base = 70_000;
+        let mut consts: Vec<_> = (base..base + 10_000)
+            .map(|n| (Variable(n), Variable(n + 1), Span::default()))
+            .collect();
+        consts.push((Variable(base + 10_000), Tuple(vec![Int, Variable(base + 5)]), Span::default()));
+        consts.push((Variable(base + 5), Tuple(vec![Variable(1), Bool]), Span::default()));
+        let mut subst = Substitution::default();
+        let errors = unify(&mut consts, &mut subst).unwrap_err();
+        assert!(matches!(errors[..], [Error::InfiniteType(..)]));
+
+        let mut consts: Vec<_> = (base..base + 10_000)
+            .map(|n| (Variable(n + 1), Variable(n), Span::default()))
+            .collect();
+        consts.push((Variable(base), Tuple(vec![Int, Variable(1)]), Span::default()));
+        consts.push((Variable(1), Bool, Span::default()));
+        let mut subst = Substitution::default();
+        unify(&mut consts, &mut subst).unwrap();
+        let mut t = Variable(base + 10_000);
+        t.substitute_vars(&subst);
+        assert_eq!(t, Tuple(vec![Int, Bool]));
+    }
 }
 
-impl Type {
-    /// given a map from variables to types, substitute variable types in self with
-    /// the corresponding types from map. The substituted-with types can themselves
-    /// have substitutable variables so we recures
-    /// # REQUIRES
-    /// no cycles in substitutions map
-    pub fn substitute_vars(&mut self, map: &HashMap<u16, Type>) {
-        match *self {
-            Type::Int | Type::Bool | Type::String | Type::Unit | Type::Generic(_) | Type::Constructor {..} => (),
-            Type::Variable(n) => {
-                if let Some(t) = map.get(&n) {
-                    *self = t.clone();
-                    self.substitute_vars(map);
-                }
+/// A substitution of type variables, kept as a union-find forest. Variables
+/// unified with each other are in the same class, and a class is bound to at
+/// most one non-variable type. Only the root of a class stores its binding
+#[derive(Default, Debug)]
+pub struct Substitution {
+    /// parent of each variable in its class, roots are their own parents
+    parent: Vec<TypeVar>,
+    /// upper bound on the height of the tree of each root
+    rank: Vec<u8>,
+    /// the type the class of each root is bound to. Bindings only refer to
+    /// variables of other classes, which unify guarantees by the occurs check
+    bound: Vec<Option<Rc<Type>>>,
+}
+
+impl Substitution {
+    /// the type the variable is bound to, if any
+    pub fn get(&self, n: TypeVar) -> Option<&Type> {
+        self.binding(self.root(n)).map(|t| &**t)
+    }
+
+    /// binding of a root, variables past the end of the forest are unbound roots
+    fn binding(&self, root: TypeVar) -> Option<&Rc<Type>> {
+        self.bound.get(root as usize)?.as_ref()
+    }
+
+    /// the root of the class of n without compressing the path
+    fn root(&self, mut n: TypeVar) -> TypeVar {
+        while let Some(&p) = self.parent.get(n as usize) {
+            if p == n {
+                break;
             }
-            Type::Function(ref mut from, ref mut to) => {
-                from.substitute_vars(map);
-                to.substitute_vars(map);
+            n = p;
+        }
+        n
+    }
+
+    /// the root of the class of n, halving the path to it
+    fn find(&mut self, mut n: TypeVar) -> TypeVar {
+        let len = self.parent.len() as TypeVar;
+        if n >= len {
+            self.parent.extend(len..=n);
+            self.rank.resize(n as usize + 1, 0);
+            self.bound.resize(n as usize + 1, None);
+            return n;
+        }
+        loop {
+            let p = self.parent[n as usize];
+            if p == n {
+                return n;
             }
-            Type::Tuple(ref mut v) | Type::Sum(_, ref mut v) => {
-                for t in v {
-                    t.substitute_vars(map);
-                }
+            let gp = self.parent[p as usize];
+            self.parent[n as usize] = gp;
+            n = gp;
+        }
+    }
+
+    /// merge the classes of two roots, returning the new root
+    fn union(&mut self, a: TypeVar, b: TypeVar) -> TypeVar {
+        let (ra, rb) = (self.rank[a as usize], self.rank[b as usize]);
+        let (root, child) = if ra < rb { (b, a) } else { (a, b) };
+        if ra == rb {
+            self.rank[root as usize] += 1;
+        }
+        self.parent[child as usize] = root;
+        root
+    }
+
+    /// does the class of root occur in t
+    fn occurs(&self, root: TypeVar, t: &Type) -> bool {
+        match *t {
+            Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String | Type::Unit | Type::Generic(_) | Type::Constructor {..} => false,
+            Type::Variable(n) => {
+                let n = self.root(n);
+                n == root || self.binding(n).is_some_and(|t| self.occurs(root, t))
             }
+            Type::Function(ref from, ref to) => self.occurs(root, from) || self.occurs(root, to),
+            Type::Tuple(ref v) | Type::Sum(_, ref v) => v.iter().any(|t| self.occurs(root, t)),
+            Type::Trait(ref methods) => methods.iter().any(|(_, t)| self.occurs(root, t)),
         }
     }
-}
 
-/// Hindly-Milner unification
-pub fn unify(consts: &mut Vec<(Type, Type)>) -> Result<HashMap<u16, Type>, Error<'static>> {
-    let mut map = HashMap::new();
-    // FIXME: don't return immediately at error, keep unifying
-    while consts.len() > 0 {
-        let (mut tl, mut tr) = consts.pop().unwrap();
-        tl.substitute_vars(&map); 
-        tr.substitute_vars(&map);
-        if tl == tr { continue; }
-        match  (tl, tr) {
+    /// t with the substitution applied, to report it in errors
+    fn resolve(&self, t: &Type) -> Type {
+        let mut t = t.clone();
+        t.substitute_vars(self);
+        t
+    }
+
+    /// merge the classes of two roots and bind the new class to t
+    fn merge(&mut self, n: TypeVar, m: TypeVar, t: Option<Rc<Type>>) {
+        self.bound[n as usize] = None;
+        self.bound[m as usize] = None;
+        let root = self.union(n, m);
+        self.bound[root as usize] = t;
+    }
+
+    /// report an infinite type if the class of root n occurs in t
+    fn check_occurs(&self, n: TypeVar, t: &Type, span: Span, errors: &mut Vec<Error<'static>>) -> bool {
+        let occurs = self.occurs(n, t);
+        if occurs {
+            errors.push(Error::InfiniteType(Type::Variable(n), self.resolve(t), span));
+        }
+        occurs
+    }
+
+    fn unify(&mut self, t1: &Type, t2: &Type, span: Span, errors: &mut Vec<Error<'static>>) {
+        match (t1, t2) {
             (Type::Int, Type::Int)
+            | (Type::Float, Type::Float)
+            | (Type::BigInt, Type::BigInt)
+            | (Type::Char, Type::Char)
             | (Type::Bool, Type::Bool)
             | (Type::Unit, Type::Unit)
             | (Type::String, Type::String) => (),
-            (Type::Variable(n), Type::Variable(m)) if n == m => (),
-            (Type::Variable(n), l) | (l, Type::Variable(n)) => { 
-                map.insert(n, l); 
+            (&Type::Variable(n), &Type::Variable(m)) => {
+                let (n, m) = (self.find(n), self.find(m));
+                if n == m {
+                    return;
+                }
+                match (self.bound[n as usize].clone(), self.bound[m as usize].clone()) {
+                    (None, None) => self.merge(n, m, None),
+                    (Some(t), None) if !self.check_occurs(m, &t, span, errors) => self.merge(n, m, Some(t)),
+                    (None, Some(t)) if !self.check_occurs(n, &t, span, errors) => self.merge(n, m, Some(t)),
+                    (Some(t), Some(u)) => {
+                        let num_errors = errors.len();
+                        self.unify(&t, &u, span, errors);
+                        // the classes may have been merged while unifying their types.
+                        // on a mismatch they are kept apart, like a skipped constraint
+                        let (n, m) = (self.find(n), self.find(m));
+                        if n != m && errors.len() == num_errors && !self.occurs(n, &t) && !self.occurs(m, &t) {
+                            self.merge(n, m, Some(t));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            (&Type::Variable(n), t) | (t, &Type::Variable(n)) => {
+                let n = self.find(n);
+                match self.bound[n as usize].clone() {
+                    Some(bound) => self.unify(&bound, t, span, errors),
+                    None if !self.check_occurs(n, t, span, errors) => {
+                        self.bound[n as usize] = Some(Rc::new(t.clone()))
+                    }
+                    None => (),
+                }
             }
             (Type::Function(from1, to1), Type::Function(from2, to2)) => {
-                consts.push((*from1, *from2));
-                consts.push((*to1, *to2));
+                self.unify(from1, from2, span, errors);
+                self.unify(to1, to2, span, errors);
             }
-            (Type::Tuple(v), Type::Tuple(u)) => {
-                for (x, y) in v.into_iter().zip(u.into_iter()) {
-                    consts.push((x, y));
+            (Type::Tuple(v), Type::Tuple(u)) if v.len() == u.len() => {
+                for (x, y) in v.iter().zip(u) {
+                    self.unify(x, y, span, errors);
                 }
             }
-            (Type::Sum(n, v), Type::Sum(m, u)) => {
-                if n == m {
-                    for (x, y) in v.into_iter().zip(u.into_iter()) {
-                        consts.push((x, y));
-                    }                
-                } else {
-                    return Err(Error::TypeMismatch(Type::Sum(n, v), Type::Sum(m, u)));
+            (Type::Sum(n, v), Type::Sum(m, u)) if n == m => {
+                for (x, y) in v.iter().zip(u) {
+                    self.unify(x, y, span, errors);
+                }
+            }
+            // the same trait type has the same names, only subtyping relates different ones
+            (Type::Trait(v), Type::Trait(u)) if v.len() == u.len() && v.iter().zip(u).all(|(x, y)| x.0 == y.0) => {
+                for ((_, x), (_, y)) in v.iter().zip(u) {
+                    self.unify(x, y, span, errors);
                 }
             }
             // generics are always instantiated to variables before unification
@@ -157,10 +339,61 @@ pub fn unify(consts: &mut Vec<(Type, Type)>) -> Result<HashMap<u16, Type>, Error
                 panic!("Generic not expected in unification")
             }
             (Type::Constructor { .. }, _) | (_, Type::Constructor { .. }) => {
-                return Err(Error::ConstructorUnification)
+                errors.push(Error::ConstructorUnification(span))
             }
-            (t1, t2) => return Err(Error::TypeMismatch(t1, t2)),
+            (t1, t2) => errors.push(Error::TypeMismatch(self.resolve(t1), self.resolve(t2), span)),
         }
     }
-    Ok(map)
+}
+
+impl Type {
+    /// substitute the variables in self with the types they are bound to in subst,
+    /// or with the representative of their class if they are unbound. The bound
+    /// types can themselves have substitutable variables so we recurse
+    pub fn substitute_vars(&mut self, subst: &Substitution) {
+        match *self {
+            Type::Int | Type::Float | Type::BigInt | Type::Bool | Type::Char | Type::String | Type::Unit | Type::Generic(_) | Type::Constructor {..} => (),
+            Type::Variable(n) => {
+                let root = subst.root(n);
+                match subst.binding(root) {
+                    Some(t) => {
+                        *self = Type::clone(t);
+                        self.substitute_vars(subst);
+                    }
+                    None => *self = Type::Variable(root),
+                }
+            }
+            Type::Function(ref mut from, ref mut to) => {
+                from.substitute_vars(subst);
+                to.substitute_vars(subst);
+            }
+            Type::Tuple(ref mut v) | Type::Sum(_, ref mut v) => {
+                for t in v {
+                    t.substitute_vars(subst);
+                }
+            }
+            Type::Trait(ref mut methods) => {
+                for (_, t) in methods {
+                    t.substitute_vars(subst);
+                }
+            }
+        }
+    }
+}
+
+/// Hindly-Milner unification
+/// each constraint carries the span of the source that required it. The solution
+/// extends subst, so constraints can be solved incrementally, e.g. before generalising
+/// a local binding. A constraint that can't be satisfied is reported and skipped, so
+/// all the constraints are consumed and every independent error is found
+pub fn unify(consts: &mut Vec<(Type, Type, Span)>, subst: &mut Substitution) -> Result<(), Vec<Error<'static>>> {
+    let mut errors = Vec::new();
+    for (tl, tr, span) in consts.drain(..) {
+        subst.unify(&tl, &tr, span, &mut errors);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
\ No newline at end of file